@@ -0,0 +1,1051 @@
+// binary.rs
+// Created: 10/17/2026
+// Description: Owned ELF object model. Parses the file header, section
+//              headers and program headers of a binary once and exposes
+//              them to library consumers and the chisel CLI.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::slice;
+
+use crate::elf;
+use crate::error::ChiselError;
+use crate::util;
+
+
+#[derive(Debug)]
+pub struct Elf {
+    bytes: Vec<u8>,
+    file_header: elf::FileHeader,
+    section_headers: Vec<elf::SectionHeader>,
+    program_headers: Vec<elf::ProgramHeader>,
+}
+
+
+// A run of executable bytes, either an executable section or, for
+// binaries without section headers, an executable PT_LOAD segment
+#[derive(Debug, Clone)]
+pub struct CodeRegion<'a> {
+    pub name: String,
+    pub vaddr: u64,
+    pub offset: u64,
+    pub data: &'a [u8]
+}
+
+
+impl<'a> CodeRegion<'a> {
+    // Virtual address just past the region. Segments placed at the top of
+    // the address space are cut short rather than wrapping around.
+    pub fn end(&self) -> u64 {
+        self.vaddr.saturating_add(self.data.len() as u64)
+    }
+
+
+    // File offset just past the region
+    pub fn end_offset(&self) -> u64 {
+        self.offset.saturating_add(self.data.len() as u64)
+    }
+
+
+    // Part of the region between two virtual addresses, if they overlap
+    pub fn clip(&self, start: u64, end: u64) -> Option<CodeRegion<'a>> {
+        let start: u64 = start.max(self.vaddr);
+        let end: u64 = end.min(self.end());
+
+        if start >= end {
+            return None;
+        }
+
+        let first: usize = (start - self.vaddr) as usize;
+        let last: usize = (end - self.vaddr) as usize;
+
+        Some(CodeRegion {
+            name: self.name.clone(),
+            vaddr: start,
+            offset: self.offset + first as u64,
+            data: &self.data[first..last]
+        })
+    }
+
+
+    // Part of the region between two file offsets, if they overlap
+    pub fn clip_offsets(&self, start: u64, end: u64) -> Option<CodeRegion<'a>> {
+        let start: u64 = start.max(self.offset);
+        let end: u64 = end.min(self.end_offset());
+
+        if start >= end {
+            return None;
+        }
+
+        self.clip(self.vaddr.saturating_add(start - self.offset), self.vaddr.saturating_add(end - self.offset))
+    }
+}
+
+
+impl Elf {
+
+    // Check a buffer for the ELF magic number
+    pub fn is_elf(bytes: &[u8]) -> bool {
+        bytes.len() >= elf::MAGIC_NUMBER.len() && &bytes[0..4] == elf::MAGIC_NUMBER
+    }
+
+
+    // Read a binary from disk and parse it
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Elf, ChiselError> {
+        let bytes: Vec<u8> = fs::read(path)?;
+        Elf::parse(bytes)
+    }
+
+
+    // Take ownership of a binary image and parse its headers
+    pub fn parse(bytes: Vec<u8>) -> Result<Elf, ChiselError> {
+
+        if !Elf::is_elf(&bytes) {
+            return Err(ChiselError::BadMagic);
+        }
+
+        // Build the File Header data structure
+        let file_header: elf::FileHeader = util::build_file_header(&bytes)?;
+
+        let section_headers: Vec<elf::SectionHeader> = parse_section_headers(&bytes, &file_header)?;
+        let program_headers: Vec<elf::ProgramHeader> = parse_program_headers(&bytes, &file_header)?;
+
+        Ok(Elf {
+            bytes,
+            file_header,
+            section_headers,
+            program_headers,
+        })
+    }
+
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+
+    pub fn file_header(&self) -> &elf::FileHeader {
+        &self.file_header
+    }
+
+
+    // Decoder bitness for the instruction set described by the ELF class
+    pub fn bitness(&self) -> u32 {
+        match self.file_header.arch {
+            elf::ArchitectureType::X86 => 32,
+            _ => 64
+        }
+    }
+
+
+    pub fn sections(&self) -> slice::Iter<'_, elf::SectionHeader> {
+        self.section_headers.iter()
+    }
+
+
+    pub fn segments(&self) -> slice::Iter<'_, elf::ProgramHeader> {
+        self.program_headers.iter()
+    }
+
+
+    // Look up a section header by its .shstrtab name, e.g. ".text"
+    pub fn section_by_name(&self, name: &str) -> Option<&elf::SectionHeader> {
+        self.section_headers.iter().find(|section| section.name == name)
+    }
+
+
+    // Look up a section header by its index in the section header table
+    pub fn section_by_index(&self, index: u32) -> Result<&elf::SectionHeader, ChiselError> {
+        self.section_headers.get(index as usize).ok_or(ChiselError::BadIndex {
+            structure: "section header".to_string(),
+            index: index as u64,
+            count: self.section_headers.len() as u64
+        })
+    }
+
+
+    // Find the first section of a given SHT_* type
+    pub fn section_by_type(&self, section_type: u32) -> Option<&elf::SectionHeader> {
+        self.section_headers.iter().find(|section| section.section_type == section_type)
+    }
+
+
+    // Find the first program segment of a given PT_* type
+    pub fn segment_by_type(&self, program_type: u32) -> Option<&elf::ProgramHeader> {
+        self.program_headers.iter().find(|segment| segment.program_type == program_type)
+    }
+
+
+    // Raw file contents backing a section. SHT_NOBITS sections occupy
+    // no space in the file and yield an empty slice.
+    pub fn section_data(&self, section: &elf::SectionHeader) -> Result<&[u8], ChiselError> {
+        if section.section_type == elf::SHT_NOBITS {
+            return Ok(&[]);
+        }
+
+        let structure: String = format!("section [{}] {}", section.id, section.name);
+        util::check_bounds(&self.bytes, section.offset, section.size, &structure)?;
+
+        let start: usize = section.offset as usize;
+        let end: usize = start + section.size as usize;
+        Ok(&self.bytes[start..end])
+    }
+
+
+    // Parse every entry of a SHT_SYMTAB or SHT_DYNSYM section, resolving
+    // symbol names through the string table linked by sh_link
+    pub fn symbol_table(&self, section: &elf::SectionHeader) -> Result<Vec<elf::Symbol>, ChiselError> {
+        let mut symbols: Vec<elf::Symbol> = Vec::new();
+
+        // Cast the is_x86_64 bool to an array offset
+        // 0 : x86
+        // 1 : x64
+        let arch: usize = self.file_header.is_x86_64.into();
+
+        // Fall back on the native entry size when sh_entsize is not filled in
+        let entsize: u64 = match section.entsize {
+            0 => elf::ST_ENTRY_SIZE[arch] as u64,
+            entsize => entsize
+        };
+
+        // Validate the table bounds, then pull the linked string table
+        self.section_data(section)?;
+        let strtab_section: &elf::SectionHeader = self.section_by_index(section.link)?;
+        let strtab_data: &[u8] = self.section_data(strtab_section)?;
+
+        for i in 0..(section.size / entsize) {
+            let mut symbol: elf::Symbol = util::build_symbol(
+                &self.bytes,
+                (section.offset + i * entsize) as usize,
+                i as u32,
+                self.file_header.is_x86_64,
+                self.file_header.endian
+            )?;
+
+            symbol.name = util::parse_string(strtab_data, symbol.name_idx as usize, &strtab_section.name)?;
+            symbols.push(symbol);
+        }
+
+        Ok(symbols)
+    }
+
+
+    // Symbols from the static .symtab table. Stripped binaries have none.
+    pub fn symbols(&self) -> Result<Vec<elf::Symbol>, ChiselError> {
+        match self.section_by_type(elf::SHT_SYMTAB) {
+            Some(section) => self.symbol_table(section),
+            None => Ok(Vec::new())
+        }
+    }
+
+
+    // Symbols from the dynamic .dynsym table used by the runtime linker
+    pub fn dynamic_symbols(&self) -> Result<Vec<elf::Symbol>, ChiselError> {
+        match self.section_by_type(elf::SHT_DYNSYM) {
+            Some(section) => self.symbol_table(section),
+            None => Ok(Vec::new())
+        }
+    }
+
+
+    // Parse every entry of a SHT_RELA or SHT_REL section, resolving the
+    // referenced symbols through the symbol table linked by sh_link
+    pub fn relocation_table(&self, section: &elf::SectionHeader) -> Result<Vec<elf::Relocation>, ChiselError> {
+        let mut relocations: Vec<elf::Relocation> = Vec::new();
+
+        // Cast the is_x86_64 bool to an array offset
+        // 0 : x86
+        // 1 : x64
+        let arch: usize = self.file_header.is_x86_64.into();
+        let has_addend: bool = section.section_type == elf::SHT_RELA;
+
+        // Fall back on the native entry size when sh_entsize is not filled in
+        let entsize: u64 = match (section.entsize, has_addend) {
+            (0, true) => elf::RELA_ENTRY_SIZE[arch] as u64,
+            (0, false) => elf::REL_ENTRY_SIZE[arch] as u64,
+            (entsize, _) => entsize
+        };
+
+        // Validate the table bounds, then pull the linked symbol table.
+        // A link of SHN_UNDEF means the relocations reference no symbols.
+        self.section_data(section)?;
+        let symbols: Vec<elf::Symbol> = match section.link {
+            0 => Vec::new(),
+            link => self.symbol_table(self.section_by_index(link)?)?
+        };
+
+        for i in 0..(section.size / entsize) {
+            let mut relocation: elf::Relocation = util::build_relocation(
+                &self.bytes,
+                (section.offset + i * entsize) as usize,
+                i as u32,
+                has_addend,
+                self.file_header.is_x86_64,
+                self.file_header.endian
+            )?;
+
+            if relocation.sym_idx != 0 {
+                let symbol: &elf::Symbol = symbols.get(relocation.sym_idx as usize).ok_or(ChiselError::BadIndex {
+                    structure: format!("{} relocation [{}] symbol", section.name, i),
+                    index: relocation.sym_idx as u64,
+                    count: symbols.len() as u64
+                })?;
+
+                let mut symbol: elf::Symbol = symbol.clone();
+
+                // Section symbols are unnamed, so report them by their section
+                if symbol.sym_type == elf::STT_SECTION && symbol.name.is_empty() {
+                    if let Some(target) = self.section_headers.get(symbol.shndx as usize) {
+                        symbol.name = target.name.clone();
+                    }
+                }
+
+                relocation.symbol = Some(symbol);
+            }
+
+            relocations.push(relocation);
+        }
+
+        Ok(relocations)
+    }
+
+
+    // Parse every note held in a region of the file. Descriptors are
+    // aligned to the alignment of the containing section or segment.
+    pub fn note_table(&self, offset: u64, size: u64, align: u64) -> Result<Vec<elf::Note>, ChiselError> {
+        let mut notes: Vec<elf::Note> = Vec::new();
+        let align: u64 = if align == 8 { 8 } else { 4 };
+
+        util::check_bounds(&self.bytes, offset, size, "note region")?;
+        let region: &[u8] = &self.bytes[offset as usize..(offset + size) as usize];
+        let mut note_offset: u64 = 0;
+
+        while note_offset + elf::NOTE_HEADER_SIZE as u64 <= size {
+            let (note, note_size): (elf::Note, u64) = util::build_note(
+                region,
+                note_offset as usize,
+                align,
+                self.file_header.is_x86_64,
+                self.file_header.endian
+            )?;
+
+            notes.push(note);
+            note_offset += note_size;
+        }
+
+        Ok(notes)
+    }
+
+
+    // Parse the notes of every SHT_NOTE section, or of every PT_NOTE segment
+    // when the binary has no section headers. Each group of notes is paired
+    // with the name of the section or segment it was found in.
+    pub fn notes(&self) -> Result<Vec<(String, Vec<elf::Note>)>, ChiselError> {
+        let mut groups: Vec<(String, Vec<elf::Note>)> = Vec::new();
+
+        if !self.section_headers.is_empty() {
+            for section in self.sections().filter(|section| section.section_type == elf::SHT_NOTE) {
+                let notes: Vec<elf::Note> = self.note_table(section.offset, section.size, section.addralign)?;
+                groups.push((section.name.clone(), notes));
+            }
+        } else {
+            for segment in self.segments().filter(|segment| segment.program_type == elf::PT_NOTE) {
+                let notes: Vec<elf::Note> = self.note_table(segment.offset, segment.filesz, segment.align)?;
+                groups.push((format!("PT_NOTE [{}]", segment.id), notes));
+            }
+        }
+
+        Ok(groups)
+    }
+
+
+    // Hex string of the NT_GNU_BUILD_ID note, if the binary carries one
+    pub fn build_id(&self) -> Result<Option<String>, ChiselError> {
+        for (_, notes) in self.notes()? {
+            for note in notes {
+                if let elf::NoteData::BuildId(id) = note.data {
+                    return Ok(Some(id));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+
+    // Parse the entries of the dynamic section up to the terminating
+    // DT_NULL, resolving string-valued tags through the dynamic string table
+    pub fn dynamic_entries(&self) -> Result<Vec<elf::DynamicEntry>, ChiselError> {
+        let mut entries: Vec<elf::DynamicEntry> = Vec::new();
+
+        // Cast the is_x86_64 bool to an array offset
+        // 0 : x86
+        // 1 : x64
+        let arch: usize = self.file_header.is_x86_64.into();
+        let entsize: u64 = elf::DYN_ENTRY_SIZE[arch] as u64;
+
+        // Prefer the .dynamic section, but fall back on the PT_DYNAMIC
+        // segment for binaries whose section headers have been stripped
+        let dynamic_section: Option<&elf::SectionHeader> = self.section_by_type(elf::SHT_DYNAMIC);
+        let (dynamic_offset, dynamic_size): (u64, u64) = match dynamic_section {
+            Some(section) => {
+                self.section_data(section)?;
+                (section.offset, section.size)
+            },
+            None => match self.segment_by_type(elf::PT_DYNAMIC) {
+                Some(segment) => {
+                    self.segment_data(segment)?;
+                    (segment.offset, segment.filesz)
+                },
+                None => return Ok(entries)
+            }
+        };
+
+        for i in 0..(dynamic_size / entsize) {
+            let entry: elf::DynamicEntry = util::build_dynamic_entry(
+                &self.bytes,
+                (dynamic_offset + i * entsize) as usize,
+                i as u32,
+                self.file_header.is_x86_64,
+                self.file_header.endian
+            )?;
+
+            if entry.tag == elf::DT_NULL {
+                break;
+            }
+
+            entries.push(entry);
+        }
+
+        // Locate the dynamic string table, either through the section link
+        // or through the DT_STRTAB address and DT_STRSZ size
+        let dynstr_data: Option<&[u8]> = match dynamic_section {
+            Some(section) => Some(self.section_data(self.section_by_index(section.link)?)?),
+            None => {
+                let strtab: Option<u64> = entries.iter().find(|entry| entry.tag == elf::DT_STRTAB).map(|entry| entry.value);
+                let strsz: Option<u64> = entries.iter().find(|entry| entry.tag == elf::DT_STRSZ).map(|entry| entry.value);
+
+                match (strtab.and_then(|addr| self.vaddr_to_offset(addr)), strsz) {
+                    (Some(offset), Some(size)) => {
+                        util::check_bounds(&self.bytes, offset, size, ".dynstr")?;
+                        Some(&self.bytes[offset as usize..(offset + size) as usize])
+                    },
+                    _ => None
+                }
+            }
+        };
+
+        if let Some(dynstr_data) = dynstr_data {
+            for entry in entries.iter_mut() {
+                if matches!(entry.tag, elf::DT_NEEDED | elf::DT_SONAME | elf::DT_RPATH | elf::DT_RUNPATH) {
+                    entry.string = Some(util::parse_string(dynstr_data, entry.value as usize, ".dynstr")?);
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+
+    // Names of the shared libraries listed as DT_NEEDED dependencies
+    pub fn needed_libraries(&self) -> Result<Vec<String>, ChiselError> {
+        Ok(self.dynamic_entries()?
+            .into_iter()
+            .filter(|entry| entry.tag == elf::DT_NEEDED)
+            .filter_map(|entry| entry.string)
+            .collect())
+    }
+
+
+    // Translate a virtual address into a file offset using the PT_LOAD segments
+    pub fn vaddr_to_offset(&self, vaddr: u64) -> Option<u64> {
+        self.program_headers.iter()
+            .filter(|segment| segment.program_type == elf::PT_LOAD)
+            .find(|segment| vaddr >= segment.vaddr && vaddr - segment.vaddr < segment.filesz)
+            .map(|segment| segment.offset + (vaddr - segment.vaddr))
+    }
+
+
+    // Every executable region of the binary in file order. Sections with
+    // SHF_EXECINSTR are used when present, otherwise PT_LOAD segments with
+    // the execute flag.
+    pub fn code_regions(&self) -> Result<Vec<CodeRegion<'_>>, ChiselError> {
+        let mut regions: Vec<CodeRegion> = Vec::new();
+
+        if !self.section_headers.is_empty() {
+            for section in self.sections() {
+                if !section.flags.contains(elf::SectionFlags::EXECINSTR) || section.section_type == elf::SHT_NOBITS {
+                    continue;
+                }
+
+                regions.push(CodeRegion {
+                    name: section.name.clone(),
+                    vaddr: self.section_vaddr(section),
+                    offset: section.offset,
+                    data: self.section_data(section)?
+                });
+            }
+        } else {
+            for segment in self.segments() {
+                if segment.program_type != elf::PT_LOAD || !segment.flags.contains(elf::SegmentFlags::X) {
+                    continue;
+                }
+
+                regions.push(CodeRegion {
+                    name: format!("PT_LOAD [{}]", segment.id),
+                    vaddr: segment.vaddr,
+                    offset: segment.offset,
+                    data: self.segment_data(segment)?
+                });
+            }
+        }
+
+        Ok(regions)
+    }
+
+
+    // Parse the frame description entries of .eh_frame. Binaries without
+    // section headers locate the table through the PT_GNU_EH_FRAME header.
+    pub fn frame_entries(&self) -> Result<Vec<elf::FrameDescriptionEntry>, ChiselError> {
+        let is_x86_64: bool = self.file_header.is_x86_64;
+        let endian: elf::EndianType = self.file_header.endian;
+
+        if let Some(section) = self.section_by_name(".eh_frame") {
+            let data: &[u8] = self.section_data(section)?;
+            return util::build_frame_entries(data, self.section_vaddr(section), is_x86_64, endian);
+        }
+
+        if !self.section_headers.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let header: &elf::ProgramHeader = match self.segment_by_type(elf::PT_GNU_EH_FRAME) {
+            Some(segment) => segment,
+            None => return Ok(Vec::new())
+        };
+
+        // .eh_frame_hdr begins with a version byte and the encoding of the
+        // pointer to .eh_frame that follows the four byte preamble
+        let header_data: &[u8] = self.segment_data(header)?;
+        util::check_bounds(header_data, 0, elf::EH_FRAME_HDR_PTR_OFFSET as u64, ".eh_frame_hdr")?;
+
+        let (eh_frame_vaddr, _): (u64, usize) = util::encoded_pointer_from_buffer(
+            header_data,
+            elf::EH_FRAME_HDR_PTR_OFFSET as usize,
+            header_data[1],
+            header.vaddr + elf::EH_FRAME_HDR_PTR_OFFSET as u64,
+            is_x86_64,
+            endian
+        )?;
+
+        // The table runs until its zero terminator, so hand over the rest
+        // of the segment that holds it
+        let segment: &elf::ProgramHeader = match self.segments().find(|segment| {
+            segment.program_type == elf::PT_LOAD
+                && eh_frame_vaddr >= segment.vaddr
+                && eh_frame_vaddr - segment.vaddr < segment.filesz
+        }) {
+            Some(segment) => segment,
+            None => return Ok(Vec::new())
+        };
+
+        let data: &[u8] = self.segment_data(segment)?;
+        let start: usize = (eh_frame_vaddr - segment.vaddr) as usize;
+        util::build_frame_entries(&data[start..], eh_frame_vaddr, is_x86_64, endian)
+    }
+
+
+    // Addresses known to hold code: the entry point, defined function
+    // symbols, DT_INIT/DT_FINI and the entries of the init/fini arrays
+    pub fn code_seeds(&self) -> Result<Vec<u64>, ChiselError> {
+        let mut seeds: Vec<u64> = Vec::new();
+
+        if self.file_header.entryoff != 0 {
+            seeds.push(self.file_header.entryoff);
+        }
+
+        for symbol in self.symbols()?.iter().chain(self.dynamic_symbols()?.iter()) {
+            if symbol.sym_type == elf::STT_FUNC && symbol.shndx != elf::SHN_UNDEF {
+                seeds.push(symbol.value);
+            }
+        }
+
+        let dynamic_entries: Vec<elf::DynamicEntry> = self.dynamic_entries()?;
+        let addends: HashMap<u64, u64> = self.relative_addends()?;
+
+        for entry in dynamic_entries.iter() {
+            if (entry.tag == elf::DT_INIT || entry.tag == elf::DT_FINI) && entry.value != 0 {
+                seeds.push(entry.value);
+            }
+        }
+
+        for section in self.sections() {
+            if section.section_type == elf::SHT_INIT_ARRAY
+                || section.section_type == elf::SHT_FINI_ARRAY
+                || section.section_type == elf::SHT_PREINIT_ARRAY {
+                seeds.extend(self.read_pointers(self.section_data(section)?, self.section_vaddr(section), &addends));
+            }
+        }
+
+        // Without section headers the arrays are found through the dynamic section
+        if self.section_headers.is_empty() {
+            let arrays: [(u64, u64); 3] = [
+                (elf::DT_INIT_ARRAY, elf::DT_INIT_ARRAYSZ),
+                (elf::DT_FINI_ARRAY, elf::DT_FINI_ARRAYSZ),
+                (elf::DT_PREINIT_ARRAY, elf::DT_PREINIT_ARRAYSZ),
+            ];
+
+            for (array_tag, size_tag) in arrays.iter() {
+                let vaddr: Option<u64> = dynamic_entries.iter().find(|entry| entry.tag == *array_tag).map(|entry| entry.value);
+                let size: Option<u64> = dynamic_entries.iter().find(|entry| entry.tag == *size_tag).map(|entry| entry.value);
+
+                if let (Some(vaddr), Some(size)) = (vaddr, size) {
+                    if let Some(offset) = self.vaddr_to_offset(vaddr) {
+                        util::check_bounds(&self.bytes, offset, size, "dynamic pointer array")?;
+                        let data: &[u8] = &self.bytes[offset as usize..(offset + size) as usize];
+                        seeds.extend(self.read_pointers(data, vaddr, &addends));
+                    }
+                }
+            }
+        }
+
+        seeds.sort_unstable();
+        seeds.dedup();
+        Ok(seeds)
+    }
+
+
+    // Read a section holding an array of code pointers, such as .init_array.
+    // Entries left as zero in the file are filled in from the matching
+    // R_*_RELATIVE relocation, and the 0 / -1 sentinels are dropped.
+    pub fn pointer_array(&self, section: &elf::SectionHeader) -> Result<Vec<u64>, ChiselError> {
+        Ok(self.read_pointers(self.section_data(section)?, self.section_vaddr(section), &self.relative_addends()?))
+    }
+
+
+    fn read_pointers(&self, data: &[u8], vaddr: u64, addends: &HashMap<u64, u64>) -> Vec<u64> {
+        let mut pointers: Vec<u64> = Vec::new();
+
+        let is_x86_64: bool = self.file_header.is_x86_64;
+        let pointer_size: usize = if is_x86_64 { 8 } else { 4 };
+        let sentinel: u64 = if is_x86_64 { u64::MAX } else { u32::MAX as u64 };
+
+        for i in 0..(data.len() / pointer_size) {
+            let mut pointer: u64 = util::addr_from_buffer(data, i * pointer_size, is_x86_64, self.file_header.endian);
+
+            if pointer == 0 {
+                let slot: u64 = vaddr.wrapping_add((i * pointer_size) as u64);
+                pointer = addends.get(&slot).copied().unwrap_or(0);
+            }
+
+            if pointer != 0 && pointer != sentinel {
+                pointers.push(pointer);
+            }
+        }
+
+        pointers
+    }
+
+
+    // Addends of the R_*_RELATIVE relocations, keyed by the address each
+    // one patches
+    fn relative_addends(&self) -> Result<HashMap<u64, u64>, ChiselError> {
+        let relative: u32 = if self.file_header.is_x86_64 { elf::R_X86_64_RELATIVE } else { elf::R_386_RELATIVE };
+        let mut addends: HashMap<u64, u64> = HashMap::new();
+
+        for section in self.sections() {
+            if section.section_type != elf::SHT_RELA && section.section_type != elf::SHT_REL {
+                continue;
+            }
+
+            for relocation in self.relocation_table(section)? {
+                if relocation.rel_type == relative {
+                    if let Some(addend) = relocation.addend {
+                        addends.entry(relocation.offset).or_insert(addend as u64);
+                    }
+                }
+            }
+        }
+
+        Ok(addends)
+    }
+
+
+    // Translate a file offset into a virtual address using the PT_LOAD segments
+    pub fn offset_to_vaddr(&self, offset: u64) -> Option<u64> {
+        self.program_headers.iter()
+            .filter(|segment| segment.program_type == elf::PT_LOAD)
+            .find(|segment| offset >= segment.offset && offset - segment.offset < segment.filesz)
+            .map(|segment| segment.vaddr + (offset - segment.offset))
+    }
+
+
+    // Address a section is mapped at. Falls back to the covering PT_LOAD
+    // segment when sh_addr is unset, and to zero for relocatable objects.
+    pub fn section_vaddr(&self, section: &elf::SectionHeader) -> u64 {
+        if section.addr != 0 {
+            return section.addr;
+        }
+
+        self.offset_to_vaddr(section.offset).unwrap_or(0)
+    }
+
+
+    // Whether an address falls inside the image once loaded. PT_LOAD
+    // segments are used when present, otherwise allocated sections.
+    pub fn is_mapped(&self, vaddr: u64) -> bool {
+        if self.program_headers.iter().any(|segment| segment.program_type == elf::PT_LOAD) {
+            return self.program_headers.iter()
+                .filter(|segment| segment.program_type == elf::PT_LOAD)
+                .any(|segment| vaddr >= segment.vaddr && vaddr - segment.vaddr < segment.memsz);
+        }
+
+        self.section_headers.iter()
+            .filter(|section| section.flags.contains(elf::SectionFlags::ALLOC))
+            .any(|section| vaddr >= section.addr && vaddr - section.addr < section.size)
+    }
+
+
+    // NUL terminated string of printable characters stored at an address
+    // in the image's data. Addresses in executable or uninitialised memory,
+    // pointers into the image and bytes that do not form printable text
+    // give None.
+    pub fn string_at(&self, vaddr: u64) -> Option<String> {
+        let data: &[u8] = if self.section_headers.is_empty() {
+            let segment: &elf::ProgramHeader = self.program_headers.iter()
+                .filter(|segment| segment.program_type == elf::PT_LOAD)
+                .filter(|segment| !segment.flags.contains(elf::SegmentFlags::X))
+                .find(|segment| vaddr >= segment.vaddr && vaddr - segment.vaddr < segment.filesz)?;
+
+            &self.segment_data(segment).ok()?[(vaddr - segment.vaddr) as usize..]
+        } else {
+            let section: &elf::SectionHeader = self.section_headers.iter()
+                .filter(|section| section.flags.contains(elf::SectionFlags::ALLOC))
+                .filter(|section| !section.flags.contains(elf::SectionFlags::EXECINSTR))
+                .filter(|section| section.section_type != elf::SHT_NOBITS && section.addr != 0)
+                .find(|section| vaddr >= section.addr && vaddr - section.addr < section.size)?;
+
+            &self.section_data(section).ok()?[(vaddr - section.addr) as usize..]
+        };
+
+        // Short strings are often the low bytes of a pointer to the image
+        let pointer_size: usize = if self.file_header.is_x86_64 { 8 } else { 4 };
+
+        if data.len() >= pointer_size {
+            let pointer: u64 = util::addr_from_buffer(data, 0, self.file_header.is_x86_64, self.file_header.endian);
+
+            if self.is_mapped(pointer) {
+                return None;
+            }
+        }
+
+        let length: usize = data.iter().position(|byte| *byte == 0)?;
+        let text: &[u8] = &data[..length];
+
+        let printable: bool = !text.is_empty()
+            && text.iter().all(|byte| (0x20..0x7F).contains(byte) || matches!(byte, b'\t' | b'\n' | b'\r'));
+
+        if printable {
+            Some(String::from_utf8_lossy(text).into_owned())
+        } else {
+            None
+        }
+    }
+
+
+    // Position independent executables and shared objects may be loaded
+    // at any base address
+    pub fn is_position_independent(&self) -> bool {
+        self.file_header.elf_type == elf::ET_DYN
+    }
+
+
+    // Lowest page aligned address of the PT_LOAD segments
+    pub fn image_base(&self) -> u64 {
+        self.program_headers.iter()
+            .filter(|segment| segment.program_type == elf::PT_LOAD)
+            .map(|segment| {
+                if segment.align > 1 { segment.vaddr & !(segment.align - 1) } else { segment.vaddr }
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+
+    // Amount to add to link-time addresses so the image appears loaded at
+    // base. Only position independent binaries can be rebased.
+    pub fn load_bias(&self, base: u64) -> Option<u64> {
+        if self.is_position_independent() {
+            Some(base.wrapping_sub(self.image_base()))
+        } else {
+            None
+        }
+    }
+
+
+    // Raw file contents backing a program segment
+    pub fn segment_data(&self, segment: &elf::ProgramHeader) -> Result<&[u8], ChiselError> {
+        let structure: String = format!("segment [{}]", segment.id);
+        util::check_bounds(&self.bytes, segment.offset, segment.filesz, &structure)?;
+
+        let start: usize = segment.offset as usize;
+        let end: usize = start + segment.filesz as usize;
+        Ok(&self.bytes[start..end])
+    }
+}
+
+
+fn parse_section_headers(bytes: &[u8], file_header: &elf::FileHeader) -> Result<Vec<elf::SectionHeader>, ChiselError> {
+    let mut section_headers: Vec<elf::SectionHeader> = Vec::new();
+
+    // Iterate through number of section headers
+    for i in 0..file_header.shnum {
+
+        // Determine the offset of this entry based on the section header size
+        let section_table_offset: u64 = file_header.shoff.saturating_add(file_header.shentsize as u64 * i as u64);
+
+        // Build section header data structure
+        let section_header: elf::SectionHeader = util::build_section_header(
+            bytes,
+            section_table_offset as usize,
+            i,
+            file_header.is_x86_64,
+            file_header.endian
+        )?;
+
+        section_headers.push(section_header);
+    }
+
+    // A .shstrtab index of SHN_UNDEF means the sections are unnamed
+    if file_header.shstrndx == elf::SHN_UNDEF || section_headers.is_empty() {
+        return Ok(section_headers);
+    }
+
+    let shstrtab_section: &elf::SectionHeader = section_headers.get(file_header.shstrndx as usize)
+        .ok_or(ChiselError::BadIndex {
+            structure: "e_shstrndx".to_string(),
+            index: file_header.shstrndx as u64,
+            count: file_header.shnum as u64
+        })?;
+
+    // Determine the section name for each section using the .shstrtab data
+    util::check_bounds(bytes, shstrtab_section.offset, shstrtab_section.size, ".shstrtab")?;
+
+    let shstrtab_start: usize = shstrtab_section.offset as usize;
+    let shstrtab_end: usize = shstrtab_start + shstrtab_section.size as usize;
+    let shstrtab_data: &[u8] = &bytes[shstrtab_start..shstrtab_end];
+
+    for section_header in section_headers.iter_mut() {
+        section_header.name = util::parse_section_name(shstrtab_data, section_header.name_idx as usize)?;
+    }
+
+    Ok(section_headers)
+}
+
+
+fn parse_program_headers(bytes: &[u8], file_header: &elf::FileHeader) -> Result<Vec<elf::ProgramHeader>, ChiselError> {
+    let mut program_headers: Vec<elf::ProgramHeader> = Vec::new();
+
+    // Iterate through number of Program Headers
+    for i in 0..file_header.phnum {
+
+        // Determine the offset of this entry based on the program header size
+        let program_table_offset: u64 = file_header.phoff.saturating_add(file_header.phentsize as u64 * i as u64);
+
+        // Build Program Header data structure
+        let program_header: elf::ProgramHeader = util::build_program_header(
+            bytes,
+            program_table_offset as usize,
+            i,
+            file_header.is_x86_64,
+            file_header.endian
+        )?;
+
+        program_headers.push(program_header);
+    }
+
+    Ok(program_headers)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::elf;
+    use crate::error::ChiselError;
+    use crate::util;
+
+    use super::Elf;
+
+
+    #[test]
+    fn parses_64_bit_headers() {
+        let binary: Elf = Elf::from_file("testing/hello").unwrap();
+        let text: &elf::SectionHeader = binary.section_by_name(".text").unwrap();
+
+        assert_eq!(binary.bitness(), 64);
+        assert_eq!(binary.file_header().entryoff, 0x1060);
+        assert_eq!(binary.sections().len(), 31);
+        assert_eq!(binary.segments().len(), 13);
+        assert_eq!((text.addr, text.offset, text.size), (0x1060, 0x1060, 0x10c));
+    }
+
+
+    // Elf32 headers pack their fields at different offsets and widths
+    #[test]
+    fn parses_32_bit_headers() {
+        let binary: Elf = Elf::from_file("testing/hello32").unwrap();
+        let text: &elf::SectionHeader = binary.section_by_name(".text").unwrap();
+        let code: &elf::ProgramHeader = binary.segments().find(|segment| segment.offset == 0x1000).unwrap();
+
+        assert_eq!(binary.bitness(), 32);
+        assert_eq!(binary.file_header().entryoff, 0x107d);
+        assert_eq!(binary.sections().len(), 22);
+        assert_eq!(binary.segments().len(), 12);
+        assert_eq!((text.addr, text.offset, text.size), (0x1030, 0x1030, 0x6c));
+        assert_eq!((code.program_type, code.vaddr, code.filesz, code.align), (elf::PT_LOAD, 0x1000, 0x9c, 0x1000));
+    }
+
+
+    fn symbol(symbols: &[elf::Symbol], name: &str) -> elf::Symbol {
+        symbols.iter().find(|symbol| symbol.name == name).unwrap().clone()
+    }
+
+
+    #[test]
+    fn parses_symbol_tables() {
+        let binary: Elf = Elf::from_file("testing/hello").unwrap();
+
+        let main: elf::Symbol = symbol(&binary.symbols().unwrap(), "main");
+        assert_eq!((main.value, main.size, main.sym_type, main.shndx), (0x1149, 35, elf::STT_FUNC, 16));
+        assert_eq!(util::parse_symbol_binding(main.binding), "GLOBAL");
+
+        let printf: elf::Symbol = symbol(&binary.dynamic_symbols().unwrap(), "printf");
+        assert_eq!((printf.value, printf.sym_type, printf.shndx), (0, elf::STT_FUNC, elf::SHN_UNDEF));
+    }
+
+
+    // Elf32_Sym orders its fields differently from Elf64_Sym
+    #[test]
+    fn parses_32_bit_symbol_tables() {
+        let binary: Elf = Elf::from_file("testing/hello32").unwrap();
+
+        let main: elf::Symbol = symbol(&binary.symbols().unwrap(), "main");
+        assert_eq!((main.value, main.size, main.sym_type, main.shndx), (0x1030, 77, elf::STT_FUNC, 11));
+
+        let greet: elf::Symbol = symbol(&binary.dynamic_symbols().unwrap(), "greet");
+        assert_eq!((greet.value, greet.sym_type, greet.shndx), (0, elf::STT_FUNC, elf::SHN_UNDEF));
+    }
+
+
+    fn dynamic_value(binary: &Elf, tag: u64) -> Option<u64> {
+        binary.dynamic_entries().unwrap().iter().find(|entry| entry.tag == tag).map(|entry| entry.value)
+    }
+
+
+    #[test]
+    fn parses_dynamic_section() {
+        let binary: Elf = Elf::from_file("testing/hello").unwrap();
+
+        assert_eq!(binary.needed_libraries().unwrap(), vec!["libc.so.6".to_string()]);
+        assert_eq!(dynamic_value(&binary, elf::DT_PLTGOT), Some(0x3fb8));
+        assert_eq!(dynamic_value(&binary, elf::DT_JMPREL), Some(0x610));
+    }
+
+
+    #[test]
+    fn parses_32_bit_dynamic_section() {
+        let binary: Elf = Elf::from_file("testing/hello32").unwrap();
+
+        assert_eq!(binary.needed_libraries().unwrap(), vec!["libgreet.so".to_string()]);
+        assert_eq!(dynamic_value(&binary, elf::DT_PLTGOT), Some(0x3ff4));
+        assert_eq!(dynamic_value(&binary, elf::DT_JMPREL), Some(0x264));
+    }
+
+
+    fn relocations(binary: &Elf, section: &str) -> Vec<elf::Relocation> {
+        binary.relocation_table(binary.section_by_name(section).unwrap()).unwrap()
+    }
+
+
+    #[test]
+    fn parses_rela_relocations() {
+        let binary: Elf = Elf::from_file("testing/hello").unwrap();
+        let plt: Vec<elf::Relocation> = relocations(&binary, ".rela.plt");
+
+        assert_eq!(plt.len(), 1);
+        assert_eq!((plt[0].offset, plt[0].rel_type, plt[0].addend), (0x3fd0, elf::R_X86_64_JUMP_SLOT, Some(0)));
+        assert_eq!(plt[0].symbol.as_ref().map(|symbol| symbol.name.as_str()), Some("printf"));
+    }
+
+
+    // REL entries keep their addend in the relocated field instead
+    #[test]
+    fn parses_rel_relocations() {
+        let binary: Elf = Elf::from_file("testing/hello32").unwrap();
+        let plt: Vec<elf::Relocation> = relocations(&binary, ".rel.plt");
+        let dynamic: Vec<elf::Relocation> = relocations(&binary, ".rel.dyn");
+
+        assert_eq!(plt.len(), 1);
+        assert_eq!((plt[0].offset, plt[0].rel_type, plt[0].addend), (0x4000, elf::R_386_JUMP_SLOT, None));
+        assert_eq!(plt[0].symbol.as_ref().map(|symbol| symbol.name.as_str()), Some("greet"));
+
+        let relative: Vec<(u64, u32)> = dynamic.iter().map(|relocation| (relocation.offset, relocation.rel_type)).collect();
+        assert_eq!(relative, vec![(0x4004, elf::R_386_RELATIVE), (0x4008, elf::R_386_RELATIVE)]);
+        assert!(dynamic.iter().all(|relocation| relocation.symbol.is_none()));
+    }
+
+
+    fn notes(binary: &Elf) -> Vec<elf::NoteData> {
+        binary.notes().unwrap().into_iter().flat_map(|(_, notes)| notes).map(|note| note.data).collect()
+    }
+
+
+    fn feature_flags(notes: &[elf::NoteData]) -> Option<u32> {
+        notes.iter()
+            .filter_map(|data| match data {
+                elf::NoteData::GnuProperties(properties) => Some(properties),
+                _ => None
+            })
+            .flatten()
+            .find(|property| property.pr_type == elf::GNU_PROPERTY_X86_FEATURE_1_AND)
+            .map(|property| property.value)
+    }
+
+
+    #[test]
+    fn decodes_notes() {
+        let binary: Elf = Elf::from_file("testing/hello").unwrap();
+        let notes: Vec<elf::NoteData> = notes(&binary);
+
+        assert_eq!(binary.build_id().unwrap().as_deref(), Some("d282d37b48b1029e467412154d319bbcbe9e3e49"));
+        assert!(notes.iter().any(|data| matches!(data, elf::NoteData::AbiTag { os: 0, major: 3, minor: 2, patch: 0 })));
+        assert_eq!(feature_flags(&notes), Some(elf::GNU_PROPERTY_X86_FEATURE_1_IBT | elf::GNU_PROPERTY_X86_FEATURE_1_SHSTK));
+    }
+
+
+    // i386 notes and properties are padded to 4 bytes rather than 8
+    #[test]
+    fn decodes_32_bit_notes() {
+        let binary: Elf = Elf::from_file("testing/hello32").unwrap();
+
+        assert_eq!(binary.build_id().unwrap().as_deref(), Some("8f76288df89fcfb7ac5c9622d4e3a33297b7c710"));
+        assert_eq!(feature_flags(&notes(&binary)), Some(elf::GNU_PROPERTY_X86_FEATURE_1_IBT | elf::GNU_PROPERTY_X86_FEATURE_1_SHSTK));
+    }
+
+
+    #[test]
+    fn rejects_invalid_data_encoding() {
+        let mut bytes: Vec<u8> = fs::read("testing/hello").unwrap();
+        bytes[elf::ENDIAN_OFFSET as usize] = 0x03;
+
+        assert!(matches!(Elf::parse(bytes), Err(ChiselError::InvalidDataEncoding(0x03))));
+    }
+}
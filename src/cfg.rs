@@ -0,0 +1,327 @@
+// cfg.rs
+// Created: 10/17/2026
+// Description: Basic block control flow graphs. Splits the code of each
+//              discovered function into basic blocks and links them with
+//              typed edges.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use iced_x86::{Decoder, DecoderOptions, FlowControl, Instruction};
+
+use crate::binary::CodeRegion;
+use crate::disasm;
+use crate::functions::Function;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    FallThrough,
+    ConditionalTaken,
+    ConditionalNotTaken,
+    Unconditional,
+    CallReturn
+}
+
+
+impl fmt::Display for EdgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            EdgeKind::FallThrough => "fall-through",
+            EdgeKind::ConditionalTaken => "taken",
+            EdgeKind::ConditionalNotTaken => "not taken",
+            EdgeKind::Unconditional => "unconditional",
+            EdgeKind::CallReturn => "call-return"
+        })
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Edge {
+    pub from: u64,
+    pub to: u64,
+    pub kind: EdgeKind
+}
+
+
+// Straight-line run of instructions with a single entry and a single exit
+#[derive(Debug, Clone)]
+pub struct BasicBlock {
+    pub start: u64,
+    pub end: u64,
+    pub instructions: Vec<Instruction>
+}
+
+
+impl BasicBlock {
+    pub fn terminator(&self) -> &Instruction {
+        // Blocks are never built empty
+        &self.instructions[self.instructions.len() - 1]
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ControlFlowGraph {
+    pub name: String,
+    pub entry: u64,
+    pub blocks: BTreeMap<u64, BasicBlock>,
+    pub edges: Vec<Edge>
+}
+
+
+impl ControlFlowGraph {
+    pub fn block_containing(&self, address: u64) -> Option<&BasicBlock> {
+        self.blocks.range(..=address)
+            .next_back()
+            .map(|(_, block)| block)
+            .filter(|block| address < block.end)
+    }
+
+
+    pub fn successors(&self, block: u64) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.from == block)
+    }
+
+
+    pub fn predecessors(&self, block: u64) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.to == block)
+    }
+
+
+    // Direct call targets made from within the function
+    pub fn call_targets(&self) -> Vec<u64> {
+        let mut targets: Vec<u64> = self.blocks.values()
+            .map(|block| block.terminator())
+            .filter(|instruction| instruction.flow_control() == FlowControl::Call)
+            .filter_map(disasm::branch_target)
+            .collect();
+
+        targets.sort_unstable();
+        targets.dedup();
+        targets
+    }
+}
+
+
+// Functions of the program and the direct calls made between them
+#[derive(Debug, Clone, Default)]
+pub struct CallGraph {
+    pub nodes: Vec<(u64, String)>,
+    pub edges: Vec<(u64, u64)>
+}
+
+
+impl CallGraph {
+    pub fn callees(&self, caller: u64) -> impl Iterator<Item = u64> + '_ {
+        self.edges.iter().filter(move |(from, _)| *from == caller).map(|(_, to)| *to)
+    }
+
+
+    pub fn callers(&self, callee: u64) -> impl Iterator<Item = u64> + '_ {
+        self.edges.iter().filter(move |(_, to)| *to == callee).map(|(from, _)| *from)
+    }
+}
+
+
+// Build the call graph from the direct calls in each function's CFG.
+// Calls to addresses that are not a known function start are dropped.
+pub fn build_call_graph(graphs: &[ControlFlowGraph]) -> CallGraph {
+    let mut call_graph: CallGraph = CallGraph::default();
+    let entries: BTreeSet<u64> = graphs.iter().map(|graph| graph.entry).collect();
+
+    for graph in graphs {
+        call_graph.nodes.push((graph.entry, graph.name.clone()));
+
+        for target in graph.call_targets() {
+            if entries.contains(&target) {
+                call_graph.edges.push((graph.entry, target));
+            }
+        }
+    }
+
+    call_graph
+}
+
+
+// Build the control flow graph of every function
+pub fn build_cfgs(functions: &[Function], regions: &[CodeRegion], bitness: u32) -> Vec<ControlFlowGraph> {
+    functions.iter()
+        .map(|function| build_cfg(function, regions, bitness))
+        .collect()
+}
+
+
+// Build the control flow graph of a single function. Only code inside the
+// function's range is followed; jumps that leave it are treated as tail
+// calls and get no edge. Blocks end at branches, returns and calls, and
+// before any instruction that another branch targets.
+pub fn build_cfg(function: &Function, regions: &[CodeRegion], bitness: u32) -> ControlFlowGraph {
+    let mut cfg: ControlFlowGraph = ControlFlowGraph {
+        name: function.name.clone(),
+        entry: function.start,
+        blocks: BTreeMap::new(),
+        edges: Vec::new()
+    };
+
+    let region: &CodeRegion = match disasm::find_region(regions, function.start) {
+        Some(region) => region,
+        None => return cfg
+    };
+
+    let instructions: BTreeMap<u64, Instruction> = decode_function(function, region, bitness);
+    let leaders: BTreeSet<u64> = find_leaders(function, &instructions);
+
+    // Group instructions into blocks
+    let mut current: Option<BasicBlock> = None;
+
+    for (address, instruction) in instructions.iter() {
+        let starts_block: bool = match &current {
+            Some(block) => leaders.contains(address) || block.end != *address || is_terminator(block.terminator()),
+            None => true
+        };
+
+        if starts_block {
+            if let Some(block) = current.take() {
+                cfg.blocks.insert(block.start, block);
+            }
+
+            current = Some(BasicBlock {
+                start: *address,
+                end: *address,
+                instructions: Vec::new()
+            });
+        }
+
+        if let Some(block) = current.as_mut() {
+            block.instructions.push(*instruction);
+            block.end = instruction.next_ip();
+        }
+    }
+
+    if let Some(block) = current.take() {
+        cfg.blocks.insert(block.start, block);
+    }
+
+    // Link each block to its successors based on how it ends
+    let mut edges: Vec<Edge> = Vec::new();
+
+    for block in cfg.blocks.values() {
+        let terminator: &Instruction = block.terminator();
+        let target: Option<u64> = disasm::branch_target(terminator);
+
+        match terminator.flow_control() {
+            FlowControl::ConditionalBranch => {
+                if let Some(target) = target {
+                    edges.push(Edge { from: block.start, to: target, kind: EdgeKind::ConditionalTaken });
+                }
+                edges.push(Edge { from: block.start, to: block.end, kind: EdgeKind::ConditionalNotTaken });
+            },
+            FlowControl::UnconditionalBranch => {
+                if let Some(target) = target {
+                    edges.push(Edge { from: block.start, to: target, kind: EdgeKind::Unconditional });
+                }
+            },
+            FlowControl::Call | FlowControl::IndirectCall => {
+                edges.push(Edge { from: block.start, to: block.end, kind: EdgeKind::CallReturn });
+            },
+            FlowControl::Return | FlowControl::IndirectBranch | FlowControl::Exception => {},
+            _ => edges.push(Edge { from: block.start, to: block.end, kind: EdgeKind::FallThrough })
+        }
+    }
+
+    // Drop edges that lead outside of the function
+    edges.retain(|edge| cfg.blocks.contains_key(&edge.to));
+    cfg.edges = edges;
+
+    cfg
+}
+
+
+// Decode the instructions reachable from the function start without
+// leaving the function's range
+fn decode_function(function: &Function, region: &CodeRegion, bitness: u32) -> BTreeMap<u64, Instruction> {
+    let mut instructions: BTreeMap<u64, Instruction> = BTreeMap::new();
+    let mut worklist: Vec<u64> = vec![function.start];
+
+    let mut decoder: Decoder = Decoder::with_ip(bitness, region.data, region.vaddr, DecoderOptions::NONE);
+    let mut instruction: Instruction = Instruction::default();
+
+    while let Some(start) = worklist.pop() {
+        if !function.contains(start) || start < region.vaddr || start - region.vaddr >= region.data.len() as u64 {
+            continue;
+        }
+
+        // Start lies within the region, so the position is always valid
+        let _ = decoder.set_position((start - region.vaddr) as usize);
+        decoder.set_ip(start);
+
+        while decoder.can_decode() && function.contains(decoder.ip()) {
+            let address: u64 = decoder.ip();
+
+            if instructions.contains_key(&address) {
+                break;
+            }
+
+            decoder.decode_out(&mut instruction);
+
+            if instruction.is_invalid() {
+                break;
+            }
+
+            instructions.insert(address, instruction);
+
+            match instruction.flow_control() {
+                FlowControl::ConditionalBranch => {
+                    if let Some(target) = disasm::branch_target(&instruction) {
+                        worklist.push(target);
+                    }
+                },
+                FlowControl::UnconditionalBranch => {
+                    if let Some(target) = disasm::branch_target(&instruction) {
+                        worklist.push(target);
+                    }
+                    break;
+                },
+                FlowControl::Return | FlowControl::IndirectBranch | FlowControl::Exception => break,
+                _ => {}
+            }
+        }
+    }
+
+    instructions
+}
+
+
+// Addresses that begin a basic block: the function entry, branch targets
+// and the instructions following branches and calls
+fn find_leaders(function: &Function, instructions: &BTreeMap<u64, Instruction>) -> BTreeSet<u64> {
+    let mut leaders: BTreeSet<u64> = BTreeSet::new();
+    leaders.insert(function.start);
+
+    for instruction in instructions.values() {
+        if !is_terminator(instruction) {
+            continue;
+        }
+
+        leaders.insert(instruction.next_ip());
+
+        if let Some(target) = disasm::branch_target(instruction) {
+            if function.contains(target) {
+                leaders.insert(target);
+            }
+        }
+    }
+
+    leaders
+}
+
+
+// Instructions that end a basic block
+fn is_terminator(instruction: &Instruction) -> bool {
+    !matches!(
+        instruction.flow_control(),
+        FlowControl::Next | FlowControl::Interrupt | FlowControl::XbeginXabortXend
+    )
+}
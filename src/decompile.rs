@@ -0,0 +1,2025 @@
+// decompile.rs
+// Created: 10/17/2026
+// Description: Pseudo-C decompiler built on the analysis IR. Registers are
+//              followed through each block to rebuild expressions, stack
+//              slots become locals and argument registers become
+//              parameters per the System V ABI, and the control flow graph
+//              is structured back into loops and if statements.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use iced_x86::Register;
+
+use crate::functions::Function;
+use crate::ir::{self, BinaryOp, Expr, Flag, IrBlock, IrFunction, Stmt, UnaryOp, Var};
+use crate::pseudoc::{self, CStmt, CallSite, LoopKind, Name, Printer, Value};
+use crate::symbols::SymbolMap;
+
+
+// Integer arguments in the order the System V ABI passes them
+const ARGUMENT_REGISTERS: [Register; 6] = [Register::RDI, Register::RSI, Register::RDX, Register::RCX, Register::R8, Register::R9];
+
+// Registers a call may clobber
+const CALLER_SAVED: [Register; 9] = [
+    Register::RAX, Register::RCX, Register::RDX, Register::RSI, Register::RDI,
+    Register::R8, Register::R9, Register::R10, Register::R11
+];
+
+// Registers a function saves before using them
+const CALLEE_SAVED: [Register; 6] = [Register::RBX, Register::RBP, Register::R12, Register::R13, Register::R14, Register::R15];
+
+const FLAGS: [Flag; 4] = [Flag::Carry, Flag::Zero, Flag::Sign, Flag::Overflow];
+
+// Calls to these never return
+const NO_RETURN: [&str; 8] = ["exit", "_exit", "_Exit", "abort", "__stack_chk_fail", "__assert_fail", "__fortify_fail", "__chk_fail"];
+
+// Variables the decompiler introduces are temporaries numbered well above
+// the few the lifter uses within an instruction
+const PARAMETER_BASE: u32 = 0x10000;
+const LOCAL_BASE: u32 = 0x20000;
+const SAVED_BASE: u32 = 0x30000;
+const PLACEHOLDER_BASE: u32 = 0x40000;
+const ADDRESS_BASE: u32 = 0x50000;
+
+// Expressions larger than this are assigned to their register instead of
+// being substituted further
+const MAX_EXPRESSION_SIZE: usize = 64;
+
+// Virtual node every exit of the function flows into
+const EXIT: u64 = u64::MAX;
+
+
+// Decompile a function lifted to the IR into pseudo-C. Symbols, functions
+// and strings are used to name constants that are addresses.
+pub fn decompile(
+    function: &IrFunction,
+    symbols: &SymbolMap,
+    functions: &[Function],
+    strings: &BTreeMap<u64, String>) -> String {
+
+    let mut decompiler: Decompiler = Decompiler::new(function, symbols);
+
+    // Nothing was decoded at the start of the function
+    if !function.blocks.contains_key(&function.entry) {
+        let body: Vec<CStmt> = vec![CStmt::Comment(format!("no code decoded at {:#x}", function.entry))];
+        return decompiler.print(body, functions, strings);
+    }
+
+    let nodes: BTreeMap<u64, Node> = decompiler.translate();
+    let nodes: BTreeMap<u64, Node> = decompiler.simplify_graph(nodes);
+
+    let mut structurer: Structurer = Structurer::new(&nodes, function.entry);
+    let body: Vec<CStmt> = structurer.run();
+    let body: Vec<CStmt> = tidy(body);
+
+    let mut labels: BTreeSet<u64> = structurer.labels.clone();
+    goto_targets(&body, &mut labels);
+    let body: Vec<CStmt> = strip_labels(body, &labels);
+    let mut body: Vec<CStmt> = fold_returns(body);
+
+    // A void function falls off its end
+    if matches!(body.last(), Some(CStmt::Return(None))) {
+        body.pop();
+    }
+
+    let body: Vec<CStmt> = decompiler.alias_parameters(body);
+
+    decompiler.print(body, functions, strings)
+}
+
+
+// Stack and frame pointer at the start of a block, as offsets from the
+// stack pointer on entry to the function
+#[derive(Debug, Clone, Copy, Default)]
+struct Frame {
+    rsp: Option<i64>,
+    rbp: Option<i64>
+}
+
+
+// Block translated to C statements, ending in a jump to another block
+#[derive(Debug, Clone)]
+struct Node {
+    statements: Vec<CStmt>,
+    exit: Exit,
+    // Blocks reached through an indirect jump
+    indirect: Vec<u64>
+}
+
+
+#[derive(Debug, Clone)]
+enum Exit {
+    Stop,
+    Next(u64),
+    Branch { condition: Expr, taken: u64, not_taken: u64 }
+}
+
+
+impl Node {
+    fn successors(&self) -> Vec<u64> {
+        match &self.exit {
+            Exit::Stop => self.indirect.clone(),
+            Exit::Next(next) => vec![*next],
+            Exit::Branch { taken, not_taken, .. } => vec![*taken, *not_taken]
+        }
+    }
+}
+
+
+// Variables read and written by a statement
+struct Effects {
+    uses: Vec<Var>,
+    defs: Vec<Var>
+}
+
+
+struct Decompiler<'a> {
+    function: &'a IrFunction,
+    symbols: &'a SymbolMap,
+    frames: HashMap<u64, Frame>,
+    frame_pointer: bool,
+    returns_value: bool,
+    // Blocks execution can continue to after each block
+    successors: HashMap<u64, Vec<u64>>,
+    // Blocks ending in a call that never returns
+    no_return: HashSet<u64>,
+    // Stack slots read or written, keyed by offset and width
+    locals: BTreeMap<(i64, u32), Var>,
+    saved: u32
+}
+
+
+impl<'a> Decompiler<'a> {
+    fn new(function: &'a IrFunction, symbols: &'a SymbolMap) -> Decompiler<'a> {
+        let frames: HashMap<u64, Frame> = stack_frames(function);
+        let frame_pointer: bool = frames.values().any(|frame| frame.rbp.is_some());
+
+        let mut decompiler: Decompiler = Decompiler {
+            function,
+            symbols,
+            frames,
+            frame_pointer,
+            returns_value: false,
+            successors: HashMap::new(),
+            no_return: HashSet::new(),
+            locals: BTreeMap::new(),
+            saved: 0
+        };
+
+        decompiler.no_return = function.blocks.values()
+            .filter(|block| block.statements().any(|statement| decompiler.is_no_return_call(statement)))
+            .map(|block| block.start)
+            .collect();
+
+        let mut successors: HashMap<u64, Vec<u64>> = block_successors(function);
+        for start in decompiler.no_return.iter() {
+            successors.insert(*start, Vec::new());
+        }
+        decompiler.successors = successors;
+
+        decompiler.returns_value = decompiler.returns_value();
+        decompiler
+    }
+
+
+    // Name of a called function, without the @plt or @got suffix
+    fn callee(&self, target: &Expr) -> Option<&str> {
+        let address: u64 = match target {
+            Expr::Const { value, .. } => *value,
+            Expr::Load { address, .. } => address.as_const()?,
+            _ => return None
+        };
+
+        self.symbols.name_at(address).map(|name| name.trim_end_matches("@plt").trim_end_matches("@got"))
+    }
+
+
+    fn is_no_return_call(&self, statement: &Stmt) -> bool {
+        match statement {
+            Stmt::Call { target } => self.callee(target).is_some_and(|name| NO_RETURN.contains(&name)),
+            _ => false
+        }
+    }
+
+
+    fn successors(&self, block: &IrBlock) -> Vec<u64> {
+        self.successors.get(&block.start).cloned().unwrap_or_default()
+    }
+
+
+    // Registers and flags tracked across blocks. The stack pointer is
+    // followed through the frames instead, as is the frame pointer when
+    // the function sets one up.
+    fn tracked(&self, var: &Var) -> bool {
+        match var {
+            Var::Register(Register::RSP) => false,
+            Var::Register(Register::RBP) => !self.frame_pointer,
+            Var::Register(register) => register.is_gpr(),
+            Var::Flag(_) => true,
+            Var::Temp(..) => false
+        }
+    }
+
+
+    // Whether RAX holds a value at every return. It does when each path
+    // assigns it after the last call.
+    fn returns_value(&self) -> bool {
+        let rax: Var = Var::Register(Register::RAX);
+        let mut defined_out: HashMap<u64, bool> = self.function.blocks.keys().map(|start| (*start, true)).collect();
+        let mut at_returns: Vec<bool> = Vec::new();
+        let mut changed: bool = true;
+
+        let mut predecessors: HashMap<u64, Vec<u64>> = HashMap::new();
+        for (start, targets) in self.successors.iter() {
+            for target in targets {
+                predecessors.entry(*target).or_default().push(*start);
+            }
+        }
+
+        while changed {
+            changed = false;
+            at_returns.clear();
+
+            for block in self.function.blocks.values() {
+                let predecessors: &[u64] = predecessors.get(&block.start).map_or(&[], |from| from.as_slice());
+
+                let mut defined: bool = block.start != self.function.entry
+                    && !predecessors.is_empty()
+                    && predecessors.iter().all(|predecessor| defined_out[predecessor]);
+
+                for statement in block.statements() {
+                    match statement {
+                        Stmt::Assign { dest, value } if *dest == rax => defined = !matches!(value, Expr::Undefined(_)),
+                        Stmt::Call { .. } => defined = false,
+                        Stmt::Return => at_returns.push(defined),
+                        _ => {}
+                    }
+                }
+
+                if defined_out[&block.start] != defined {
+                    defined_out.insert(block.start, defined);
+                    changed = true;
+                }
+            }
+        }
+
+        !at_returns.is_empty() && at_returns.iter().all(|defined| *defined)
+    }
+
+
+    // Arguments of each call in a block: the prefix of the argument
+    // registers written since the previous call
+    fn call_arguments(&self, statements: &[&Stmt]) -> HashMap<usize, Vec<Register>> {
+        let mut arguments: HashMap<usize, Vec<Register>> = HashMap::new();
+        let mut written: HashSet<Register> = HashSet::new();
+
+        for (index, statement) in statements.iter().enumerate() {
+            match statement {
+                Stmt::Assign { dest: Var::Register(register), .. } => {
+                    written.insert(*register);
+                },
+                Stmt::Call { .. } | Stmt::Jump { .. } => {
+                    let passed: Vec<Register> = ARGUMENT_REGISTERS.iter()
+                        .take_while(|register| written.contains(register))
+                        .copied()
+                        .collect();
+
+                    arguments.insert(index, passed);
+                    written.clear();
+                },
+                _ => {}
+            }
+        }
+
+        arguments
+    }
+
+
+    fn effects(&self, statement: &Stmt, arguments: Option<&Vec<Register>>) -> Effects {
+        let mut uses: Vec<Var> = Vec::new();
+        let mut defs: Vec<Var> = Vec::new();
+
+        let read = |expr: &Expr, uses: &mut Vec<Var>| expr.visit(&mut |node| {
+            if let Expr::Var(var) = node {
+                uses.push(*var);
+            }
+        });
+
+        match statement {
+            Stmt::Assign { dest, value } => {
+                read(value, &mut uses);
+                defs.push(*dest);
+            },
+            Stmt::Store { address, value } => {
+                read(address, &mut uses);
+                read(value, &mut uses);
+            },
+            Stmt::Jump { target } => read(target, &mut uses),
+            Stmt::Branch { condition, target } => {
+                read(condition, &mut uses);
+                read(target, &mut uses);
+            },
+            Stmt::Call { target } => {
+                read(target, &mut uses);
+                defs.extend(CALLER_SAVED.iter().map(|register| Var::Register(*register)));
+                defs.extend(FLAGS.iter().map(|flag| Var::Flag(*flag)));
+            },
+            Stmt::Return if self.returns_value => uses.push(Var::Register(Register::RAX)),
+            Stmt::Return | Stmt::Unsupported(_) => {}
+        }
+
+        if let Some(arguments) = arguments {
+            uses.extend(arguments.iter().map(|register| Var::Register(*register)));
+        }
+
+        uses.retain(|var| self.tracked(var));
+        defs.retain(|var| self.tracked(var));
+        Effects { uses, defs }
+    }
+
+
+    // Registers and flags live at the end of each block
+    fn liveness(&self) -> HashMap<u64, HashSet<Var>> {
+        let mut summaries: HashMap<u64, (HashSet<Var>, HashSet<Var>)> = HashMap::new();
+
+        for block in self.function.blocks.values() {
+            let statements: Vec<&Stmt> = block.statements().collect();
+            let arguments: HashMap<usize, Vec<Register>> = self.call_arguments(&statements);
+            let mut uses: HashSet<Var> = HashSet::new();
+            let mut defs: HashSet<Var> = HashSet::new();
+
+            for (index, statement) in statements.iter().enumerate() {
+                let effects: Effects = self.effects(statement, arguments.get(&index));
+                uses.extend(effects.uses.into_iter().filter(|var| !defs.contains(var)));
+                defs.extend(effects.defs);
+            }
+
+            summaries.insert(block.start, (uses, defs));
+        }
+
+        let mut live_in: HashMap<u64, HashSet<Var>> = HashMap::new();
+        let mut live_out: HashMap<u64, HashSet<Var>> = HashMap::new();
+        let mut changed: bool = true;
+
+        while changed {
+            changed = false;
+
+            for block in self.function.blocks.values().rev() {
+                let out: HashSet<Var> = self.successors(block)
+                    .iter()
+                    .flat_map(|successor| live_in.get(successor).cloned().unwrap_or_default())
+                    .collect();
+
+                let (uses, defs) = &summaries[&block.start];
+                let mut live: HashSet<Var> = out.iter().filter(|var| !defs.contains(var)).copied().collect();
+                live.extend(uses.iter().copied());
+
+                if live_in.get(&block.start) != Some(&live) {
+                    live_in.insert(block.start, live);
+                    changed = true;
+                }
+                live_out.insert(block.start, out);
+            }
+        }
+
+        live_out
+    }
+
+
+    // Translate every block into C statements
+    fn translate(&mut self) -> BTreeMap<u64, Node> {
+        let live_out: HashMap<u64, HashSet<Var>> = self.liveness();
+        let function: &IrFunction = self.function;
+        let mut nodes: BTreeMap<u64, Node> = BTreeMap::new();
+
+        for block in function.blocks.values() {
+            let node: Node = self.translate_block(block, &live_out[&block.start]);
+            nodes.insert(block.start, node);
+        }
+
+        nodes
+    }
+
+
+    // Values of the registers at the start of a block
+    fn initial_state(&self, start: u64) -> HashMap<Var, Expr> {
+        let frame: Frame = self.frames.get(&start).copied().unwrap_or_default();
+        let mut state: HashMap<Var, Expr> = HashMap::new();
+
+        state.insert(Var::Register(Register::RSP), frame.rsp.map_or(Expr::Undefined(64), stack_address));
+
+        if let Some(rbp) = frame.rbp {
+            state.insert(Var::Register(Register::RBP), stack_address(rbp));
+        }
+
+        if start == self.function.entry {
+            for (index, register) in ARGUMENT_REGISTERS.iter().enumerate() {
+                state.insert(Var::Register(*register), Expr::var(Var::Temp(PARAMETER_BASE + index as u32, 64)));
+            }
+        }
+
+        state
+    }
+
+
+    fn translate_block(&mut self, block: &IrBlock, live_out: &HashSet<Var>) -> Node {
+        let statements: Vec<&Stmt> = block.statements().collect();
+        let arguments: HashMap<usize, Vec<Register>> = self.call_arguments(&statements);
+
+        // Registers and flags live after each statement
+        let mut live_after: Vec<HashSet<Var>> = vec![HashSet::new(); statements.len()];
+        let mut live: HashSet<Var> = if self.no_return.contains(&block.start) { HashSet::new() } else { live_out.clone() };
+
+        for (index, statement) in statements.iter().enumerate().rev() {
+            live_after[index] = live.clone();
+
+            let effects: Effects = self.effects(statement, arguments.get(&index));
+            for def in effects.defs {
+                live.remove(&def);
+            }
+            live.extend(effects.uses);
+        }
+
+        let mut state: HashMap<Var, Expr> = self.initial_state(block.start);
+        let mut out: Vec<CStmt> = Vec::new();
+        let mut terminator: Option<Stmt> = None;
+        let mut returned: Option<Expr> = None;
+        let mut tail_call: Option<CallSite> = None;
+
+        for (index, statement) in statements.iter().enumerate() {
+            let live: &HashSet<Var> = &live_after[index];
+
+            match statement {
+                Stmt::Assign { dest, value } => {
+                    let value: Expr = self.substitute(value, &state);
+
+                    if expression_size(&value) <= MAX_EXPRESSION_SIZE {
+                        state.insert(*dest, value);
+                    } else if live.contains(dest) || is_lifter_temp(dest) {
+                        // Long chains of operations are assigned as they go
+                        // rather than growing a single expression
+                        let mut users: Vec<Var> = state.iter()
+                            .filter(|(var, used)| (live.contains(var) || is_lifter_temp(var)) && *var != dest && used.uses(dest))
+                            .map(|(var, _)| *var)
+                            .collect();
+                        users.sort();
+
+                        self.materialize(users, &mut state, live, &mut out);
+                        state.remove(dest);
+                        out.push(CStmt::Assign { dest: *dest, value: Value::Expr(value) });
+                    } else {
+                        state.remove(dest);
+                    }
+                },
+                Stmt::Store { address, value } => {
+                    let address: Expr = self.substitute(address, &state);
+                    let value: Expr = self.substitute(value, &state);
+
+                    // The stack protector canary is copied in from fs
+                    if reads_segment(&value) {
+                        continue;
+                    }
+
+                    match stack_offset(&address) {
+                        Some(offset) => {
+                            // Callee saved registers pushed to be restored on return
+                            if matches!(&value, Expr::Var(Var::Register(register)) if CALLEE_SAVED.contains(register)) {
+                                continue;
+                            }
+
+                            let local: Var = self.local(offset, value.width());
+                            let users: Vec<Var> = pending(&state, live, |expr| expr.uses(&local));
+
+                            self.materialize(users, &mut state, live, &mut out);
+                            out.push(CStmt::Assign { dest: local, value: Value::Expr(value) });
+                        },
+                        None => {
+                            let readers: Vec<Var> = pending(&state, live, |expr| expr.reads_memory() || uses_local(expr));
+
+                            self.materialize(readers, &mut state, live, &mut out);
+                            out.push(CStmt::Store { address, value });
+                        }
+                    }
+                },
+                Stmt::Call { target } => {
+                    let site: CallSite = self.call_site(target, &arguments[&index], &state);
+                    let result: Var = Var::Register(Register::RAX);
+                    let assigned: bool = live.contains(&result);
+
+                    // Registers the call overwrites need not be kept
+                    let surviving: HashSet<Var> = live.iter()
+                        .filter(|var| !matches!(var, Var::Flag(_)) && !matches!(var, Var::Register(register) if CALLER_SAVED.contains(register)))
+                        .copied()
+                        .collect();
+
+                    // Values that read memory the call may change, or the
+                    // register its result is assigned to
+                    let readers: Vec<Var> = pending(&state, &surviving, |expr| {
+                        expr.reads_memory() || uses_local(expr) || (assigned && expr.uses(&result))
+                    });
+
+                    self.materialize(readers, &mut state, &surviving, &mut out);
+
+                    for register in CALLER_SAVED.iter() {
+                        state.remove(&Var::Register(*register));
+                    }
+                    for flag in FLAGS.iter() {
+                        state.remove(&Var::Flag(*flag));
+                    }
+
+                    if assigned {
+                        out.push(CStmt::Assign { dest: result, value: Value::Call(site) });
+                    } else {
+                        out.push(CStmt::Call(site));
+                    }
+
+                    if self.is_no_return_call(statement) {
+                        return Node { statements: out, exit: Exit::Stop, indirect: Vec::new() };
+                    }
+                },
+                Stmt::Jump { target } if self.is_tail_call(target) => {
+                    tail_call = Some(self.call_site(target, &arguments[&index], &state));
+                    terminator = Some((*statement).clone());
+                },
+                Stmt::Branch { condition, target } => {
+                    terminator = Some(Stmt::Branch {
+                        condition: self.substitute(condition, &state),
+                        target: self.substitute(target, &state)
+                    });
+                },
+                Stmt::Jump { target } => {
+                    terminator = Some(Stmt::Jump { target: self.substitute(target, &state) });
+                },
+                Stmt::Return => {
+                    if self.returns_value {
+                        returned = Some(strip_extend(self.substitute(&Expr::register(Register::RAX), &state)));
+                    }
+                    terminator = Some(Stmt::Return);
+                },
+                Stmt::Unsupported(mnemonic) => {
+                    out.push(CStmt::Comment(format!("unsupported instruction: {}", mnemonic)));
+                }
+            }
+        }
+
+        // Assign the registers later blocks read, keeping the expressions
+        // the block ends with in terms of the values before the assignment
+        let mut finishing: Vec<Expr> = Vec::new();
+
+        match &terminator {
+            Some(Stmt::Branch { condition, .. }) => finishing.push(condition.clone()),
+            Some(Stmt::Jump { target }) => finishing.push(target.clone()),
+            _ => {}
+        }
+
+        let assigned: Vec<Var> = pending(&state, live_out, |_| true);
+        let finishing: Vec<Expr> = self.assign_live(assigned, finishing, &mut state, live_out, &mut out);
+
+        let exit: Exit = match terminator {
+            Some(Stmt::Branch { target, .. }) => {
+                let condition: Expr = finishing[0].clone();
+                let falls_through: bool = self.function.blocks.contains_key(&block.end);
+
+                match target.as_const() {
+                    Some(taken) if self.function.blocks.contains_key(&taken) && falls_through => {
+                        Exit::Branch { condition, taken, not_taken: block.end }
+                    },
+                    Some(taken) if self.function.blocks.contains_key(&taken) => {
+                        // Falls through out of the function
+                        let leave: Vec<CStmt> = vec![CStmt::Jump(Expr::constant(block.end, 64))];
+                        out.push(CStmt::If { condition: negate(condition), then: leave, otherwise: Vec::new() });
+                        Exit::Next(taken)
+                    },
+                    _ => {
+                        // Conditional jump out of the function
+                        let leave: Vec<CStmt> = vec![CStmt::Jump(target)];
+                        out.push(CStmt::If { condition, then: leave, otherwise: Vec::new() });
+
+                        if falls_through {
+                            Exit::Next(block.end)
+                        } else {
+                            out.push(CStmt::Jump(Expr::constant(block.end, 64)));
+                            Exit::Stop
+                        }
+                    }
+                }
+            },
+            Some(Stmt::Jump { .. }) if tail_call.is_some() => {
+                let site: CallSite = tail_call.unwrap_or_else(|| unreachable!());
+
+                if self.returns_value {
+                    out.push(CStmt::Return(Some(Value::Call(site))));
+                } else {
+                    out.push(CStmt::Call(site));
+                    out.push(CStmt::Return(None));
+                }
+                Exit::Stop
+            },
+            Some(Stmt::Jump { target }) => match target.as_const() {
+                Some(next) if self.function.blocks.contains_key(&next) => Exit::Next(next),
+                _ => {
+                    out.push(CStmt::Jump(finishing[0].clone()));
+
+                    let indirect: Vec<u64> = self.successors(block);
+                    return Node { statements: out, exit: Exit::Stop, indirect };
+                }
+            },
+            Some(_) => {
+                out.push(CStmt::Return(returned.map(Value::Expr)));
+                Exit::Stop
+            },
+            None => match self.successors(block).first() {
+                Some(next) => Exit::Next(*next),
+                None => Exit::Stop
+            }
+        };
+
+        Node { statements: out, exit, indirect: Vec::new() }
+    }
+
+
+    // A jump to another function's start
+    fn is_tail_call(&self, target: &Expr) -> bool {
+        match target.as_const() {
+            Some(address) => !self.function.blocks.contains_key(&address) && self.symbols.name_at(address).is_some(),
+            None => false
+        }
+    }
+
+
+    fn call_site(&mut self, target: &Expr, arguments: &[Register], state: &HashMap<Var, Expr>) -> CallSite {
+        CallSite {
+            target: self.substitute(target, state),
+            arguments: arguments.iter()
+                .map(|register| self.substitute(&Expr::register(*register), state))
+                .collect()
+        }
+    }
+
+
+    // Replace the registers in an expression with their current values,
+    // turning stack slot reads into locals
+    fn substitute(&mut self, expr: &Expr, state: &HashMap<Var, Expr>) -> Expr {
+        let replaced: Expr = ir::simplify(replace_vars(expr.clone(), state));
+
+        let localized: Expr = replaced.map(&mut |node| match node {
+            Expr::Load { address, width } => match stack_offset(&address) {
+                Some(offset) => Expr::var(self.local(offset, width)),
+                None => Expr::Load { address, width }
+            },
+            other => other
+        });
+
+        ir::simplify(localized)
+    }
+
+
+    // Variable for the stack slot at an offset
+    fn local(&mut self, offset: i64, width: u32) -> Var {
+        let index: u32 = self.locals.len() as u32;
+        *self.locals.entry((offset, width)).or_insert(Var::Temp(LOCAL_BASE + index, width))
+    }
+
+
+    fn saved_var(&mut self, width: u32) -> Var {
+        self.saved += 1;
+        Var::Temp(SAVED_BASE + self.saved, width)
+    }
+
+
+    // Emit assignments for registers whose values are still pending as
+    // expressions. Other pending values that read the old contents of those
+    // registers are assigned first when live, or dropped when dead.
+    fn materialize(&mut self, vars: Vec<Var>, state: &mut HashMap<Var, Expr>, live: &HashSet<Var>, out: &mut Vec<CStmt>) {
+        self.assign_live(vars, Vec::new(), state, live, out);
+    }
+
+
+    // Assign a set of registers in parallel, then return the expressions
+    // given rewritten to use the registers' new values where they can
+    fn assign_live(
+        &mut self,
+        vars: Vec<Var>,
+        expressions: Vec<Expr>,
+        state: &mut HashMap<Var, Expr>,
+        live: &HashSet<Var>,
+        out: &mut Vec<CStmt>) -> Vec<Expr> {
+
+        let mut assigned: Vec<Var> = vars;
+        let mut changed: bool = !assigned.is_empty();
+
+        while changed {
+            changed = false;
+
+            let mut dependents: Vec<Var> = state.iter()
+                .filter(|(var, value)| !assigned.contains(var) && assigned.iter().any(|other| value.uses(other)))
+                .map(|(var, _)| *var)
+                .collect();
+            dependents.sort();
+
+            for var in dependents {
+                if live.contains(&var) || is_lifter_temp(&var) {
+                    assigned.push(var);
+                    changed = true;
+                } else {
+                    state.remove(&var);
+                }
+            }
+        }
+
+        // Stand in for the new values in the expressions, saving old values
+        // they still read
+        let placeholders: Vec<(Var, Var)> = assigned.iter()
+            .enumerate()
+            .map(|(index, var)| (*var, Var::Temp(PLACEHOLDER_BASE + index as u32, var.width())))
+            .collect();
+
+        let mut expressions: Vec<Expr> = expressions.into_iter()
+            .map(|mut expr| {
+                for (var, placeholder) in placeholders.iter() {
+                    expr = replace_value(expr, &state[var], *placeholder);
+                }
+                expr
+            })
+            .collect();
+
+        for var in assigned.iter() {
+            if expressions.iter().any(|expr| expr.uses(var)) {
+                let saved: Var = self.saved_var(var.width());
+                out.push(CStmt::Assign { dest: saved, value: Value::Expr(Expr::var(*var)) });
+                expressions = expressions.into_iter().map(|expr| replace_var(expr, *var, Expr::var(saved))).collect();
+            }
+        }
+
+        // Order the assignments so no register is overwritten before the
+        // others have read it, breaking cycles with a saved copy
+        let mut pending: Vec<(Var, Expr)> = assigned.iter().map(|var| (*var, state.remove(var).unwrap_or(Expr::var(*var)))).collect();
+
+        while !pending.is_empty() {
+            let ready: Option<usize> = (0..pending.len())
+                .find(|index| pending.iter().enumerate().all(|(other, (_, value))| other == *index || !value.uses(&pending[*index].0)));
+
+            match ready {
+                Some(index) => {
+                    let (dest, value): (Var, Expr) = pending.remove(index);
+                    out.push(CStmt::Assign { dest, value: Value::Expr(value) });
+                },
+                None => {
+                    let var: Var = pending[0].0;
+                    let saved: Var = self.saved_var(var.width());
+                    out.push(CStmt::Assign { dest: saved, value: Value::Expr(Expr::var(var)) });
+
+                    for (_, value) in pending.iter_mut().skip(1) {
+                        *value = replace_var(value.clone(), var, Expr::var(saved));
+                    }
+                }
+            }
+        }
+
+        expressions.into_iter()
+            .map(|mut expr| {
+                for (var, placeholder) in placeholders.iter() {
+                    expr = replace_var(expr, *placeholder, Expr::var(*var));
+                }
+                ir::simplify(expr)
+            })
+            .collect()
+    }
+
+
+    // Clean up the block graph before structuring: drop the branches to
+    // stack protector failures and merge conditions of the same target
+    // into short circuit expressions
+    fn simplify_graph(&self, mut nodes: BTreeMap<u64, Node>) -> BTreeMap<u64, Node> {
+        let failures: HashSet<u64> = self.function.blocks.values()
+            .filter(|block| block.statements().any(|statement| matches!(
+                statement,
+                Stmt::Call { target } if self.callee(target).is_some_and(|name| name.starts_with("__stack_chk_fail"))
+            )))
+            .map(|block| block.start)
+            .collect();
+
+        for node in nodes.values_mut() {
+            if let Exit::Branch { taken, not_taken, .. } = node.exit {
+                if failures.contains(&taken) {
+                    node.exit = Exit::Next(not_taken);
+                } else if failures.contains(&not_taken) {
+                    node.exit = Exit::Next(taken);
+                }
+            }
+        }
+
+        while let Some((start, merged, removed)) = self.short_circuit(&nodes) {
+            nodes.remove(&removed);
+            if let Some(node) = nodes.get_mut(&start) {
+                node.exit = merged;
+            }
+        }
+
+        nodes
+    }
+
+
+    // Find a branch whose side is an empty block branching to the same
+    // place as the first, e.g. the second test of a || b
+    fn short_circuit(&self, nodes: &BTreeMap<u64, Node>) -> Option<(u64, Exit, u64)> {
+        let mut predecessors: HashMap<u64, usize> = HashMap::new();
+
+        for node in nodes.values() {
+            for successor in node.successors() {
+                *predecessors.entry(successor).or_default() += 1;
+            }
+        }
+
+        for (start, node) in nodes.iter() {
+            let (condition, taken, not_taken) = match &node.exit {
+                Exit::Branch { condition, taken, not_taken } => (condition, *taken, *not_taken),
+                _ => continue
+            };
+
+            for (side, other, side_taken) in [(taken, not_taken, true), (not_taken, taken, false)] {
+                let second: &Node = match nodes.get(&side) {
+                    Some(second) => second,
+                    None => continue
+                };
+
+                if side == *start || side == self.function.entry || predecessors.get(&side) != Some(&1) || !second.statements.is_empty() {
+                    continue;
+                }
+
+                let (inner, inner_taken, inner_not_taken) = match &second.exit {
+                    Exit::Branch { condition, taken, not_taken } => (condition, *taken, *not_taken),
+                    _ => continue
+                };
+
+                // Condition for the first block to go to the shared target
+                let first: Expr = if side_taken { negate(condition.clone()) } else { condition.clone() };
+
+                let (second, rest): (Expr, u64) = if inner_taken == other {
+                    (inner.clone(), inner_not_taken)
+                } else if inner_not_taken == other {
+                    (negate(inner.clone()), inner_taken)
+                } else {
+                    continue;
+                };
+
+                if rest == other {
+                    continue;
+                }
+
+                let merged: Exit = Exit::Branch {
+                    condition: ir::simplify(Expr::binary(BinaryOp::Or, first, second)),
+                    taken: other,
+                    not_taken: rest
+                };
+
+                return Some((*start, merged, side));
+            }
+        }
+
+        None
+    }
+
+
+    // Replace locals that only hold a copy of a parameter with the
+    // parameter, as unoptimized code spills every argument to the stack
+    fn alias_parameters(&self, body: Vec<CStmt>) -> Vec<CStmt> {
+        let mut assignments: HashMap<Var, usize> = HashMap::new();
+        count_assignments(&body, &mut assignments);
+
+        let mut taken: BTreeSet<i64> = BTreeSet::new();
+        for statement in body.iter() {
+            statement.visit_exprs(&mut |expr| stack_addresses(expr, &mut taken));
+        }
+
+        let mut aliases: HashMap<Var, Expr> = HashMap::new();
+        let mut remaining: Vec<CStmt> = Vec::new();
+        let mut prologue: bool = true;
+
+        for statement in body {
+            prologue &= matches!(statement, CStmt::Assign { .. } | CStmt::Store { .. } | CStmt::Comment(_));
+
+            if prologue {
+                if let CStmt::Assign { dest, value: Value::Expr(value) } = &statement {
+                    let offset: Option<i64> = self.local_offset(dest);
+                    let copy: bool = match value {
+                        Expr::Var(var) => is_parameter(var),
+                        Expr::Truncate { operand, .. } => matches!(&**operand, Expr::Var(var) if is_parameter(var)),
+                        _ => false
+                    };
+
+                    if copy && assignments.get(dest) == Some(&1) && offset.is_some_and(|offset| !taken.contains(&offset)) {
+                        aliases.insert(*dest, value.clone());
+                        continue;
+                    }
+                }
+            }
+
+            remaining.push(statement);
+        }
+
+        remaining.into_iter()
+            .map(|statement| statement.map_exprs(&mut |expr| {
+                expr.map(&mut |node| match node {
+                    Expr::Var(var) if aliases.contains_key(&var) => aliases[&var].clone(),
+                    other => other
+                })
+            }))
+            .collect()
+    }
+
+
+    fn local_offset(&self, var: &Var) -> Option<i64> {
+        self.locals.iter().find(|(_, local)| *local == var).map(|((offset, _), _)| *offset)
+    }
+
+
+    // Print the function with its signature and declarations
+    fn print(&self, body: Vec<CStmt>, functions: &[Function], strings: &BTreeMap<u64, String>) -> String {
+        // Stack addresses that remain are taken with &
+        let mut addressed: BTreeSet<i64> = BTreeSet::new();
+        for statement in body.iter() {
+            statement.visit_exprs(&mut |expr| stack_addresses(expr, &mut addressed));
+        }
+
+        let addresses: Vec<i64> = addressed.iter().copied().collect();
+        let body: Vec<CStmt> = body.into_iter()
+            .map(|statement| statement.map_exprs(&mut |expr| {
+                rewrite(expr, &mut |node| {
+                    let offset: i64 = stack_offset(node)?;
+                    let index: usize = addresses.iter().position(|address| *address == offset)?;
+                    Some(Expr::var(Var::Temp(ADDRESS_BASE + index as u32, 64)))
+                })
+            }))
+            .collect();
+
+        // Widths each register and parameter is used at
+        let mut widths: HashMap<Var, BTreeSet<u32>> = HashMap::new();
+        let mut used: BTreeSet<Var> = BTreeSet::new();
+        let mut returned: Vec<Value> = Vec::new();
+        collect_usage(&body, &mut widths, &mut used, &mut returned);
+
+        let mut names: HashMap<Var, Name> = HashMap::new();
+        let mut declarations: Vec<(i64, String)> = Vec::new();
+        let mut parameters: u32 = 0;
+        let mut temps: u32 = 0;
+
+        let single_width = |var: &Var| -> u32 {
+            match widths.get(var).map(|set| set.iter().copied().collect::<Vec<u32>>()).as_deref() {
+                Some([width]) if [8, 16, 32, 64].contains(width) => *width,
+                _ => 64
+            }
+        };
+
+        for var in used.iter() {
+            let name: Name = match var {
+                Var::Register(register) => {
+                    let width: u32 = single_width(var);
+                    Name { text: register_name(*register, width), width }
+                },
+                Var::Flag(flag) => Name { text: flag.to_string().to_lowercase(), width: 1 },
+                Var::Temp(id, _) if *id >= ADDRESS_BASE => {
+                    let offset: i64 = addresses[(*id - ADDRESS_BASE) as usize];
+                    Name { text: format!("&{}", slot_name(offset)), width: 64 }
+                },
+                Var::Temp(id, width) if *id >= PLACEHOLDER_BASE => Name { text: var.to_string(), width: *width },
+                Var::Temp(id, width) if *id >= SAVED_BASE => Name { text: format!("saved_{}", id - SAVED_BASE), width: *width },
+                Var::Temp(id, width) if *id >= LOCAL_BASE => {
+                    let offset: i64 = self.local_offset(var).unwrap_or_default();
+                    let mut text: String = slot_name(offset);
+
+                    // The same slot used at several widths
+                    if self.locals.keys().any(|(other, other_width)| *other == offset && other_width != width) {
+                        text = format!("{}_{}", text, width);
+                    }
+                    Name { text, width: *width }
+                },
+                Var::Temp(id, _) if *id >= PARAMETER_BASE => {
+                    parameters = parameters.max(id - PARAMETER_BASE + 1);
+                    let width: u32 = single_width(var);
+                    Name { text: format!("param_{}", id - PARAMETER_BASE + 1), width }
+                },
+                Var::Temp(_, width) => {
+                    temps += 1;
+                    Name { text: format!("tmp_{}", temps), width: *width }
+                }
+            };
+
+            names.insert(*var, name);
+        }
+
+        // Declare every variable but the parameters, locals first from the
+        // top of the frame down
+        for var in used.iter() {
+            let name: &Name = &names[var];
+
+            match var {
+                Var::Temp(id, _) if *id >= ADDRESS_BASE || (*id >= PARAMETER_BASE && *id < LOCAL_BASE) => {},
+                Var::Temp(id, _) if *id >= LOCAL_BASE && *id < SAVED_BASE => {
+                    let offset: i64 = self.local_offset(var).unwrap_or_default();
+                    declarations.push((-offset, format!("{} {};", pseudoc::type_name(name.width), name.text)));
+                },
+                _ => declarations.push((i64::MAX, format!("{} {};", pseudoc::type_name(name.width), name.text)))
+            }
+        }
+
+        // Slots only used through their address, e.g. buffers passed to a
+        // call, are declared as byte arrays up to the next slot
+        let slots: BTreeSet<i64> = self.locals.keys().map(|(offset, _)| *offset).chain(addresses.iter().copied()).collect();
+
+        for offset in addresses.iter() {
+            let declared: bool = self.locals.iter().any(|((other, _), var)| other == offset && used.contains(var));
+
+            if !declared {
+                let next: i64 = slots.range(offset + 1..).next().copied().unwrap_or(0).max(offset + 1);
+                let size: i64 = if *offset < 0 { next - offset } else { 8 };
+                declarations.push((-offset, format!("char {}[{}];", slot_name(*offset), size)));
+            }
+        }
+
+        declarations.sort_by_key(|(key, _)| *key);
+
+        let printer: Printer = Printer {
+            names: &names,
+            symbols: self.symbols,
+            functions,
+            strings
+        };
+
+        let return_type: &str = if self.returns_value {
+            pseudoc::type_name(return_width(&returned, &names))
+        } else {
+            "void"
+        };
+
+        let parameter_list: Vec<String> = (0..parameters)
+            .map(|index| {
+                let var: Var = Var::Temp(PARAMETER_BASE + index, 64);
+                let width: u32 = names.get(&var).map_or(64, |name| name.width);
+                format!("{} param_{}", pseudoc::type_name(width), index + 1)
+            })
+            .collect();
+
+        let mut text: String = String::new();
+        text.push_str(&format!(
+            "{} {}({})\n{{\n",
+            return_type,
+            self.function.name,
+            if parameter_list.is_empty() { "void".to_string() } else { parameter_list.join(", ") }
+        ));
+
+        for (_, declaration) in declarations.iter() {
+            text.push_str(&format!("    {}\n", declaration));
+        }
+        if !declarations.is_empty() {
+            text.push('\n');
+        }
+
+        printer.statements(&body, 1, &mut text);
+        text.push_str("}\n");
+        text
+    }
+}
+
+
+// Follow the stack and frame pointers from the entry of the function
+fn stack_frames(function: &IrFunction) -> HashMap<u64, Frame> {
+    let mut frames: HashMap<u64, Frame> = HashMap::new();
+    let mut worklist: Vec<u64> = vec![function.entry];
+    let successors: HashMap<u64, Vec<u64>> = block_successors(function);
+
+    frames.insert(function.entry, Frame { rsp: Some(0), rbp: None });
+
+    while let Some(start) = worklist.pop() {
+        let block: &IrBlock = match function.blocks.get(&start) {
+            Some(block) => block,
+            None => continue
+        };
+
+        let frame: Frame = frames[&start];
+        let mut state: HashMap<Var, Expr> = HashMap::new();
+
+        state.insert(Var::Register(Register::RSP), frame.rsp.map_or(Expr::Undefined(64), stack_address));
+        if let Some(rbp) = frame.rbp {
+            state.insert(Var::Register(Register::RBP), stack_address(rbp));
+        }
+
+        for statement in block.statements() {
+            if let Stmt::Assign { dest, value } = statement {
+                let value: Expr = ir::simplify(replace_vars(value.clone(), &state));
+
+                if expression_size(&value) <= MAX_EXPRESSION_SIZE {
+                    state.insert(*dest, value);
+                } else {
+                    state.remove(dest);
+                }
+            }
+        }
+
+        let offset = |register: Register| state.get(&Var::Register(register)).and_then(stack_offset);
+        let exit: Frame = Frame { rsp: offset(Register::RSP), rbp: offset(Register::RBP) };
+
+        for to in successors.get(&start).into_iter().flatten() {
+            if !frames.contains_key(to) {
+                frames.insert(*to, exit);
+                worklist.push(*to);
+            }
+        }
+    }
+
+    frames
+}
+
+
+// Successors of each block within the function
+fn block_successors(function: &IrFunction) -> HashMap<u64, Vec<u64>> {
+    let mut successors: HashMap<u64, Vec<u64>> = HashMap::new();
+
+    for edge in function.edges.iter().filter(|edge| function.blocks.contains_key(&edge.to)) {
+        successors.entry(edge.from).or_default().push(edge.to);
+    }
+
+    successors
+}
+
+
+// Address of a stack slot, relative to the stack pointer on entry
+fn stack_address(offset: i64) -> Expr {
+    ir::simplify(Expr::binary(BinaryOp::Add, Expr::register(Register::RSP), Expr::constant(offset as u64, 64)))
+}
+
+
+// Offset of a stack slot address from the stack pointer on entry
+fn stack_offset(expr: &Expr) -> Option<i64> {
+    let rsp: Expr = Expr::register(Register::RSP);
+
+    match expr {
+        Expr::Var(Var::Register(Register::RSP)) => Some(0),
+        Expr::Binary { op: BinaryOp::Add, left, right } if **left == rsp => right.as_const().map(|value| value as i64),
+        Expr::Binary { op: BinaryOp::Sub, left, right } if **left == rsp => right.as_const().map(|value| (value as i64).wrapping_neg()),
+        _ => None
+    }
+}
+
+
+// Locals below the return address are named after their distance from
+// it, arguments passed on the stack after their offset above it
+fn slot_name(offset: i64) -> String {
+    if offset < 0 {
+        format!("local_{:x}", offset.unsigned_abs())
+    } else {
+        format!("stack_{:x}", offset)
+    }
+}
+
+
+// Name of a register narrowed to a width, e.g. eax for RAX at 32 bits
+fn register_name(register: Register, width: u32) -> String {
+    let high_bytes: [Register; 4] = [Register::AH, Register::BH, Register::CH, Register::DH];
+
+    let narrowed: Register = Register::values()
+        .find(|other| {
+            other.is_gpr() && other.full_register() == register && other.size() as u32 * 8 == width && !high_bytes.contains(other)
+        })
+        .unwrap_or(register);
+
+    format!("{:?}", narrowed).to_lowercase()
+}
+
+
+fn is_parameter(var: &Var) -> bool {
+    matches!(var, Var::Temp(id, _) if (PARAMETER_BASE..LOCAL_BASE).contains(id))
+}
+
+
+fn is_lifter_temp(var: &Var) -> bool {
+    matches!(var, Var::Temp(id, _) if *id < PARAMETER_BASE)
+}
+
+
+fn expression_size(expr: &Expr) -> usize {
+    let mut size: usize = 0;
+    expr.visit(&mut |_| size += 1);
+    size
+}
+
+
+fn uses_local(expr: &Expr) -> bool {
+    let mut found: bool = false;
+    expr.visit(&mut |node| found |= matches!(node, Expr::Var(Var::Temp(id, _)) if (LOCAL_BASE..SAVED_BASE).contains(id)));
+    found
+}
+
+
+// Whether an expression reads memory through the fs or gs segment
+fn reads_segment(expr: &Expr) -> bool {
+    let mut found: bool = false;
+
+    expr.visit(&mut |node| {
+        if let Expr::Load { address, .. } = node {
+            found |= address.uses(&Var::Register(Register::FS)) || address.uses(&Var::Register(Register::GS));
+        }
+    });
+
+    found
+}
+
+
+// Registers with a pending value that satisfy a predicate, when they are
+// still read later
+fn pending(state: &HashMap<Var, Expr>, live: &HashSet<Var>, predicate: impl Fn(&Expr) -> bool) -> Vec<Var> {
+    let mut vars: Vec<Var> = state.iter()
+        .filter(|(var, value)| live.contains(var) && **value != Expr::var(**var) && predicate(value))
+        .map(|(var, _)| *var)
+        .collect();
+
+    vars.sort();
+    vars
+}
+
+
+fn replace_vars(expr: Expr, state: &HashMap<Var, Expr>) -> Expr {
+    expr.map(&mut |node| match node {
+        Expr::Var(var) => state.get(&var).cloned().unwrap_or(Expr::Var(var)),
+        other => other
+    })
+}
+
+
+fn replace_var(expr: Expr, var: Var, value: Expr) -> Expr {
+    expr.map(&mut |node| match node {
+        Expr::Var(used) if used == var => value.clone(),
+        other => other
+    })
+}
+
+
+// Replace occurrences of a value, or of its narrower form when the value
+// is an extension, with a variable
+fn replace_value(expr: Expr, value: &Expr, var: Var) -> Expr {
+    rewrite(expr, &mut |node| {
+        if node == value {
+            return Some(Expr::var(var));
+        }
+
+        match value {
+            Expr::Extend { operand, signed: false, .. } if node == &**operand => Some(Expr::truncate(Expr::var(var), operand.width())),
+            _ => None
+        }
+    })
+}
+
+
+// Rewrite an expression from the top down, replacing the outermost nodes
+// the function gives a value for
+fn rewrite(expr: Expr, f: &mut dyn FnMut(&Expr) -> Option<Expr>) -> Expr {
+    if let Some(replaced) = f(&expr) {
+        return replaced;
+    }
+
+    match expr {
+        Expr::Load { address, width } => Expr::load(rewrite(*address, f), width),
+        Expr::Unary { op, operand } => Expr::unary(op, rewrite(*operand, f)),
+        Expr::Binary { op, left, right } => {
+            let left: Expr = rewrite(*left, f);
+            Expr::binary(op, left, rewrite(*right, f))
+        },
+        Expr::Extend { operand, width, signed } => Expr::Extend { operand: Box::new(rewrite(*operand, f)), width, signed },
+        Expr::Truncate { operand, width } => Expr::truncate(rewrite(*operand, f), width),
+        Expr::Select { condition, then, otherwise } => {
+            let condition: Expr = rewrite(*condition, f);
+            let then: Expr = rewrite(*then, f);
+            Expr::select(condition, then, rewrite(*otherwise, f))
+        },
+        leaf => leaf
+    }
+}
+
+
+// Offsets of the stack slots whose address an expression takes
+fn stack_addresses(expr: &Expr, offsets: &mut BTreeSet<i64>) {
+    rewrite(expr.clone(), &mut |node| {
+        let offset: i64 = stack_offset(node)?;
+        offsets.insert(offset);
+        Some(node.clone())
+    });
+}
+
+
+fn strip_extend(expr: Expr) -> Expr {
+    match expr {
+        Expr::Extend { operand, .. } => *operand,
+        other => other
+    }
+}
+
+
+fn negate(condition: Expr) -> Expr {
+    ir::simplify(Expr::unary(UnaryOp::Not, condition))
+}
+
+
+// Structures the block graph into loops and if statements. Blocks that
+// can not be placed are reached with a goto.
+struct Structurer<'a> {
+    nodes: &'a BTreeMap<u64, Node>,
+    entry: u64,
+    post_dominators: HashMap<u64, u64>,
+    loops: HashMap<u64, BTreeSet<u64>>,
+    loop_stack: Vec<LoopContext>,
+    emitted: HashSet<u64>,
+    // Blocks reached through indirect jumps, which keep their labels
+    labels: BTreeSet<u64>
+}
+
+
+struct LoopContext {
+    header: u64,
+    body: BTreeSet<u64>,
+    // Where the loop continues when it is done
+    follow: Option<u64>,
+    // Set while placing a path that leaves the loop early
+    escaped: bool
+}
+
+
+impl<'a> Structurer<'a> {
+    fn new(nodes: &'a BTreeMap<u64, Node>, entry: u64) -> Structurer<'a> {
+        let successors: HashMap<u64, Vec<u64>> = nodes.iter()
+            .map(|(start, node)| (*start, node.successors().into_iter().filter(|to| nodes.contains_key(to)).collect()))
+            .collect();
+
+        let dominators: HashMap<u64, u64> = immediate_dominators(entry, &successors);
+
+        // Post dominators are the dominators of the reversed graph, with
+        // every block that leaves the function flowing into one exit
+        let mut reversed: HashMap<u64, Vec<u64>> = HashMap::new();
+        for (start, targets) in successors.iter().filter(|(start, _)| dominators.contains_key(start)) {
+            if targets.is_empty() {
+                reversed.entry(EXIT).or_default().push(*start);
+            }
+            for target in targets {
+                reversed.entry(*target).or_default().push(*start);
+            }
+        }
+        let post_dominators: HashMap<u64, u64> = immediate_dominators(EXIT, &reversed);
+
+        let mut loops: HashMap<u64, BTreeSet<u64>> = HashMap::new();
+        let predecessors: HashMap<u64, Vec<u64>> = reversed;
+
+        for (start, targets) in successors.iter().filter(|(start, _)| dominators.contains_key(start)) {
+            for header in targets.iter().filter(|target| dominates(&dominators, **target, *start)) {
+                let body: &mut BTreeSet<u64> = loops.entry(*header).or_insert_with(|| BTreeSet::from([*header]));
+                let mut worklist: Vec<u64> = vec![*start];
+
+                while let Some(node) = worklist.pop() {
+                    if body.insert(node) {
+                        worklist.extend(predecessors.get(&node).into_iter().flatten().filter(|from| **from != EXIT));
+                    }
+                }
+            }
+        }
+
+        Structurer {
+            nodes,
+            entry,
+            post_dominators,
+            loops,
+            loop_stack: Vec::new(),
+            emitted: HashSet::new(),
+            labels: BTreeSet::new()
+        }
+    }
+
+
+    fn run(&mut self) -> Vec<CStmt> {
+        let mut body: Vec<CStmt> = self.structure(self.entry, None);
+
+        // Blocks only reached through indirect jumps follow the rest
+        let indirect: BTreeSet<u64> = self.nodes.values().flat_map(|node| node.indirect.iter().copied()).collect();
+
+        for target in indirect {
+            if !self.emitted.contains(&target) && self.nodes.contains_key(&target) {
+                self.labels.insert(target);
+                body.extend(self.structure(target, None));
+            }
+        }
+
+        body
+    }
+
+
+    // Structure the blocks from start until stop is reached or control
+    // leaves the region
+    fn structure(&mut self, start: u64, stop: Option<u64>) -> Vec<CStmt> {
+        let mut out: Vec<CStmt> = Vec::new();
+        let mut current: Option<u64> = Some(start);
+
+        while let Some(node) = current {
+            if Some(node) == stop {
+                break;
+            }
+
+            if let Some(jump) = self.loop_exit(node) {
+                out.extend(jump);
+                break;
+            }
+
+            if self.emitted.contains(&node) {
+                out.push(CStmt::Goto(node));
+                break;
+            }
+
+            current = if self.loops.contains_key(&node) {
+                self.structure_loop(node, &mut out)
+            } else {
+                self.structure_block(node, stop, true, &mut out)
+            };
+        }
+
+        out
+    }
+
+
+    // Place a block and the branches leaving it, returning where control
+    // continues afterwards
+    fn structure_block(&mut self, node: u64, stop: Option<u64>, label: bool, out: &mut Vec<CStmt>) -> Option<u64> {
+        self.emitted.insert(node);
+
+        // Control leaves the function for an address that is not a block
+        let block: &Node = match self.nodes.get(&node) {
+            Some(block) => block,
+            None => {
+                out.push(CStmt::Jump(Expr::constant(node, 64)));
+                return None;
+            }
+        };
+
+        if label {
+            out.push(CStmt::Label(node));
+        }
+
+        out.extend(block.statements.iter().cloned());
+
+        match block.exit.clone() {
+            Exit::Stop => None,
+            Exit::Next(next) => Some(next),
+            Exit::Branch { condition, taken, not_taken } => {
+                let join: Option<u64> = self.join(node);
+                let then: Vec<CStmt> = self.structure(taken, join.or(stop));
+                let otherwise: Vec<CStmt> = self.structure(not_taken, join.or(stop));
+
+                out.push(CStmt::If { condition, then, otherwise });
+                join
+            }
+        }
+    }
+
+
+    fn structure_loop(&mut self, header: u64, out: &mut Vec<CStmt>) -> Option<u64> {
+        let body: BTreeSet<u64> = self.loops[&header].clone();
+        let follow: Option<u64> = self.loop_follow(header, &body);
+
+        self.loop_stack.push(LoopContext { header, body, follow, escaped: false });
+
+        let mut statements: Vec<CStmt> = Vec::new();
+        if let Some(next) = self.structure_block(header, None, false, &mut statements) {
+            statements.extend(self.structure(next, None));
+        }
+
+        self.loop_stack.pop();
+
+        out.push(CStmt::Label(header));
+        out.push(CStmt::Loop { kind: LoopKind::While, condition: Expr::constant(1, 1), body: statements });
+        follow
+    }
+
+
+    // Continue, break or goto for a node that leaves the innermost loop
+    fn loop_exit(&mut self, node: u64) -> Option<Vec<CStmt>> {
+        let depth: usize = self.loop_stack.len();
+
+        for (level, context) in self.loop_stack.iter().enumerate().rev() {
+            let innermost: bool = level + 1 == depth;
+
+            if node == context.header || Some(node) == context.follow {
+                if innermost {
+                    return Some(vec![if node == context.header { CStmt::Continue } else { CStmt::Break }]);
+                }
+
+                return Some(vec![CStmt::Goto(node)]);
+            }
+        }
+
+        let context: &LoopContext = self.loop_stack.last()?;
+        if context.escaped || context.body.contains(&node) {
+            return None;
+        }
+
+        // Leaves the loop somewhere other than its follow, e.g. a return
+        self.loop_stack[depth - 1].escaped = true;
+        let mut path: Vec<CStmt> = self.structure(node, None);
+        self.loop_stack[depth - 1].escaped = false;
+
+        if !pseudoc::ends_in_jump(&path) {
+            path.push(CStmt::Break);
+        }
+        Some(path)
+    }
+
+
+    // Block both sides of a branch meet at, if it is inside the loop being
+    // structured
+    fn join(&self, node: u64) -> Option<u64> {
+        let join: u64 = *self.post_dominators.get(&node).filter(|join| **join != EXIT)?;
+
+        match self.loop_stack.last() {
+            Some(context) if !context.body.contains(&join) => None,
+            _ => Some(join)
+        }
+    }
+
+
+    // First block after the loop, where a break continues
+    fn loop_follow(&self, header: u64, body: &BTreeSet<u64>) -> Option<u64> {
+        let mut node: u64 = header;
+
+        while let Some(next) = self.post_dominators.get(&node).copied().filter(|next| *next != EXIT && *next != node) {
+            if !body.contains(&next) {
+                return Some(next);
+            }
+            node = next;
+        }
+
+        // Loops that return from within have no single exit, continue
+        // where the first block leaving the loop goes
+        body.iter()
+            .filter_map(|start| self.nodes.get(start))
+            .flat_map(|node| node.successors())
+            .find(|successor| !body.contains(successor) && self.nodes.contains_key(successor))
+    }
+}
+
+
+// Immediate dominators of the nodes reachable from a root, using the
+// iterative algorithm of Cooper, Harvey and Kennedy
+fn immediate_dominators(root: u64, successors: &HashMap<u64, Vec<u64>>) -> HashMap<u64, u64> {
+    // Reverse postorder
+    let mut order: Vec<u64> = Vec::new();
+    let mut visited: HashSet<u64> = HashSet::from([root]);
+    let mut stack: Vec<(u64, usize)> = vec![(root, 0)];
+
+    while let Some((node, next)) = stack.pop() {
+        let targets: &[u64] = successors.get(&node).map_or(&[], |targets| targets.as_slice());
+
+        match targets.get(next) {
+            Some(target) => {
+                stack.push((node, next + 1));
+                if visited.insert(*target) {
+                    stack.push((*target, 0));
+                }
+            },
+            None => order.push(node)
+        }
+    }
+    order.reverse();
+
+    let index: HashMap<u64, usize> = order.iter().enumerate().map(|(index, node)| (*node, index)).collect();
+    let mut predecessors: HashMap<u64, Vec<u64>> = HashMap::new();
+
+    for node in order.iter() {
+        for target in successors.get(node).into_iter().flatten() {
+            predecessors.entry(*target).or_default().push(*node);
+        }
+    }
+
+    let mut dominators: HashMap<u64, u64> = HashMap::from([(root, root)]);
+    let mut changed: bool = true;
+
+    while changed {
+        changed = false;
+
+        for node in order.iter().skip(1) {
+            let mut processed = predecessors[node].iter().filter(|predecessor| dominators.contains_key(predecessor));
+            let mut dominator: u64 = match processed.next() {
+                Some(first) => *first,
+                None => continue
+            };
+
+            for predecessor in processed {
+                let mut a: u64 = *predecessor;
+                let mut b: u64 = dominator;
+
+                while a != b {
+                    while index[&a] > index[&b] {
+                        a = dominators[&a];
+                    }
+                    while index[&b] > index[&a] {
+                        b = dominators[&b];
+                    }
+                }
+                dominator = a;
+            }
+
+            if dominators.get(node) != Some(&dominator) {
+                dominators.insert(*node, dominator);
+                changed = true;
+            }
+        }
+    }
+
+    dominators
+}
+
+
+// Whether a dominates b
+fn dominates(dominators: &HashMap<u64, u64>, a: u64, b: u64) -> bool {
+    let mut node: u64 = b;
+
+    loop {
+        if node == a {
+            return true;
+        }
+
+        match dominators.get(&node) {
+            Some(parent) if *parent != node => node = *parent,
+            _ => return false
+        }
+    }
+}
+
+
+fn goto_targets(statements: &[CStmt], targets: &mut BTreeSet<u64>) {
+    for statement in statements {
+        match statement {
+            CStmt::Goto(target) => {
+                targets.insert(*target);
+            },
+            CStmt::If { then, otherwise, .. } => {
+                goto_targets(then, targets);
+                goto_targets(otherwise, targets);
+            },
+            CStmt::Loop { body, .. } => goto_targets(body, targets),
+            _ => {}
+        }
+    }
+}
+
+
+// Remove the labels no goto jumps to
+fn strip_labels(statements: Vec<CStmt>, labels: &BTreeSet<u64>) -> Vec<CStmt> {
+    statements.into_iter()
+        .filter(|statement| !matches!(statement, CStmt::Label(target) if !labels.contains(target)))
+        .map(|statement| match statement {
+            CStmt::If { condition, then, otherwise } => CStmt::If {
+                condition,
+                then: strip_labels(then, labels),
+                otherwise: strip_labels(otherwise, labels)
+            },
+            CStmt::Loop { kind, condition, body } => CStmt::Loop { kind, condition, body: strip_labels(body, labels) },
+            other => other
+        })
+        .collect()
+}
+
+
+// Rewrite the structured statements into their most natural form: ifs are
+// flattened when a branch ends in a jump, and loops that test their
+// condition first or last become while and do while loops
+fn tidy(statements: Vec<CStmt>) -> Vec<CStmt> {
+    let mut out: Vec<CStmt> = Vec::new();
+
+    for statement in statements {
+        match statement {
+            CStmt::If { condition, then, otherwise } => push_if(&mut out, condition, tidy(then), tidy(otherwise)),
+            CStmt::Loop { body, .. } => out.push(make_loop(tidy(body))),
+            other => out.push(other)
+        }
+    }
+
+    out
+}
+
+
+// Add an if for a branch, preferring the side execution falls through to
+// in the then branch and hoisting a side that ends in a jump
+fn push_if(out: &mut Vec<CStmt>, condition: Expr, taken: Vec<CStmt>, not_taken: Vec<CStmt>) {
+    let ends_in_continue = |statements: &[CStmt]| matches!(statements.last(), Some(CStmt::Continue));
+
+    // Branches decided when the function was compiled
+    if let Some(value) = condition.as_const() {
+        out.extend(if value != 0 { taken } else { not_taken });
+        return;
+    }
+
+    if taken.is_empty() && not_taken.is_empty() {
+        return;
+    }
+
+    if not_taken.is_empty() {
+        out.push(CStmt::If { condition, then: taken, otherwise: Vec::new() });
+        return;
+    }
+
+    if taken.is_empty() {
+        out.push(CStmt::If { condition: negate(condition), then: not_taken, otherwise: Vec::new() });
+        return;
+    }
+
+    match (pseudoc::ends_in_jump(&taken), pseudoc::ends_in_jump(&not_taken)) {
+        (true, true) if ends_in_continue(&not_taken) && !ends_in_continue(&taken) => {
+            out.push(CStmt::If { condition, then: taken, otherwise: Vec::new() });
+            out.extend(not_taken);
+        },
+        (_, true) => {
+            out.push(CStmt::If { condition: negate(condition), then: not_taken, otherwise: Vec::new() });
+            out.extend(taken);
+        },
+        (true, false) => {
+            out.push(CStmt::If { condition, then: taken, otherwise: Vec::new() });
+            out.extend(not_taken);
+        },
+        (false, false) => {
+            out.push(CStmt::If { condition: negate(condition), then: not_taken, otherwise: taken });
+        }
+    }
+}
+
+
+// Pick the loop form from where the body tests for the exit
+fn make_loop(mut body: Vec<CStmt>) -> CStmt {
+    if matches!(body.last(), Some(CStmt::Continue)) {
+        body.pop();
+    }
+
+    let breaks = |statement: &CStmt| -> Option<Expr> {
+        match statement {
+            CStmt::If { condition, then, otherwise } if then.as_slice() == [CStmt::Break] && otherwise.is_empty() => Some(condition.clone()),
+            _ => None
+        }
+    };
+
+    if let Some(condition) = body.first().and_then(breaks) {
+        body.remove(0);
+        return CStmt::Loop { kind: LoopKind::While, condition: negate(condition), body };
+    }
+
+    if let Some(condition) = body.last().and_then(breaks) {
+        if !contains_continue(&body) {
+            body.pop();
+            return CStmt::Loop { kind: LoopKind::DoWhile, condition: negate(condition), body };
+        }
+    }
+
+    CStmt::Loop { kind: LoopKind::While, condition: Expr::constant(1, 1), body }
+}
+
+
+// Whether a continue in the statements applies to the enclosing loop
+fn contains_continue(statements: &[CStmt]) -> bool {
+    statements.iter().any(|statement| match statement {
+        CStmt::Continue => true,
+        CStmt::If { then, otherwise, .. } => contains_continue(then) || contains_continue(otherwise),
+        _ => false
+    })
+}
+
+
+// Return a value directly instead of assigning it to eax first
+fn fold_returns(statements: Vec<CStmt>) -> Vec<CStmt> {
+    let rax: Var = Var::Register(Register::RAX);
+    let mut out: Vec<CStmt> = Vec::new();
+
+    for statement in statements {
+        let statement: CStmt = match statement {
+            CStmt::If { condition, then, otherwise } => CStmt::If { condition, then: fold_returns(then), otherwise: fold_returns(otherwise) },
+            CStmt::Loop { kind, condition, body } => CStmt::Loop { kind, condition, body: fold_returns(body) },
+            other => other
+        };
+
+        if let CStmt::Return(Some(Value::Expr(returned))) = &statement {
+            let width: Option<u32> = match returned {
+                Expr::Var(var) if *var == rax => Some(64),
+                Expr::Truncate { operand, width } if **operand == Expr::var(rax) => Some(*width),
+                _ => None
+            };
+
+            if let (Some(width), Some(CStmt::Assign { dest, .. })) = (width, out.last()) {
+                if *dest == rax {
+                    let value: Value = match out.pop() {
+                        Some(CStmt::Assign { value: Value::Expr(value), .. }) => Value::Expr(ir::simplify(Expr::truncate(value, width))),
+                        Some(CStmt::Assign { value, .. }) => value,
+                        _ => unreachable!()
+                    };
+
+                    out.push(CStmt::Return(Some(value)));
+                    continue;
+                }
+            }
+        }
+
+        out.push(statement);
+    }
+
+    out
+}
+
+
+fn count_assignments(statements: &[CStmt], counts: &mut HashMap<Var, usize>) {
+    for statement in statements {
+        match statement {
+            CStmt::Assign { dest, .. } => *counts.entry(*dest).or_default() += 1,
+            CStmt::If { then, otherwise, .. } => {
+                count_assignments(then, counts);
+                count_assignments(otherwise, counts);
+            },
+            CStmt::Loop { body, .. } => count_assignments(body, counts),
+            _ => {}
+        }
+    }
+}
+
+
+// Collect the variables used, the widths registers and parameters are
+// read and written at, and the values returned
+fn collect_usage(statements: &[CStmt], widths: &mut HashMap<Var, BTreeSet<u32>>, used: &mut BTreeSet<Var>, returned: &mut Vec<Value>) {
+    for statement in statements {
+        match statement {
+            CStmt::Assign { dest, value } => {
+                used.insert(*dest);
+                if let Value::Expr(value) = value {
+                    widths.entry(*dest).or_default().insert(strip_extend(value.clone()).width());
+                }
+            },
+            CStmt::Return(Some(value)) => returned.push(value.clone()),
+            CStmt::If { then, otherwise, .. } => {
+                collect_usage(then, widths, used, returned);
+                collect_usage(otherwise, widths, used, returned);
+            },
+            CStmt::Loop { body, .. } => collect_usage(body, widths, used, returned),
+            _ => {}
+        }
+
+        // Nested statements are visited by the recursion above
+        let own: CStmt = match statement {
+            CStmt::If { condition, .. } | CStmt::Loop { condition, .. } => CStmt::Jump(condition.clone()),
+            CStmt::Return(Some(Value::Expr(Expr::Var(var)))) => {
+                used.insert(*var);
+                continue;
+            },
+            other => other.clone()
+        };
+
+        own.visit_exprs(&mut |expr| record_widths(expr, widths, used));
+    }
+}
+
+
+fn record_widths(expr: &Expr, widths: &mut HashMap<Var, BTreeSet<u32>>, used: &mut BTreeSet<Var>) {
+    match expr {
+        Expr::Truncate { operand, width } if matches!(**operand, Expr::Var(_)) => {
+            if let Expr::Var(var) = &**operand {
+                used.insert(*var);
+                widths.entry(*var).or_default().insert(*width);
+            }
+        },
+        Expr::Var(var) => {
+            used.insert(*var);
+            widths.entry(*var).or_default().insert(var.width());
+        },
+        Expr::Load { address: operand, .. } | Expr::Unary { operand, .. } | Expr::Extend { operand, .. } | Expr::Truncate { operand, .. } => {
+            record_widths(operand, widths, used);
+        },
+        Expr::Binary { left, right, .. } => {
+            record_widths(left, widths, used);
+            record_widths(right, widths, used);
+        },
+        Expr::Select { condition, then, otherwise } => {
+            record_widths(condition, widths, used);
+            record_widths(then, widths, used);
+            record_widths(otherwise, widths, used);
+        },
+        Expr::Const { .. } | Expr::Undefined(_) => {}
+    }
+}
+
+
+// Width of the return type, from the values returned
+fn return_width(returned: &[Value], names: &HashMap<Var, Name>) -> u32 {
+    returned.iter()
+        .map(|value| match value {
+            Value::Expr(Expr::Const { value, width }) => {
+                let signed: i64 = ir::sign_extend_value(*value, *width);
+                if signed >= i32::MIN as i64 && signed <= i32::MAX as i64 { 32 } else { 64 }
+            },
+            Value::Expr(Expr::Var(var)) => names.get(var).map_or(64, |name| name.width),
+            Value::Expr(expr) => expr.width(),
+            Value::Call(_) => 32
+        })
+        .max()
+        .unwrap_or(32)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::binary::{CodeRegion, Elf};
+    use crate::functions::{self, Function};
+    use crate::symbols::SymbolMap;
+    use crate::xrefs::{self, XrefKind};
+    use crate::{cfg, lift};
+
+    use super::decompile;
+
+
+    // Decompile a function of one of the test binaries, resolving strings
+    // the way the listing does. The function can be cut short at an end
+    // address.
+    fn decompile_function(path: &str, name: &str, end: Option<u64>) -> String {
+        let binary: Elf = Elf::from_file(path).unwrap();
+        let regions: Vec<CodeRegion> = binary.code_regions().unwrap();
+        let functions: Vec<Function> = functions::discover_functions(&binary, &regions, 0).unwrap();
+        let symbols: SymbolMap = SymbolMap::from_binary(&binary, &regions, &functions, 0).unwrap();
+
+        let strings: BTreeMap<u64, String> = xrefs::build_xrefs(&binary, &regions, None, 0)
+            .iter()
+            .filter(|xref| !matches!(xref.kind, XrefKind::Call | XrefKind::Jump | XrefKind::ConditionalJump))
+            .filter_map(|xref| binary.string_at(xref.to).map(|string| (xref.to, string)))
+            .collect();
+
+        let mut function: Function = functions.iter().find(|function| function.name == name).unwrap().clone();
+        function.end = end.unwrap_or(function.end);
+        let graph: cfg::ControlFlowGraph = cfg::build_cfg(&function, &regions, binary.bitness());
+
+        decompile(&lift::lift_function(&graph), &symbols, &functions, &strings)
+    }
+
+
+    #[test]
+    fn decompiles_is_prime() {
+        let expected: &str = "\
+int main(void)
+{
+    int local_14;
+    int local_18;
+    int local_1c;
+
+    local_14 = 0;
+    printf(\"Enter a positive integer: \");
+    __isoc99_scanf(\"%d\", &local_1c);
+    if (local_1c == 0 || local_1c == 1) {
+        local_14 = 1;
+    }
+    local_18 = 2;
+    while (local_18 <= local_1c / 2) {
+        if (local_1c % local_18 == 0) {
+            local_14 = 1;
+            break;
+        }
+        local_18 += 1;
+    }
+    if (local_14 == 0) {
+        printf(\"%d is a prime number.\", local_1c);
+    } else {
+        printf(\"%d is not a prime number.\", local_1c);
+    }
+    return 0;
+}
+";
+
+        assert_eq!(decompile_function("testing/isPrime", "main", None), expected);
+    }
+
+
+    // Functions cut short on a conditional branch, whose fall through or
+    // target is outside the function, leave it with a goto
+    #[test]
+    fn decompiles_function_ending_on_branch() {
+        let expected: &str = "\
+void main(void)
+{
+    int local_14;
+    int local_1c;
+
+    local_14 = 0;
+    printf(\"Enter a positive integer: \");
+    __isoc99_scanf(\"%d\", &local_1c);
+    if (local_1c == 0) {
+        goto *0x11e9;
+    }
+    if (local_1c != 1) {
+        goto *0x11f0;
+    }
+    goto *0x11e9;
+}
+";
+
+        assert_eq!(decompile_function("testing/isPrime", "main", Some(0x11e9)), expected);
+
+        // The loop condition jumps out of the function
+        let source: String = decompile_function("testing/isPrime", "main", Some(0x1224));
+        assert!(source.contains("    while (1) {\n        if (local_18 > local_1c / 2) {\n            goto *0x1224;\n"));
+    }
+}
@@ -0,0 +1,312 @@
+// disasm.rs
+// Created: 10/17/2026
+// Description: Disassembly routines for chisel. Wraps the icedx86 decoder
+//              to turn raw section bytes into instruction listings.
+
+use std::collections::BTreeMap;
+
+use iced_x86::{
+    Decoder, DecoderOptions, FastFormatter, FlowControl, Formatter, GasFormatter, Instruction,
+    IntelFormatter, MasmFormatter, NasmFormatter, OpKind, SymbolResolver
+};
+
+use crate::binary::CodeRegion;
+
+
+// Streaming linear sweep over a buffer of machine code. A single decoder
+// walks the whole buffer; bytes that do not decode are yielded one at a
+// time as invalid instructions, which the formatters print as "(bad)",
+// and decoding resynchronises on the following byte.
+pub struct LinearSweep<'a> {
+    decoder: Decoder<'a>,
+    ip: u64
+}
+
+
+impl<'a> LinearSweep<'a> {
+    // The ip argument is the address of the first byte in the buffer, and
+    // bitness selects 32-bit (x86) or 64-bit (x86-64) decoding.
+    pub fn new(code: &'a [u8], ip: u64, bitness: u32) -> LinearSweep<'a> {
+        LinearSweep {
+            decoder: Decoder::with_ip(bitness, code, ip, DecoderOptions::NONE),
+            ip
+        }
+    }
+}
+
+
+impl Iterator for LinearSweep<'_> {
+    type Item = Instruction;
+
+    fn next(&mut self) -> Option<Instruction> {
+        if !self.decoder.can_decode() {
+            return None;
+        }
+
+        let position: usize = self.decoder.position();
+        let mut instruction: Instruction = Instruction::default();
+        self.decoder.decode_out(&mut instruction);
+
+        if instruction.is_invalid() {
+            // Account for exactly one byte, then restart decoding after it
+            let address: u64 = self.ip.wrapping_add(position as u64);
+            instruction = Instruction::default();
+            instruction.set_len(1);
+            instruction.set_next_ip(address.wrapping_add(1));
+
+            // can_decode() guarantees position + 1 is within the buffer
+            let _ = self.decoder.set_position(position + 1);
+            self.decoder.set_ip(address.wrapping_add(1));
+        }
+
+        Some(instruction)
+    }
+}
+
+
+// Perform a linear sweep disassembly over a buffer of machine code,
+// collecting every instruction (including "(bad)" bytes) into a list.
+pub fn linear_sweep(code: &[u8], ip: u64, bitness: u32) -> Vec<Instruction> {
+    LinearSweep::new(code, ip, bitness).collect()
+}
+
+
+// Assembly syntax the listing is printed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    Nasm,
+    Intel,
+    Masm,
+    Gas,
+    Fast
+}
+
+
+// Instruction formatter for any of the supported syntaxes. The fast
+// formatter does not implement the icedx86 Formatter trait, so it is kept
+// as its own variant.
+pub enum AsmFormatter {
+    Standard(Box<dyn Formatter>),
+    Fast(Box<FastFormatter>)
+}
+
+
+impl AsmFormatter {
+    pub fn new(syntax: Syntax, symbol_resolver: Option<Box<dyn SymbolResolver>>) -> AsmFormatter {
+        let mut formatter: Box<dyn Formatter> = match syntax {
+            Syntax::Nasm => Box::new(NasmFormatter::with_options(symbol_resolver, None)),
+            Syntax::Intel => Box::new(IntelFormatter::with_options(symbol_resolver, None)),
+            Syntax::Masm => Box::new(MasmFormatter::with_options(symbol_resolver, None)),
+            Syntax::Gas => Box::new(GasFormatter::with_options(symbol_resolver, None)),
+            Syntax::Fast => {
+                // The default fast formatter always accepts a symbol resolver
+                let formatter: FastFormatter = FastFormatter::try_with_options(symbol_resolver).unwrap_or_default();
+                return AsmFormatter::Fast(Box::new(formatter));
+            }
+        };
+
+        // Formatting pattern partially borrowed from icedx86 docs
+        // https://docs.rs/iced-x86/latest/iced_x86/#disassemble-decode-and-format-instructions
+        if syntax == Syntax::Nasm {
+            formatter.options_mut().set_digit_separator("`");
+        }
+        formatter.options_mut().set_first_operand_char_index(10);
+
+        AsmFormatter::Standard(formatter)
+    }
+
+
+    pub fn format(&mut self, instruction: &Instruction, output: &mut String) {
+        match self {
+            AsmFormatter::Standard(formatter) => formatter.format(instruction, output),
+            AsmFormatter::Fast(formatter) => formatter.format(instruction, output)
+        }
+    }
+}
+
+
+// Result of a recursive descent pass. Holds every instruction that was
+// reached from the seed addresses, keyed by address.
+#[derive(Debug, Default)]
+pub struct CodeMap {
+    instructions: BTreeMap<u64, Instruction>
+}
+
+
+impl CodeMap {
+    pub fn instructions(&self) -> impl Iterator<Item = &Instruction> {
+        self.instructions.values()
+    }
+
+
+    pub fn instruction_at(&self, address: u64) -> Option<&Instruction> {
+        self.instructions.get(&address)
+    }
+
+
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+
+    // Instruction whose bytes cover the given address, if any
+    pub fn instruction_containing(&self, address: u64) -> Option<&Instruction> {
+        self.instructions.range(..=address)
+            .next_back()
+            .map(|(_, instruction)| instruction)
+            .filter(|instruction| address < instruction.next_ip())
+    }
+
+
+    pub fn is_code(&self, address: u64) -> bool {
+        self.instruction_containing(address).is_some()
+    }
+
+
+    // Address ranges [start, end) of a region that no reached instruction covers
+    pub fn data_ranges(&self, region: &CodeRegion) -> Vec<(u64, u64)> {
+        let mut ranges: Vec<(u64, u64)> = Vec::new();
+        let region_end: u64 = region.end();
+        let mut cursor: u64 = region.vaddr;
+
+        for (address, instruction) in self.instructions.range(region.vaddr..region_end) {
+            if *address > cursor {
+                ranges.push((cursor, *address));
+            }
+            cursor = cursor.max(instruction.next_ip());
+        }
+
+        if cursor < region_end {
+            ranges.push((cursor, region_end));
+        }
+
+        ranges
+    }
+
+
+    // Continue a recursive descent from more seed addresses, keeping the
+    // instructions already decoded
+    pub fn extend(&mut self, regions: &[CodeRegion], seeds: &[u64], bitness: u32) {
+        let mut worklist: Vec<u64> = seeds.iter().rev().copied().collect();
+
+        while let Some(start) = worklist.pop() {
+            let region: &CodeRegion = match find_region(regions, start) {
+                Some(region) => region,
+                None => continue
+            };
+
+            let mut decoder: Decoder = Decoder::with_ip(bitness, region.data, region.vaddr, DecoderOptions::NONE);
+            let mut instruction: Instruction = Instruction::default();
+
+            // find_region guarantees the start address lies within the region
+            let _ = decoder.set_position((start - region.vaddr) as usize);
+            decoder.set_ip(start);
+
+            while decoder.can_decode() {
+                let address: u64 = decoder.ip();
+
+                // Stop once we run into code that has already been decoded
+                if self.is_code(address) {
+                    break;
+                }
+
+                decoder.decode_out(&mut instruction);
+
+                if instruction.is_invalid() {
+                    break;
+                }
+
+                self.instructions.insert(address, instruction);
+
+                match instruction.flow_control() {
+                    FlowControl::UnconditionalBranch => {
+                        if let Some(target) = branch_target(&instruction) {
+                            worklist.push(target);
+                        }
+                        break;
+                    },
+                    FlowControl::ConditionalBranch | FlowControl::Call => {
+                        if let Some(target) = branch_target(&instruction) {
+                            worklist.push(target);
+                        }
+                    },
+                    FlowControl::IndirectBranch | FlowControl::Return | FlowControl::Exception => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+
+// Perform a recursive descent disassembly over the code regions of a
+// binary. Decoding starts at each seed address and follows fall-through,
+// direct jumps and direct calls; it stops at returns, indirect jumps,
+// undecodable bytes and addresses that fall inside an earlier instruction.
+pub fn recursive_descent(regions: &[CodeRegion], seeds: &[u64], bitness: u32) -> CodeMap {
+    let mut code_map: CodeMap = CodeMap::default();
+    code_map.extend(regions, seeds, bitness);
+    code_map
+}
+
+
+// Code region that holds the given address
+pub fn find_region<'a, 'b>(regions: &'a [CodeRegion<'b>], address: u64) -> Option<&'a CodeRegion<'b>> {
+    regions.iter().find(|region| address >= region.vaddr && address - region.vaddr < region.data.len() as u64)
+}
+
+
+// Destination of a direct jump or call
+pub fn branch_target(instruction: &Instruction) -> Option<u64> {
+    match instruction.op0_kind() {
+        OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => Some(instruction.near_branch_target()),
+        _ => None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use iced_x86::{Instruction, Mnemonic, Register};
+
+    use crate::binary::{CodeRegion, Elf};
+
+    use super::{linear_sweep, LinearSweep};
+
+
+    // i386 code decoded in 64-bit mode would read the lea as using rcx
+    #[test]
+    fn decodes_i386_code() {
+        let binary: Elf = Elf::from_file("testing/hello32").unwrap();
+        let regions: Vec<CodeRegion> = binary.code_regions().unwrap();
+        let text: &CodeRegion = regions.iter().find(|region| region.name == ".text").unwrap();
+
+        let instructions: Vec<Instruction> = linear_sweep(text.data, text.vaddr, binary.bitness());
+
+        assert_eq!(instructions[0].mnemonic(), Mnemonic::Endbr32);
+        assert_eq!(instructions[1].mnemonic(), Mnemonic::Lea);
+        assert_eq!(instructions[1].op0_register(), Register::ECX);
+        assert_eq!(instructions[1].memory_base(), Register::ESP);
+        assert_eq!(instructions[1].ip(), 0x1034);
+    }
+
+
+    // Each undecodable byte is reported on its own and decoding picks up
+    // again right after it
+    #[test]
+    fn resynchronises_after_invalid_bytes() {
+        // nop; (bad); mov rbp,rsp; a truncated two byte opcode
+        let code: [u8; 6] = [0x90, 0x06, 0x48, 0x89, 0xE5, 0x0F];
+
+        let decoded: Vec<(u64, usize, bool)> = LinearSweep::new(&code, 0x1000, 64)
+            .map(|instruction| (instruction.ip(), instruction.len(), instruction.is_invalid()))
+            .collect();
+
+        assert_eq!(decoded, vec![(0x1000, 1, false), (0x1001, 1, true), (0x1002, 3, false), (0x1005, 1, true)]);
+    }
+}
@@ -17,6 +17,7 @@ pub const ENDIAN_OFFSET: u8 = 0x05;     // Endian offset (1 - little, 2 - big);
 pub const ABI_OFFSET: u8 = 0x07;        // ABI identifier; 1 byte
 pub const TYPE_OFFSET: u8 = 0x10;       // Object type identifier; 2 bytes
 pub const MACHINE_OFFSET: u8 = 0x12;    // Instruction set type; 2 bytes
+pub const IDENT_SIZE: u8 = 0x10;        // Size of the e_ident identification block
 
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -37,6 +38,7 @@ pub const PHNUM_OFFSET: [u8; 2] = [0x2C, 0x38];        // Program header table n
 pub const SHENTSIZE_OFFSET: [u8; 2] = [0x2E, 0x3A];    // Size of section header table; 2 bytes
 pub const SHNUM_OFFSET: [u8; 2] = [0x30, 0x3C];        // Number of entries in section table pointer; 2 bytes
 pub const SHSTRNDX_OFFSET: [u8; 2] = [0x32, 0x3E];     // Index of section header that contains names; 2 bytes
+pub const FILE_HEADER_SIZE: [u8; 2] = [0x34, 0x40];    // Size of the complete file header
 
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -56,6 +58,7 @@ pub const PH_PADDR_OFFSET: [u8; 2] = [0x0C, 0x18];
 pub const PH_FILESZ_OFFSET: [u8; 2] = [0x10, 0x20];
 pub const PH_MEMSZ_OFFSET: [u8; 2] = [0x14, 0x28];
 pub const PH_ALIGN_OFFSET: [u8; 2] = [0x1C, 0x30];
+pub const PH_ENTRY_SIZE: [u8; 2] = [0x20, 0x38];
 
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -77,10 +80,193 @@ pub const SH_LINK_OFFSET: [u8; 2] = [0x18, 0x28];
 pub const SH_INFO_OFFSET: [u8; 2] = [0x1C, 0x2C];
 pub const SH_ADDRALIGN_OFFSET: [u8; 2] = [0x20, 0x30];
 pub const SH_ENTSIZE_OFFSET: [u8; 2] = [0x24, 0x38];
+pub const SH_ENTRY_SIZE: [u8; 2] = [0x28, 0x40];
 
 
 
-#[derive(Debug)]
+///////////////////////////////////////////////////////////////////////////////
+///
+/// Offsets for symbol table entry information.
+/// Arrayed offset are split by architecture:
+///      0 : x86
+///      1 : x86_64
+///
+///////////////////////////////////////////////////////////////////////////////
+
+pub const ST_NAME_OFFSET: u8 = 0x00;
+pub const ST_VALUE_OFFSET: [u8; 2] = [0x04, 0x08];
+pub const ST_SIZE_OFFSET: [u8; 2] = [0x08, 0x10];
+pub const ST_INFO_OFFSET: [u8; 2] = [0x0C, 0x04];
+pub const ST_OTHER_OFFSET: [u8; 2] = [0x0D, 0x05];
+pub const ST_SHNDX_OFFSET: [u8; 2] = [0x0E, 0x06];
+pub const ST_ENTRY_SIZE: [u8; 2] = [0x10, 0x18];
+
+
+
+///////////////////////////////////////////////////////////////////////////////
+///
+/// Offsets for dynamic section entry information.
+/// Arrayed offset are split by architecture:
+///      0 : x86
+///      1 : x86_64
+///
+///////////////////////////////////////////////////////////////////////////////
+
+pub const D_TAG_OFFSET: u8 = 0x00;
+pub const D_VAL_OFFSET: [u8; 2] = [0x04, 0x08];
+pub const DYN_ENTRY_SIZE: [u8; 2] = [0x08, 0x10];
+
+
+
+///////////////////////////////////////////////////////////////////////////////
+///
+/// Offsets for relocation entry information.
+/// Arrayed offset are split by architecture:
+///      0 : x86
+///      1 : x86_64
+///
+///////////////////////////////////////////////////////////////////////////////
+
+pub const R_OFFSET_OFFSET: u8 = 0x00;
+pub const R_INFO_OFFSET: [u8; 2] = [0x04, 0x08];
+pub const R_ADDEND_OFFSET: [u8; 2] = [0x08, 0x10];
+pub const REL_ENTRY_SIZE: [u8; 2] = [0x08, 0x10];
+pub const RELA_ENTRY_SIZE: [u8; 2] = [0x0C, 0x18];
+
+
+
+///////////////////////////////////////////////////////////////////////////////
+///
+/// Offsets for note entry information. Note headers use 4 byte words
+/// for both x86 and x86_64.
+///
+///////////////////////////////////////////////////////////////////////////////
+
+pub const N_NAMESZ_OFFSET: u8 = 0x00;
+pub const N_DESCSZ_OFFSET: u8 = 0x04;
+pub const N_TYPE_OFFSET: u8 = 0x08;
+pub const NOTE_HEADER_SIZE: u8 = 0x0C;
+
+
+
+///////////////////////////////////////////////////////////////////////////////
+///
+/// Object, section header and program header type identifiers.
+///
+///////////////////////////////////////////////////////////////////////////////
+
+pub const ET_REL: u16 = 0x01;
+pub const ET_EXEC: u16 = 0x02;
+pub const ET_DYN: u16 = 0x03;
+
+pub const SHN_UNDEF: u16 = 0x00;
+pub const SHN_ABS: u16 = 0xFFF1;
+pub const SHN_COMMON: u16 = 0xFFF2;
+
+pub const SHT_SYMTAB: u32 = 0x02;
+pub const SHT_STRTAB: u32 = 0x03;
+pub const SHT_RELA: u32 = 0x04;
+pub const SHT_DYNAMIC: u32 = 0x06;
+pub const SHT_NOTE: u32 = 0x07;
+pub const SHT_NOBITS: u32 = 0x08;
+pub const SHT_REL: u32 = 0x09;
+pub const SHT_DYNSYM: u32 = 0x0B;
+pub const SHT_INIT_ARRAY: u32 = 0x0E;
+pub const SHT_FINI_ARRAY: u32 = 0x0F;
+pub const SHT_PREINIT_ARRAY: u32 = 0x10;
+
+pub const PT_LOAD: u32 = 0x01;
+pub const PT_DYNAMIC: u32 = 0x02;
+pub const PT_NOTE: u32 = 0x04;
+pub const PT_GNU_EH_FRAME: u32 = 0x6474E550;
+
+pub const STT_NOTYPE: u8 = 0x00;
+pub const STT_OBJECT: u8 = 0x01;
+pub const STT_FUNC: u8 = 0x02;
+pub const STT_SECTION: u8 = 0x03;
+pub const STT_FILE: u8 = 0x04;
+
+pub const DT_NULL: u64 = 0x00;
+pub const DT_NEEDED: u64 = 0x01;
+pub const DT_PLTRELSZ: u64 = 0x02;
+pub const DT_PLTGOT: u64 = 0x03;
+pub const DT_STRTAB: u64 = 0x05;
+pub const DT_RELASZ: u64 = 0x08;
+pub const DT_RELAENT: u64 = 0x09;
+pub const DT_STRSZ: u64 = 0x0A;
+pub const DT_SYMENT: u64 = 0x0B;
+pub const DT_INIT: u64 = 0x0C;
+pub const DT_FINI: u64 = 0x0D;
+pub const DT_INIT_ARRAY: u64 = 0x19;
+pub const DT_FINI_ARRAY: u64 = 0x1A;
+pub const DT_INIT_ARRAYSZ: u64 = 0x1B;
+pub const DT_FINI_ARRAYSZ: u64 = 0x1C;
+pub const DT_PREINIT_ARRAY: u64 = 0x20;
+pub const DT_PREINIT_ARRAYSZ: u64 = 0x21;
+pub const DT_SONAME: u64 = 0x0E;
+pub const DT_RPATH: u64 = 0x0F;
+pub const DT_RELSZ: u64 = 0x12;
+pub const DT_RELENT: u64 = 0x13;
+pub const DT_PLTREL: u64 = 0x14;
+pub const DT_JMPREL: u64 = 0x17;
+pub const DT_RUNPATH: u64 = 0x1D;
+pub const DT_FLAGS: u64 = 0x1E;
+pub const DT_RELACOUNT: u64 = 0x6FFFFFF9;
+pub const DT_RELCOUNT: u64 = 0x6FFFFFFA;
+pub const DT_FLAGS_1: u64 = 0x6FFFFFFB;
+pub const DT_VERDEFNUM: u64 = 0x6FFFFFFD;
+pub const DT_VERNEEDNUM: u64 = 0x6FFFFFFF;
+
+pub const R_386_32: u32 = 0x01;
+pub const R_386_COPY: u32 = 0x05;
+pub const R_386_GLOB_DAT: u32 = 0x06;
+pub const R_386_JUMP_SLOT: u32 = 0x07;
+pub const R_386_RELATIVE: u32 = 0x08;
+pub const R_X86_64_64: u32 = 0x01;
+pub const R_X86_64_COPY: u32 = 0x05;
+pub const R_X86_64_GLOB_DAT: u32 = 0x06;
+pub const R_X86_64_JUMP_SLOT: u32 = 0x07;
+pub const R_X86_64_RELATIVE: u32 = 0x08;
+
+pub const NT_GNU_ABI_TAG: u32 = 0x01;
+pub const NT_GNU_BUILD_ID: u32 = 0x03;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 0x05;
+
+pub const GNU_PROPERTY_X86_ISA_1_USED_OLD: u32 = 0xC0000000;
+pub const GNU_PROPERTY_X86_ISA_1_NEEDED_OLD: u32 = 0xC0000001;
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xC0000002;
+pub const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xC0008002;
+pub const GNU_PROPERTY_X86_FEATURE_2_USED: u32 = 0xC0010001;
+pub const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xC0010002;
+
+pub const GNU_PROPERTY_X86_FEATURE_1_IBT: u32 = 0x01;
+pub const GNU_PROPERTY_X86_FEATURE_1_SHSTK: u32 = 0x02;
+
+
+///////////////////////////////////////////////////////////////////////////////
+///
+/// Pointer encodings used by .eh_frame and .eh_frame_hdr. The low nibble
+/// selects the value format, the high nibble how the value is applied.
+///
+///////////////////////////////////////////////////////////////////////////////
+
+pub const DW_EH_PE_ABSPTR: u8 = 0x00;
+pub const DW_EH_PE_ULEB128: u8 = 0x01;
+pub const DW_EH_PE_UDATA2: u8 = 0x02;
+pub const DW_EH_PE_UDATA4: u8 = 0x03;
+pub const DW_EH_PE_UDATA8: u8 = 0x04;
+pub const DW_EH_PE_SLEB128: u8 = 0x09;
+pub const DW_EH_PE_SDATA2: u8 = 0x0A;
+pub const DW_EH_PE_SDATA4: u8 = 0x0B;
+pub const DW_EH_PE_SDATA8: u8 = 0x0C;
+pub const DW_EH_PE_PCREL: u8 = 0x10;
+pub const DW_EH_PE_OMIT: u8 = 0xFF;
+
+pub const EH_FRAME_HDR_PTR_OFFSET: u8 = 0x04;   // Encoded pointer to .eh_frame in .eh_frame_hdr
+
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ArchitectureType {
     X86,
     X86_64,
@@ -88,13 +274,105 @@ pub enum ArchitectureType {
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EndianType {
     Big,
     Little,
     Unknown
 }
 
+// Section header sh_flags bits. Flags are stored as a raw bit set so that
+// OS and processor specific bits survive a round trip through the patcher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SectionFlags(pub u64);
+
+impl SectionFlags {
+    pub const WRITE: SectionFlags = SectionFlags(0x00000001);
+    pub const ALLOC: SectionFlags = SectionFlags(0x00000002);
+    pub const EXECINSTR: SectionFlags = SectionFlags(0x00000004);
+    pub const MERGE: SectionFlags = SectionFlags(0x00000010);
+    pub const STRINGS: SectionFlags = SectionFlags(0x00000020);
+    pub const INFO_LINK: SectionFlags = SectionFlags(0x00000040);
+    pub const LINK_ORDER: SectionFlags = SectionFlags(0x00000080);
+    pub const OS_NONCONFORMING: SectionFlags = SectionFlags(0x00000100);
+    pub const GROUP: SectionFlags = SectionFlags(0x00000200);
+    pub const TLS: SectionFlags = SectionFlags(0x00000400);
+    pub const COMPRESSED: SectionFlags = SectionFlags(0x00000800);
+    pub const GNU_RETAIN: SectionFlags = SectionFlags(0x00200000);
+    pub const MASKOS: SectionFlags = SectionFlags(0x0FF00000);
+    pub const MASKPROC: SectionFlags = SectionFlags(0xF0000000);
+    pub const X86_64_LARGE: SectionFlags = SectionFlags(0x10000000);
+    pub const EXCLUDE: SectionFlags = SectionFlags(0x80000000);
+
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+
+    pub fn contains(self, other: SectionFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl std::ops::BitOr for SectionFlags {
+    type Output = SectionFlags;
+
+    fn bitor(self, rhs: SectionFlags) -> SectionFlags {
+        SectionFlags(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitAnd for SectionFlags {
+    type Output = SectionFlags;
+
+    fn bitand(self, rhs: SectionFlags) -> SectionFlags {
+        SectionFlags(self.0 & rhs.0)
+    }
+}
+
+
+// Program header p_flags bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SegmentFlags(pub u32);
+
+impl SegmentFlags {
+    pub const X: SegmentFlags = SegmentFlags(0x1);
+    pub const W: SegmentFlags = SegmentFlags(0x2);
+    pub const R: SegmentFlags = SegmentFlags(0x4);
+
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub fn contains(self, other: SegmentFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl std::ops::BitOr for SegmentFlags {
+    type Output = SegmentFlags;
+
+    fn bitor(self, rhs: SegmentFlags) -> SegmentFlags {
+        SegmentFlags(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitAnd for SegmentFlags {
+    type Output = SegmentFlags;
+
+    fn bitand(self, rhs: SegmentFlags) -> SegmentFlags {
+        SegmentFlags(self.0 & rhs.0)
+    }
+}
+
+
 // TODO: Types in structs for holding addresses are most likely
 //       too small, increase to u32 maybe?
 //       Refer to structs in /usr/include/elf.h for this
@@ -125,7 +403,7 @@ pub struct FileHeader {
 pub struct ProgramHeader {
     pub id: u16,
     pub program_type: u32,
-    pub flags: u32,
+    pub flags: SegmentFlags,
     pub offset: u64,
     pub vaddr: u64,
     pub paddr: u64,
@@ -138,9 +416,10 @@ pub struct ProgramHeader {
 #[derive(Debug, Clone)]
 pub struct SectionHeader {
     pub id: u16,
+    pub name: String,
     pub name_idx: u32,
     pub section_type: u32,
-    pub flags: u64,
+    pub flags: SectionFlags,
     pub addr: u64,
     pub offset: u64,
     pub size: u64,
@@ -148,4 +427,79 @@ pub struct SectionHeader {
     pub info: u32,
     pub addralign: u64,
     pub entsize: u64
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub id: u32,
+    pub name: String,
+    pub name_idx: u32,
+    pub value: u64,
+    pub size: u64,
+    pub sym_type: u8,
+    pub binding: u8,
+    pub visibility: u8,
+    pub shndx: u16
+}
+
+
+#[derive(Debug, Clone)]
+pub struct DynamicEntry {
+    pub id: u32,
+    pub tag: u64,
+    pub value: u64,
+    pub string: Option<String>
+}
+
+
+// Address range covered by a frame description entry in .eh_frame
+#[derive(Debug, Clone)]
+pub struct FrameDescriptionEntry {
+    pub offset: u64,
+    pub pc_begin: u64,
+    pub pc_range: u64
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Relocation {
+    pub id: u32,
+    pub offset: u64,
+    pub info: u64,
+    pub rel_type: u32,
+    pub sym_idx: u32,
+    pub addend: Option<i64>,
+    pub symbol: Option<Symbol>
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Note {
+    pub name: String,
+    pub note_type: u32,
+    pub desc: Vec<u8>,
+    pub data: NoteData
+}
+
+
+#[derive(Debug, Clone)]
+pub enum NoteData {
+    BuildId(String),
+    AbiTag {
+        os: u32,
+        major: u32,
+        minor: u32,
+        patch: u32
+    },
+    GnuProperties(Vec<GnuProperty>),
+    Unknown
+}
+
+
+#[derive(Debug, Clone)]
+pub struct GnuProperty {
+    pub pr_type: u32,
+    pub value: u32,
+    pub data: Vec<u8>
 }
\ No newline at end of file
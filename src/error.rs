@@ -0,0 +1,127 @@
+// error.rs
+// Created: 10/17/2026
+// Description: Error type returned by chisel's parsing routines when a
+//              binary is malformed, truncated or otherwise unreadable.
+
+use std::fmt;
+use std::io;
+
+
+#[derive(Debug)]
+pub enum ChiselError {
+    // Underlying filesystem error while reading a binary or patch file
+    Io(io::Error),
+
+    // File does not start with the ELF magic number
+    BadMagic,
+
+    // EI_CLASS byte is neither ELFCLASS32 nor ELFCLASS64
+    InvalidClass(u8),
+
+    // EI_DATA byte is neither ELFDATA2LSB nor ELFDATA2MSB
+    InvalidDataEncoding(u8),
+
+    // A structure extends past the end of the file
+    Truncated {
+        structure: String,
+        offset: u64,
+        size: u64,
+        file_size: usize
+    },
+
+    // An index into a table refers to an entry that does not exist
+    BadIndex {
+        structure: String,
+        index: u64,
+        count: u64
+    },
+
+    // A string table offset lies outside of the table
+    StringOutOfBounds {
+        table: String,
+        index: u64,
+        size: u64
+    },
+
+    // A string runs off the end of its table without a NUL terminator
+    UnterminatedString {
+        table: String,
+        index: u64
+    },
+
+    // A value is stored in an encoding chisel does not understand
+    UnsupportedEncoding {
+        structure: String,
+        encoding: u8
+    },
+
+    // A section required for an operation is not present
+    MissingSection(String),
+
+    // A program segment required for an operation is not present
+    MissingSegment(String),
+
+    // A name given by the user matches no symbol or function
+    UnknownSymbol(String),
+
+    // The patch payload supplied to the patcher cannot be used
+    InvalidPatchFile(String),
+}
+
+
+impl fmt::Display for ChiselError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChiselError::Io(err) => write!(f, "I/O error: {}", err),
+            ChiselError::BadMagic => write!(f, "Could not find magic number, is this an ELF executable?"),
+            ChiselError::InvalidClass(class) => write!(f, "Invalid ELF class {:#04x} at offset 0x04", class),
+            ChiselError::InvalidDataEncoding(encoding) => write!(f, "Invalid ELF data encoding {:#04x} at offset 0x05", encoding),
+            ChiselError::Truncated { structure, offset, size, file_size } => write!(
+                f,
+                "Truncated {}: {} bytes at offset {:#x} extend past end of file ({} bytes)",
+                structure, size, offset, file_size
+            ),
+            ChiselError::BadIndex { structure, index, count } => write!(
+                f,
+                "Invalid {}: index {} is out of range ({} entries)",
+                structure, index, count
+            ),
+            ChiselError::StringOutOfBounds { table, index, size } => write!(
+                f,
+                "String offset {:#x} is outside of {} ({} bytes)",
+                index, table, size
+            ),
+            ChiselError::UnterminatedString { table, index } => write!(
+                f,
+                "Unterminated string at offset {:#x} in {}",
+                index, table
+            ),
+            ChiselError::UnsupportedEncoding { structure, encoding } => write!(
+                f,
+                "Unsupported encoding {:#04x} in {}",
+                encoding, structure
+            ),
+            ChiselError::MissingSection(name) => write!(f, "Binary has no {} section", name),
+            ChiselError::MissingSegment(name) => write!(f, "Binary has no {} segment", name),
+            ChiselError::UnknownSymbol(name) => write!(f, "No symbol or function named '{}'", name),
+            ChiselError::InvalidPatchFile(path) => write!(f, "Patch file '{}' is invalid or cannot be read", path),
+        }
+    }
+}
+
+
+impl std::error::Error for ChiselError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ChiselError::Io(err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+
+impl From<io::Error> for ChiselError {
+    fn from(err: io::Error) -> ChiselError {
+        ChiselError::Io(err)
+    }
+}
@@ -0,0 +1,160 @@
+// export.rs
+// Created: 10/17/2026
+// Description: Graphviz DOT and JSON export of control flow graphs and the
+//              program call graph.
+
+use crate::cfg::{CallGraph, ControlFlowGraph, EdgeKind};
+use crate::disasm::AsmFormatter;
+
+
+// Render a function's control flow graph as a Graphviz digraph. Each block
+// is labelled with its instructions as produced by the given formatter.
+pub fn cfg_to_dot(graph: &ControlFlowGraph, formatter: &mut AsmFormatter) -> String {
+    let mut dot: String = String::new();
+    let mut output: String = String::new();
+
+    dot.push_str(&format!("digraph \"{}\" {{\n", escape_dot(&graph.name)));
+    dot.push_str("\tnode [shape=box, fontname=\"monospace\"];\n");
+
+    for block in graph.blocks.values() {
+        let mut label: String = format!("block_{:x}:\\l", block.start);
+
+        for instruction in block.instructions.iter() {
+            output.clear();
+            formatter.format(instruction, &mut output);
+            label.push_str(&format!("{:016X}  {}\\l", instruction.ip(), escape_dot(&output)));
+        }
+
+        dot.push_str(&format!("\tb_{:x} [label=\"{}\"];\n", block.start, label));
+    }
+
+    for edge in graph.edges.iter() {
+        let style: &str = match edge.kind {
+            EdgeKind::ConditionalTaken => "color=green",
+            EdgeKind::ConditionalNotTaken => "color=red",
+            EdgeKind::Unconditional => "color=blue",
+            EdgeKind::FallThrough => "color=black",
+            EdgeKind::CallReturn => "color=black, style=dashed"
+        };
+
+        dot.push_str(&format!(
+            "\tb_{:x} -> b_{:x} [{}, label=\"{}\"];\n",
+            edge.from, edge.to, style, edge.kind
+        ));
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+
+// Render the call graph as a Graphviz digraph with one node per function
+pub fn call_graph_to_dot(call_graph: &CallGraph) -> String {
+    let mut dot: String = String::new();
+
+    dot.push_str("digraph \"call_graph\" {\n");
+    dot.push_str("\tnode [shape=box, fontname=\"monospace\"];\n");
+
+    for (address, name) in call_graph.nodes.iter() {
+        dot.push_str(&format!("\tf_{:x} [label=\"{}\\n{:#x}\"];\n", address, escape_dot(name), address));
+    }
+
+    for (caller, callee) in call_graph.edges.iter() {
+        dot.push_str(&format!("\tf_{:x} -> f_{:x};\n", caller, callee));
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+
+// Render a set of control flow graphs as a JSON array
+pub fn cfgs_to_json(graphs: &[ControlFlowGraph], formatter: &mut AsmFormatter) -> String {
+    let mut output: String = String::new();
+    let mut functions: Vec<String> = Vec::new();
+
+    for graph in graphs {
+        let mut blocks: Vec<String> = Vec::new();
+
+        for block in graph.blocks.values() {
+            let mut instructions: Vec<String> = Vec::new();
+
+            for instruction in block.instructions.iter() {
+                output.clear();
+                formatter.format(instruction, &mut output);
+
+                instructions.push(format!(
+                    "{{\"address\":{},\"length\":{},\"text\":\"{}\"}}",
+                    instruction.ip(), instruction.len(), escape_json(&output)
+                ));
+            }
+
+            blocks.push(format!(
+                "{{\"start\":{},\"end\":{},\"instructions\":[{}]}}",
+                block.start, block.end, instructions.join(",")
+            ));
+        }
+
+        let edges: Vec<String> = graph.edges.iter()
+            .map(|edge| format!(
+                "{{\"from\":{},\"to\":{},\"kind\":\"{}\"}}",
+                edge.from, edge.to, edge.kind
+            ))
+            .collect();
+
+        functions.push(format!(
+            "{{\"name\":\"{}\",\"entry\":{},\"blocks\":[{}],\"edges\":[{}]}}",
+            escape_json(&graph.name), graph.entry, blocks.join(","), edges.join(",")
+        ));
+    }
+
+    format!("[{}]\n", functions.join(","))
+}
+
+
+// Render the call graph as a JSON object of nodes and caller/callee edges
+pub fn call_graph_to_json(call_graph: &CallGraph) -> String {
+    let nodes: Vec<String> = call_graph.nodes.iter()
+        .map(|(address, name)| format!("{{\"address\":{},\"name\":\"{}\"}}", address, escape_json(name)))
+        .collect();
+
+    let edges: Vec<String> = call_graph.edges.iter()
+        .map(|(caller, callee)| format!("{{\"caller\":{},\"callee\":{}}}", caller, callee))
+        .collect();
+
+    format!("{{\"nodes\":[{}],\"edges\":[{}]}}\n", nodes.join(","), edges.join(","))
+}
+
+
+// Name usable as a file name for a function's exported graph
+pub fn graph_file_stem(graph: &ControlFlowGraph) -> String {
+    let name: String = graph.name.chars()
+        .map(|character| if character.is_ascii_alphanumeric() || "_.@-".contains(character) { character } else { '_' })
+        .collect();
+
+    format!("{}_{:x}", name, graph.entry)
+}
+
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+
+pub fn escape_json(text: &str) -> String {
+    let mut escaped: String = String::new();
+
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c)
+        }
+    }
+
+    escaped
+}
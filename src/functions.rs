@@ -0,0 +1,411 @@
+// functions.rs
+// Created: 10/17/2026
+// Description: Function boundary discovery. Combines symbols, PLT stubs,
+//              .eh_frame FDE ranges, direct call targets and prologue
+//              patterns to recover the functions of stripped and
+//              unstripped binaries.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use iced_x86::{FlowControl, Instruction};
+
+use crate::binary::{CodeRegion, Elf};
+use crate::disasm;
+use crate::elf;
+use crate::error::ChiselError;
+use crate::symbols;
+
+
+// Evidence a function start was recovered from, strongest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FunctionSource {
+    Symbol,
+    PltStub,
+    EntryPoint,
+    // DT_INIT/DT_FINI and the init, fini and preinit array entries
+    Initializer,
+    FrameEntry,
+    CallTarget,
+    // Unconditional jumps out of a function, e.g. tail calls
+    JumpTarget,
+    Prologue
+}
+
+
+impl fmt::Display for FunctionSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            FunctionSource::Symbol => "SYMBOL",
+            FunctionSource::PltStub => "PLT",
+            FunctionSource::EntryPoint => "ENTRY",
+            FunctionSource::Initializer => "INIT",
+            FunctionSource::FrameEntry => "EH_FRAME",
+            FunctionSource::CallTarget => "CALL",
+            FunctionSource::JumpTarget => "JUMP",
+            FunctionSource::Prologue => "PROLOGUE"
+        })
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    pub start: u64,
+    pub end: u64,
+    pub source: FunctionSource
+}
+
+
+impl Function {
+    pub fn size(&self) -> u64 {
+        self.end - self.start
+    }
+
+
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.start && address < self.end
+    }
+}
+
+
+// A function start candidate collected while scanning the binary
+struct Candidate {
+    name: Option<String>,
+    end: Option<u64>,
+    source: FunctionSource
+}
+
+
+// Prologue byte patterns. Each starts a function on a frame pointer setup,
+// optionally preceded by an endbr instruction.
+const PROLOGUES_64: [&[u8]; 2] = [
+    &[0xF3, 0x0F, 0x1E, 0xFA, 0x55, 0x48, 0x89, 0xE5],     // endbr64; push rbp; mov rbp,rsp
+    &[0x55, 0x48, 0x89, 0xE5],                             // push rbp; mov rbp,rsp
+];
+
+const PROLOGUES_32: [&[u8]; 2] = [
+    &[0xF3, 0x0F, 0x1E, 0xFB, 0x55, 0x89, 0xE5],           // endbr32; push ebp; mov ebp,esp
+    &[0x55, 0x89, 0xE5],                                   // push ebp; mov ebp,esp
+];
+
+const ENDBR_64: &[u8] = &[0xF3, 0x0F, 0x1E, 0xFA];
+const ENDBR_32: &[u8] = &[0xF3, 0x0F, 0x1E, 0xFB];
+
+
+// Recover the functions held in the code regions of a binary. Addresses in
+// the binary are shifted by load_bias to match the (possibly rebased)
+// regions. Functions are returned sorted by start address.
+pub fn discover_functions(binary: &Elf, regions: &[CodeRegion], load_bias: u64) -> Result<Vec<Function>, ChiselError> {
+    let mut candidates: BTreeMap<u64, Candidate> = BTreeMap::new();
+    let bitness: u32 = binary.bitness();
+
+    // Symbols give both a name and, usually, a size
+    for symbol in binary.symbols()?.iter().chain(binary.dynamic_symbols()?.iter()) {
+        if symbol.sym_type != elf::STT_FUNC || symbol.shndx == elf::SHN_UNDEF {
+            continue;
+        }
+
+        let start: u64 = symbol.value.wrapping_add(load_bias);
+        // Sizes that run past the end of the address space are ignored
+        let end: Option<u64> = if symbol.size != 0 { start.checked_add(symbol.size) } else { None };
+        add_candidate(&mut candidates, regions, start, Some(symbol.name.clone()), end, FunctionSource::Symbol);
+    }
+
+    // Stubs that call imported functions through the PLT. Lazy stubs carry
+    // code after their jump, so each one runs up to the next stub.
+    let mut stubs: Vec<(u64, String)> = symbols::plt_stubs(binary, regions, load_bias)?;
+    stubs.extend(symbols::lazy_plt_stubs(binary, regions)?);
+    stubs.sort_unstable();
+
+    for (index, (start, name)) in stubs.iter().enumerate() {
+        let region_end: Option<u64> = disasm::find_region(regions, *start)
+            .map(|region| region.end());
+
+        let end: Option<u64> = match stubs.get(index + 1) {
+            Some((next, _)) if Some(*next) < region_end && next > start => Some(*next),
+            _ => region_end
+        };
+
+        add_candidate(&mut candidates, regions, *start, Some(name.clone()), end, FunctionSource::PltStub);
+    }
+
+    // The entry point, DT_INIT/DT_FINI and the init/fini array entries
+    // are all called by the loader or the C runtime
+    // The entry point is only named _start when no symbol has the name,
+    // as it need not be where the _start symbol is
+    let entry: u64 = binary.file_header().entryoff.wrapping_add(load_bias);
+    let entry_name: String = if candidates.values().any(|candidate| candidate.name.as_deref() == Some("_start")) {
+        format!("entry_{:x}", entry)
+    } else {
+        "_start".to_string()
+    };
+    add_candidate(&mut candidates, regions, entry, Some(entry_name), None, FunctionSource::EntryPoint);
+
+    let seeds: Vec<u64> = binary.code_seeds()?
+        .iter()
+        .map(|seed| seed.wrapping_add(load_bias))
+        .collect();
+
+    for seed in seeds.iter() {
+        add_candidate(&mut candidates, regions, *seed, None, None, FunctionSource::Initializer);
+    }
+
+    // FDEs are only a hint, so a malformed .eh_frame is ignored
+    for frame in binary.frame_entries().unwrap_or_default() {
+        let start: u64 = frame.pc_begin.wrapping_add(load_bias);
+        add_candidate(&mut candidates, regions, start, None, start.checked_add(frame.pc_range), FunctionSource::FrameEntry);
+    }
+
+    // Follow the code reachable from what we know so far, collecting the
+    // targets of direct calls as new function starts
+    let starts: Vec<u64> = candidates.keys().copied().collect();
+    let code_map: disasm::CodeMap = disasm::recursive_descent(regions, &starts, bitness);
+
+    for instruction in code_map.instructions() {
+        if instruction.flow_control() != FlowControl::Call {
+            continue;
+        }
+
+        if let Some(target) = disasm::branch_target(instruction) {
+            add_candidate(&mut candidates, regions, target, None, None, FunctionSource::CallTarget);
+        }
+    }
+
+    // Frame pointer prologues in code nothing else reached, and the targets
+    // of jumps that leave the function they are made from. Each function
+    // found this way can end right before another, so repeat until no new
+    // starts turn up. The code map is extended from the new starts only,
+    // and the final pass leaves every start decoded, which the function
+    // ends below are derived from.
+    let mut code_map: disasm::CodeMap = code_map;
+
+    loop {
+        let known: usize = candidates.len();
+
+        for start in find_prologues(regions, &code_map, bitness) {
+            add_candidate(&mut candidates, regions, start, None, None, FunctionSource::Prologue);
+        }
+
+        for start in find_tail_calls(&candidates, regions, &code_map, bitness) {
+            add_candidate(&mut candidates, regions, start, None, None, FunctionSource::JumpTarget);
+        }
+
+        let starts: Vec<u64> = candidates.keys()
+            .filter(|start| code_map.instruction_at(**start).is_none())
+            .copied()
+            .collect();
+        code_map.extend(regions, &starts, bitness);
+
+        if candidates.len() == known {
+            break;
+        }
+    }
+
+    let starts: Vec<u64> = candidates.keys().copied().collect();
+
+    let mut functions: Vec<Function> = Vec::new();
+
+    for (index, (start, candidate)) in candidates.iter().enumerate() {
+        let region_end: u64 = match disasm::find_region(regions, *start) {
+            Some(region) => region.end(),
+            None => continue
+        };
+
+        // A function never runs into the next one or off its region
+        let limit: u64 = starts.get(index + 1).copied().unwrap_or(u64::MAX).min(region_end);
+
+        let end: u64 = match candidate.end {
+            Some(end) if end > *start => end.min(limit),
+            _ => code_map.instructions()
+                .skip_while(|instruction| instruction.ip() < *start)
+                .take_while(|instruction| instruction.ip() < limit)
+                .map(|instruction| instruction.next_ip())
+                .max()
+                .unwrap_or(limit)
+                .min(limit)
+        };
+
+        functions.push(Function {
+            name: candidate.name.clone().unwrap_or_else(|| format!("sub_{:x}", start)),
+            start: *start,
+            end,
+            source: candidate.source
+        });
+    }
+
+    Ok(functions)
+}
+
+
+// Function whose range holds the given address
+pub fn function_containing(functions: &[Function], address: u64) -> Option<&Function> {
+    let index: usize = functions.partition_point(|function| function.start <= address);
+
+    if index == 0 {
+        return None;
+    }
+
+    Some(&functions[index - 1]).filter(|function| function.contains(address))
+}
+
+
+// Record a function start, merging it with what is already known about
+// the address. Names and ends are kept from the strongest source.
+fn add_candidate(
+    candidates: &mut BTreeMap<u64, Candidate>,
+    regions: &[CodeRegion],
+    start: u64,
+    name: Option<String>,
+    end: Option<u64>,
+    source: FunctionSource) {
+
+    if disasm::find_region(regions, start).is_none() {
+        return;
+    }
+
+    let candidate: &mut Candidate = candidates.entry(start).or_insert(Candidate {
+        name: None,
+        end: None,
+        source
+    });
+
+    if candidate.name.is_none() {
+        candidate.name = name;
+    }
+    if candidate.end.is_none() {
+        candidate.end = end;
+    }
+    candidate.source = candidate.source.min(source);
+}
+
+
+// Targets of unconditional jumps that leave the known extent of the
+// function they are made from. Only 16 byte aligned targets that begin
+// with an endbr or a prologue, or that no instruction falls through to,
+// are taken, since a function whose end is not known yet may only look
+// like it is being left.
+fn find_tail_calls(
+    candidates: &BTreeMap<u64, Candidate>,
+    regions: &[CodeRegion],
+    code_map: &disasm::CodeMap,
+    bitness: u32) -> Vec<u64> {
+
+    let mut targets: Vec<u64> = Vec::new();
+    let prologues: &[&[u8]] = if bitness == 64 { &PROLOGUES_64 } else { &PROLOGUES_32 };
+    let endbr: &[u8] = if bitness == 64 { ENDBR_64 } else { ENDBR_32 };
+
+    for instruction in code_map.instructions() {
+        if instruction.flow_control() != FlowControl::UnconditionalBranch {
+            continue;
+        }
+
+        let target: u64 = match disasm::branch_target(instruction) {
+            Some(target) if target.is_multiple_of(16) => target,
+            _ => continue
+        };
+
+        let (start, candidate): (&u64, &Candidate) = match candidates.range(..=instruction.ip()).next_back() {
+            Some(entry) => entry,
+            None => continue
+        };
+
+        // Functions without a known end run up to the next start
+        let end: u64 = candidate.end.unwrap_or_else(|| {
+            candidates.range(start.saturating_add(1)..).next().map_or(u64::MAX, |(next, _)| *next)
+        });
+
+        if target >= *start && target < end {
+            continue;
+        }
+
+        let region: &CodeRegion = match disasm::find_region(regions, target) {
+            Some(region) => region,
+            None => continue
+        };
+
+        let code: &[u8] = &region.data[(target - region.vaddr) as usize..];
+
+        let falls_through: bool = code_map.instruction_containing(target.wrapping_sub(1))
+            .is_some_and(|previous: &Instruction| !matches!(
+                previous.flow_control(),
+                FlowControl::Return | FlowControl::UnconditionalBranch | FlowControl::IndirectBranch
+            ));
+
+        if code.starts_with(endbr) || prologues.iter().any(|prologue| code.starts_with(prologue)) || !falls_through {
+            targets.push(target);
+        }
+    }
+
+    targets
+}
+
+
+// Scan the regions for prologue patterns that begin on a 16 byte boundary
+// or straight after a return or jump, ignoring bytes already decoded as the middle
+// of another instruction
+fn find_prologues(regions: &[CodeRegion], code_map: &disasm::CodeMap, bitness: u32) -> Vec<u64> {
+    let mut starts: Vec<u64> = Vec::new();
+    let prologues: &[&[u8]] = if bitness == 64 { &PROLOGUES_64 } else { &PROLOGUES_32 };
+
+    for region in regions {
+        for offset in 0..region.data.len() {
+            let address: u64 = region.vaddr + offset as u64;
+
+            if !prologues.iter().any(|prologue| region.data[offset..].starts_with(prologue)) {
+                continue;
+            }
+
+            // Skip the bare push/mov pattern that follows a matched endbr
+            if offset >= 4 && prologues.iter().any(|prologue| region.data[offset - 4..].starts_with(prologue)) {
+                continue;
+            }
+
+            let after_exit: bool = code_map.instruction_containing(address.wrapping_sub(1))
+                .is_some_and(|instruction: &Instruction| matches!(
+                    instruction.flow_control(),
+                    FlowControl::Return | FlowControl::UnconditionalBranch | FlowControl::IndirectBranch
+                ));
+
+            if !address.is_multiple_of(16) && !after_exit {
+                continue;
+            }
+
+            if code_map.is_code(address) && code_map.instruction_at(address).is_none() {
+                continue;
+            }
+
+            starts.push(address);
+        }
+    }
+
+    starts
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::binary::{CodeRegion, Elf};
+
+    use super::{discover_functions, Function, FunctionSource};
+
+
+    fn find(functions: &[Function], start: u64) -> &Function {
+        functions.iter().find(|function| function.start == start).unwrap()
+    }
+
+
+    // frame_dummy tail calls register_tm_clones, which stripped hello has
+    // no other evidence for
+    #[test]
+    fn splits_functions_at_tail_calls() {
+        let binary: Elf = Elf::from_file("testing/hello.stripped").unwrap();
+        let regions: Vec<CodeRegion> = binary.code_regions().unwrap();
+        let functions: Vec<Function> = discover_functions(&binary, &regions, 0).unwrap();
+
+        assert_eq!(find(&functions, 0x1090).end, 0x10b9);
+        assert_eq!(find(&functions, 0x10c0).end, 0x10f9);
+        assert_eq!(find(&functions, 0x10c0).source, FunctionSource::JumpTarget);
+    }
+}
@@ -0,0 +1,944 @@
+// ir.rs
+// Created: 10/17/2026
+// Description: Intermediate representation for analysis passes. Each machine
+//              instruction becomes a short list of statements that spell out
+//              its register, memory and flag effects, so passes are written
+//              against a handful of expressions instead of every opcode.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use iced_x86::Register;
+
+use crate::cfg::Edge;
+
+
+// Status flags tracked by the IR. Parity and auxiliary carry are not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Flag {
+    Carry,
+    Zero,
+    Sign,
+    Overflow
+}
+
+
+// Storage an assignment can write. Registers are always the full 64-bit
+// register, narrower accesses are expressed with truncation and masking.
+// Temporaries only live within the instruction that defines them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Var {
+    Register(Register),
+    Flag(Flag),
+    Temp(u32, u32)
+}
+
+
+impl Var {
+    // Width in bits
+    pub fn width(&self) -> u32 {
+        match self {
+            Var::Register(register) if register.is_gpr() => 64,
+            Var::Register(register) if register.is_segment_register() => 64,
+            Var::Register(register) => register.size() as u32 * 8,
+            Var::Flag(_) => 1,
+            Var::Temp(_, width) => *width
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    UMulHigh,
+    SMulHigh,
+    UDiv,
+    SDiv,
+    URem,
+    SRem,
+    And,
+    Or,
+    Xor,
+    Shl,
+    LShr,
+    AShr,
+    // High half on the left, low half on the right
+    Concat,
+    Eq,
+    Ne,
+    ULt,
+    ULe,
+    UGt,
+    UGe,
+    SLt,
+    SLe,
+    SGt,
+    SGe,
+    // Signed overflow of an addition or subtraction
+    AddOverflow,
+    SubOverflow
+}
+
+
+impl BinaryOp {
+    // Comparisons and overflow checks produce a single bit
+    pub fn is_predicate(self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Eq | BinaryOp::Ne | BinaryOp::ULt | BinaryOp::ULe | BinaryOp::UGt | BinaryOp::UGe
+                | BinaryOp::SLt | BinaryOp::SLe | BinaryOp::SGt | BinaryOp::SGe
+                | BinaryOp::AddOverflow | BinaryOp::SubOverflow
+        )
+    }
+
+
+    // Comparison that holds exactly when this one does not
+    pub fn inverse(self) -> Option<BinaryOp> {
+        match self {
+            BinaryOp::Eq => Some(BinaryOp::Ne),
+            BinaryOp::Ne => Some(BinaryOp::Eq),
+            BinaryOp::ULt => Some(BinaryOp::UGe),
+            BinaryOp::ULe => Some(BinaryOp::UGt),
+            BinaryOp::UGt => Some(BinaryOp::ULe),
+            BinaryOp::UGe => Some(BinaryOp::ULt),
+            BinaryOp::SLt => Some(BinaryOp::SGe),
+            BinaryOp::SLe => Some(BinaryOp::SGt),
+            BinaryOp::SGt => Some(BinaryOp::SLe),
+            BinaryOp::SGe => Some(BinaryOp::SLt),
+            _ => None
+        }
+    }
+
+
+    // Comparison that holds with its operands swapped
+    pub fn swapped(self) -> Option<BinaryOp> {
+        match self {
+            BinaryOp::Eq => Some(BinaryOp::Eq),
+            BinaryOp::Ne => Some(BinaryOp::Ne),
+            BinaryOp::ULt => Some(BinaryOp::UGt),
+            BinaryOp::ULe => Some(BinaryOp::UGe),
+            BinaryOp::UGt => Some(BinaryOp::ULt),
+            BinaryOp::UGe => Some(BinaryOp::ULe),
+            BinaryOp::SLt => Some(BinaryOp::SGt),
+            BinaryOp::SLe => Some(BinaryOp::SGe),
+            BinaryOp::SGt => Some(BinaryOp::SLt),
+            BinaryOp::SGe => Some(BinaryOp::SLe),
+            _ => None
+        }
+    }
+
+
+    fn is_commutative(self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Add | BinaryOp::Mul | BinaryOp::UMulHigh | BinaryOp::SMulHigh
+                | BinaryOp::And | BinaryOp::Or | BinaryOp::Xor | BinaryOp::Eq | BinaryOp::Ne
+                | BinaryOp::AddOverflow
+        )
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Const { value: u64, width: u32 },
+    Var(Var),
+    Load { address: Box<Expr>, width: u32 },
+    Unary { op: UnaryOp, operand: Box<Expr> },
+    Binary { op: BinaryOp, left: Box<Expr>, right: Box<Expr> },
+    Extend { operand: Box<Expr>, width: u32, signed: bool },
+    Truncate { operand: Box<Expr>, width: u32 },
+    Select { condition: Box<Expr>, then: Box<Expr>, otherwise: Box<Expr> },
+    // Value the lifter does not model, e.g. flags left undefined
+    Undefined(u32)
+}
+
+
+impl Expr {
+    pub fn constant(value: u64, width: u32) -> Expr {
+        Expr::Const { value: value & mask(width), width }
+    }
+
+
+    pub fn var(var: Var) -> Expr {
+        Expr::Var(var)
+    }
+
+
+    pub fn register(register: Register) -> Expr {
+        Expr::Var(Var::Register(register))
+    }
+
+
+    pub fn flag(flag: Flag) -> Expr {
+        Expr::Var(Var::Flag(flag))
+    }
+
+
+    pub fn load(address: Expr, width: u32) -> Expr {
+        Expr::Load { address: Box::new(address), width }
+    }
+
+
+    pub fn unary(op: UnaryOp, operand: Expr) -> Expr {
+        Expr::Unary { op, operand: Box::new(operand) }
+    }
+
+
+    pub fn binary(op: BinaryOp, left: Expr, right: Expr) -> Expr {
+        Expr::Binary { op, left: Box::new(left), right: Box::new(right) }
+    }
+
+
+    pub fn zero_extend(operand: Expr, width: u32) -> Expr {
+        Expr::Extend { operand: Box::new(operand), width, signed: false }
+    }
+
+
+    pub fn sign_extend(operand: Expr, width: u32) -> Expr {
+        Expr::Extend { operand: Box::new(operand), width, signed: true }
+    }
+
+
+    pub fn truncate(operand: Expr, width: u32) -> Expr {
+        Expr::Truncate { operand: Box::new(operand), width }
+    }
+
+
+    pub fn select(condition: Expr, then: Expr, otherwise: Expr) -> Expr {
+        Expr::Select { condition: Box::new(condition), then: Box::new(then), otherwise: Box::new(otherwise) }
+    }
+
+
+    // Width in bits
+    pub fn width(&self) -> u32 {
+        match self {
+            Expr::Const { width, .. } => *width,
+            Expr::Var(var) => var.width(),
+            Expr::Load { width, .. } => *width,
+            Expr::Unary { operand, .. } => operand.width(),
+            Expr::Binary { op: BinaryOp::Concat, left, right } => left.width() + right.width(),
+            Expr::Binary { op, left, .. } => if op.is_predicate() { 1 } else { left.width() },
+            Expr::Extend { width, .. } => *width,
+            Expr::Truncate { width, .. } => *width,
+            Expr::Select { then, .. } => then.width(),
+            Expr::Undefined(width) => *width
+        }
+    }
+
+
+    pub fn as_const(&self) -> Option<u64> {
+        match self {
+            Expr::Const { value, .. } => Some(*value),
+            _ => None
+        }
+    }
+
+
+    // Whether the expression reads a variable
+    pub fn uses(&self, var: &Var) -> bool {
+        let mut found: bool = false;
+        self.visit(&mut |expr| found |= matches!(expr, Expr::Var(used) if used == var));
+        found
+    }
+
+
+    // Whether the expression reads memory
+    pub fn reads_memory(&self) -> bool {
+        let mut found: bool = false;
+        self.visit(&mut |expr| found |= matches!(expr, Expr::Load { .. }));
+        found
+    }
+
+
+    // Call f on this expression and every expression inside it
+    pub fn visit(&self, f: &mut dyn FnMut(&Expr)) {
+        f(self);
+
+        match self {
+            Expr::Load { address, .. } => address.visit(f),
+            Expr::Unary { operand, .. } | Expr::Extend { operand, .. } | Expr::Truncate { operand, .. } => operand.visit(f),
+            Expr::Binary { left, right, .. } => {
+                left.visit(f);
+                right.visit(f);
+            },
+            Expr::Select { condition, then, otherwise } => {
+                condition.visit(f);
+                then.visit(f);
+                otherwise.visit(f);
+            },
+            Expr::Const { .. } | Expr::Var(_) | Expr::Undefined(_) => {}
+        }
+    }
+
+
+    // Rebuild the expression bottom up, replacing each node with what f
+    // returns for it
+    pub fn map(self, f: &mut dyn FnMut(Expr) -> Expr) -> Expr {
+        let rebuilt: Expr = match self {
+            Expr::Load { address, width } => Expr::load(address.map(f), width),
+            Expr::Unary { op, operand } => Expr::unary(op, operand.map(f)),
+            Expr::Binary { op, left, right } => Expr::binary(op, left.map(f), right.map(f)),
+            Expr::Extend { operand, width, signed } => Expr::Extend { operand: Box::new(operand.map(f)), width, signed },
+            Expr::Truncate { operand, width } => Expr::truncate(operand.map(f), width),
+            Expr::Select { condition, then, otherwise } => Expr::select(condition.map(f), then.map(f), otherwise.map(f)),
+            leaf => leaf
+        };
+
+        f(rebuilt)
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stmt {
+    Assign { dest: Var, value: Expr },
+    Store { address: Expr, value: Expr },
+    Jump { target: Expr },
+    // Taken when the condition holds, otherwise execution falls through
+    Branch { condition: Expr, target: Expr },
+    // Call that returns with the stack pointer as it was before the call
+    Call { target: Expr },
+    Return,
+    // Instruction the lifter does not model, named by its mnemonic. The
+    // registers and flags it writes are assigned undefined values after it.
+    Unsupported(String)
+}
+
+
+impl Stmt {
+    // Apply f to every expression the statement holds
+    pub fn map_exprs(self, f: &mut dyn FnMut(Expr) -> Expr) -> Stmt {
+        match self {
+            Stmt::Assign { dest, value } => Stmt::Assign { dest, value: f(value) },
+            Stmt::Store { address, value } => Stmt::Store { address: f(address), value: f(value) },
+            Stmt::Jump { target } => Stmt::Jump { target: f(target) },
+            Stmt::Branch { condition, target } => Stmt::Branch { condition: f(condition), target: f(target) },
+            Stmt::Call { target } => Stmt::Call { target: f(target) },
+            other => other
+        }
+    }
+}
+
+
+// Statements lifted from one machine instruction
+#[derive(Debug, Clone)]
+pub struct IrInstruction {
+    pub address: u64,
+    pub statements: Vec<Stmt>
+}
+
+
+#[derive(Debug, Clone)]
+pub struct IrBlock {
+    pub start: u64,
+    pub end: u64,
+    pub instructions: Vec<IrInstruction>
+}
+
+
+impl IrBlock {
+    pub fn statements(&self) -> impl Iterator<Item = &Stmt> {
+        self.instructions.iter().flat_map(|instruction| instruction.statements.iter())
+    }
+}
+
+
+// Lifted form of a control flow graph, sharing its block layout and edges
+#[derive(Debug, Clone)]
+pub struct IrFunction {
+    pub name: String,
+    pub entry: u64,
+    pub blocks: BTreeMap<u64, IrBlock>,
+    pub edges: Vec<Edge>
+}
+
+
+impl IrFunction {
+    pub fn successors(&self, block: u64) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.from == block)
+    }
+
+
+    pub fn predecessors(&self, block: u64) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.to == block)
+    }
+}
+
+
+// All ones in the low width bits
+pub fn mask(width: u32) -> u64 {
+    if width >= 64 { u64::MAX } else { (1u64 << width) - 1 }
+}
+
+
+// Sign extend the low width bits of a value to 64 bits
+pub fn sign_extend_value(value: u64, width: u32) -> i64 {
+    if width == 0 || width >= 64 {
+        value as i64
+    } else {
+        let shift: u32 = 64 - width;
+        ((value << shift) as i64) >> shift
+    }
+}
+
+
+// Fold constants and rewrite expressions into simpler equivalent forms.
+// Conditions built from the flags of a cmp or test are turned back into
+// the comparison they test, e.g. !ZF & (SF == OF) after cmp a, b becomes
+// a >s b once the flags have been substituted.
+pub fn simplify(expr: Expr) -> Expr {
+    expr.map(&mut simplify_node)
+}
+
+
+// Simplify a node whose operands are already simple
+fn simplify_node(expr: Expr) -> Expr {
+    match rewrite(&expr) {
+        Some(rewritten) => simplify(rewritten),
+        None => expr
+    }
+}
+
+
+fn rewrite(expr: &Expr) -> Option<Expr> {
+    match expr {
+        Expr::Unary { op, operand } => rewrite_unary(*op, operand),
+        Expr::Binary { op, left, right } => rewrite_binary(*op, left, right),
+        Expr::Extend { operand, width, signed } => {
+            if operand.width() == *width {
+                return Some((**operand).clone());
+            }
+
+            match &**operand {
+                Expr::Const { value, width: from } => {
+                    let value: u64 = if *signed { sign_extend_value(*value, *from) as u64 } else { *value };
+                    Some(Expr::constant(value, *width))
+                },
+                // Nested extensions of the same kind collapse
+                Expr::Extend { operand: inner, signed: inner_signed, .. } if inner_signed == signed || !inner_signed => {
+                    Some(Expr::Extend { operand: inner.clone(), width: *width, signed: *inner_signed })
+                },
+                _ => None
+            }
+        },
+        Expr::Truncate { operand, width } => rewrite_truncate(operand, *width),
+        Expr::Select { condition, then, otherwise } => {
+            match condition.as_const() {
+                Some(0) => Some((**otherwise).clone()),
+                Some(_) => Some((**then).clone()),
+                None if then == otherwise => Some((**then).clone()),
+                None => None
+            }
+        },
+        _ => None
+    }
+}
+
+
+fn rewrite_unary(op: UnaryOp, operand: &Expr) -> Option<Expr> {
+    let width: u32 = operand.width();
+
+    if let Some(value) = operand.as_const() {
+        return Some(match op {
+            UnaryOp::Neg => Expr::constant(value.wrapping_neg(), width),
+            UnaryOp::Not => Expr::constant(!value, width)
+        });
+    }
+
+    match (op, operand) {
+        (_, Expr::Unary { op: inner, operand }) if *inner == op => Some((**operand).clone()),
+        // De Morgan on conditions, so negations end up on comparisons
+        (UnaryOp::Not, Expr::Binary { op: logic @ (BinaryOp::And | BinaryOp::Or), left, right }) if width == 1 => {
+            let flipped: BinaryOp = if *logic == BinaryOp::And { BinaryOp::Or } else { BinaryOp::And };
+            Some(Expr::binary(flipped, Expr::unary(UnaryOp::Not, (**left).clone()), Expr::unary(UnaryOp::Not, (**right).clone())))
+        },
+        (UnaryOp::Not, Expr::Binary { op: compare, left, right }) => {
+            compare.inverse().map(|inverse| Expr::binary(inverse, (**left).clone(), (**right).clone()))
+        },
+        _ => None
+    }
+}
+
+
+fn rewrite_binary(op: BinaryOp, left: &Expr, right: &Expr) -> Option<Expr> {
+    let width: u32 = left.width();
+
+    if let (Some(a), Some(b)) = (left.as_const(), right.as_const()) {
+        if let Some(folded) = fold_binary(op, a, b, width, right.width()) {
+            return Some(folded);
+        }
+    }
+
+    // Keep constants on the right of commutative operators
+    if op.is_commutative() && left.as_const().is_some() && right.as_const().is_none() {
+        return Some(Expr::binary(op, right.clone(), left.clone()));
+    }
+
+    // Predicates against a constant keep the constant on the right too
+    if let (Some(swapped), Some(_), None) = (op.swapped(), left.as_const(), right.as_const()) {
+        return Some(Expr::binary(swapped, right.clone(), left.clone()));
+    }
+
+    let constant: Option<u64> = right.as_const();
+    let zero: Expr = Expr::constant(0, width);
+
+    match op {
+        BinaryOp::Add | BinaryOp::Or | BinaryOp::Xor | BinaryOp::Shl | BinaryOp::LShr | BinaryOp::AShr if constant == Some(0) => {
+            return Some(left.clone());
+        },
+        BinaryOp::Sub if constant == Some(0) => return Some(left.clone()),
+        BinaryOp::Mul | BinaryOp::UDiv | BinaryOp::SDiv if constant == Some(1) => return Some(left.clone()),
+        BinaryOp::And if constant == Some(mask(width)) => return Some(left.clone()),
+        BinaryOp::And | BinaryOp::Mul if constant == Some(0) => return Some(zero),
+        BinaryOp::Sub | BinaryOp::Xor if left == right => return Some(zero),
+        BinaryOp::And | BinaryOp::Or if left == right => return Some(left.clone()),
+        BinaryOp::AddOverflow | BinaryOp::SubOverflow if constant == Some(0) => return Some(Expr::constant(0, 1)),
+        // Nothing is unsigned below zero
+        BinaryOp::ULt if constant == Some(0) => return Some(Expr::constant(0, 1)),
+        BinaryOp::UGe if constant == Some(0) => return Some(Expr::constant(1, 1)),
+        _ => {}
+    }
+
+    // Chains of constant additions and subtractions collapse into one
+    if let (BinaryOp::Add | BinaryOp::Sub, Some(outer), Expr::Binary { op: inner @ (BinaryOp::Add | BinaryOp::Sub), left: base, right: inner_constant }) = (op, constant, left) {
+        if let Some(inner_value) = inner_constant.as_const() {
+            let signed = |op: BinaryOp, value: u64| if op == BinaryOp::Add { value } else { value.wrapping_neg() };
+            let total: u64 = signed(*inner, inner_value).wrapping_add(signed(op, outer));
+            return Some(Expr::binary(BinaryOp::Add, (**base).clone(), Expr::constant(total, width)));
+        }
+    }
+
+    // Rounding towards zero before an arithmetic shift by one is a signed
+    // division by two
+    if let (BinaryOp::AShr, Some(1), Expr::Binary { op: BinaryOp::Add, left: a, right: b }) = (op, constant, left) {
+        let sign_bit = |expr: &Expr, value: &Expr| matches!(
+            expr,
+            Expr::Binary { op: BinaryOp::LShr, left: shifted, right: shift }
+                if **shifted == *value && shift.as_const() == Some(width as u64 - 1)
+        );
+
+        if sign_bit(b, a) || sign_bit(a, b) {
+            let value: &Expr = if sign_bit(b, a) { a } else { b };
+            return Some(Expr::binary(BinaryOp::SDiv, value.clone(), Expr::constant(2, width)));
+        }
+    }
+
+    // Adding a negative constant reads better as a subtraction
+    if let (BinaryOp::Add, Some(value)) = (op, constant) {
+        if sign_extend_value(value, width) < 0 && width > 1 {
+            return Some(Expr::binary(BinaryOp::Sub, left.clone(), Expr::constant(value.wrapping_neg(), width)));
+        }
+    }
+
+    // Single bit predicates compared against a constant
+    if width == 1 {
+        match (op, constant) {
+            (BinaryOp::Eq, Some(0)) | (BinaryOp::Ne, Some(1)) => return Some(Expr::unary(UnaryOp::Not, left.clone())),
+            (BinaryOp::Ne, Some(0)) | (BinaryOp::Eq, Some(1)) => return Some(left.clone()),
+            (BinaryOp::And, Some(1)) | (BinaryOp::Or, Some(0)) => return Some(left.clone()),
+            (BinaryOp::And, Some(0)) => return Some(Expr::constant(0, 1)),
+            (BinaryOp::Or, Some(1)) => return Some(Expr::constant(1, 1)),
+            _ => {}
+        }
+    }
+
+    if let Some(rewritten) = rewrite_flags(op, left, right) {
+        return Some(rewritten);
+    }
+
+    // Equality of a difference against zero is equality of its operands
+    if let (BinaryOp::Eq | BinaryOp::Ne, Some(0), Expr::Binary { op: BinaryOp::Sub, left: a, right: b }) = (op, constant, left) {
+        return Some(Expr::binary(op, (**a).clone(), (**b).clone()));
+    }
+
+    // Comparisons of zero extended values against constants that fit
+    if let (Some(_), Some(value), Expr::Extend { operand, signed, .. }) = (op.swapped(), constant, left) {
+        let inner_width: u32 = operand.width();
+        let fits: bool = if *signed {
+            sign_extend_value(value, width) == sign_extend_value(value, inner_width)
+        } else {
+            value <= mask(inner_width)
+        };
+        let keeps_meaning: bool = matches!(op, BinaryOp::Eq | BinaryOp::Ne)
+            || *signed == matches!(op, BinaryOp::SLt | BinaryOp::SLe | BinaryOp::SGt | BinaryOp::SGe);
+
+        if fits && keeps_meaning {
+            return Some(Expr::binary(op, (**operand).clone(), Expr::constant(value, inner_width)));
+        }
+    }
+
+    // The high half of a value sign extended into a register pair, as cdq
+    // and cqo leave it
+    if let (BinaryOp::Concat, Expr::Binary { op: BinaryOp::AShr, left: source, right: shift }) = (op, left) {
+        if **source == *right && shift.as_const() == Some(right.width() as u64 - 1) {
+            return Some(Expr::sign_extend(right.clone(), right.width() * 2));
+        }
+    }
+
+    None
+}
+
+
+// Recognise the condition codes of jcc, setcc and cmovcc once the flag
+// definitions of the compare before them have been substituted
+fn rewrite_flags(op: BinaryOp, left: &Expr, right: &Expr) -> Option<Expr> {
+    // SF != OF is a signed less than, SF == OF the opposite
+    if let (BinaryOp::Eq | BinaryOp::Ne, Some((a, b))) = (op, sign_and_overflow(left, right).or_else(|| sign_and_overflow(right, left))) {
+        let compare: BinaryOp = if op == BinaryOp::Ne { BinaryOp::SLt } else { BinaryOp::SGe };
+        return Some(Expr::binary(compare, a, b));
+    }
+
+    // SF != 0 after a logic instruction has OF cleared
+    if let (BinaryOp::Ne, Some(0)) = (op, right.as_const()) {
+        if let Expr::Binary { op: BinaryOp::SLt, .. } = left {
+            return Some(left.clone());
+        }
+    }
+
+    // The strict and non-strict comparisons that combine ZF with CF or
+    // with SF and OF
+    let (first, second): (&Expr, &Expr) = (left, right);
+
+    for (x, y) in [(first, second), (second, first)] {
+        if let (Expr::Binary { op: x_op, left: a, right: b }, Expr::Binary { op: y_op, left: c, right: d }) = (x, y) {
+            if !same_difference(a, b, c, d) {
+                continue;
+            }
+
+            let combined: Option<BinaryOp> = match (op, x_op, y_op) {
+                (BinaryOp::And, BinaryOp::Ne, BinaryOp::SGe) => Some(BinaryOp::SGt),
+                (BinaryOp::And, BinaryOp::Ne, BinaryOp::UGe) => Some(BinaryOp::UGt),
+                (BinaryOp::Or, BinaryOp::Eq, BinaryOp::SLt) => Some(BinaryOp::SLe),
+                (BinaryOp::Or, BinaryOp::Eq, BinaryOp::ULt) => Some(BinaryOp::ULe),
+                _ => None
+            };
+
+            if let Some(combined) = combined {
+                return Some(Expr::binary(combined, (**c).clone(), (**d).clone()));
+            }
+        }
+    }
+
+    None
+}
+
+
+// Operands a and b when the pair is (a - b) <s 0 and subof(a, b), the sign
+// and overflow flags of cmp a, b. Logic instructions clear OF, so
+// x <s 0 against a zero bit also qualifies, as (x, 0).
+fn sign_and_overflow(sign: &Expr, overflow: &Expr) -> Option<(Expr, Expr)> {
+    let (difference, zero) = match sign {
+        Expr::Binary { op: BinaryOp::SLt, left, right } => (&**left, &**right),
+        _ => return None
+    };
+
+    if zero.as_const() != Some(0) {
+        return None;
+    }
+
+    match (difference, overflow) {
+        (_, Expr::Binary { op: BinaryOp::SubOverflow, left: c, right: d }) if same_difference(difference, &zero_like(difference), c, d) => {
+            Some(((**c).clone(), (**d).clone()))
+        },
+        (_, Expr::Const { value: 0, width: 1 }) => Some((difference.clone(), zero.clone())),
+        _ => None
+    }
+}
+
+
+// Whether a - b and c - d simplify to the same value. The operands of a
+// compare may have been folded differently in the flags that test it.
+fn same_difference(a: &Expr, b: &Expr, c: &Expr, d: &Expr) -> bool {
+    (a == c && b == d)
+        || simplify(Expr::binary(BinaryOp::Sub, a.clone(), b.clone())) == simplify(Expr::binary(BinaryOp::Sub, c.clone(), d.clone()))
+}
+
+
+// Zero of the same width as an expression
+fn zero_like(expr: &Expr) -> Expr {
+    Expr::constant(0, expr.width())
+}
+
+
+fn rewrite_truncate(operand: &Expr, width: u32) -> Option<Expr> {
+    let operand_width: u32 = operand.width();
+
+    if operand_width == width {
+        return Some(operand.clone());
+    }
+
+    // Writes to part of a register merge the new bits into the old value
+    // with a mask. Reading back only the new bits sees the value.
+    let low_bits_cleared = |expr: &Expr| matches!(
+        expr,
+        Expr::Binary { op: BinaryOp::And, right: kept, .. } if kept.as_const().is_some_and(|kept| kept & mask(width) == 0)
+    );
+
+    match operand {
+        Expr::Const { value, .. } => Some(Expr::constant(*value, width)),
+        Expr::Truncate { operand: inner, .. } => Some(Expr::truncate((**inner).clone(), width)),
+        Expr::Extend { operand: inner, signed, .. } => {
+            let inner_width: u32 = inner.width();
+
+            if inner_width == width {
+                Some((**inner).clone())
+            } else if inner_width > width {
+                Some(Expr::truncate((**inner).clone(), width))
+            } else {
+                Some(Expr::Extend { operand: inner.clone(), width, signed: *signed })
+            }
+        },
+        Expr::Binary { op: BinaryOp::Or, left, right } if low_bits_cleared(left) => {
+            Some(Expr::truncate((**right).clone(), width))
+        },
+        Expr::Binary { op: BinaryOp::Or, left, right } if low_bits_cleared(right) => {
+            Some(Expr::truncate((**left).clone(), width))
+        },
+        // Division and remainder of sign extended values stay in range,
+        // apart from the overflow that faults
+        Expr::Binary { op: op @ (BinaryOp::SDiv | BinaryOp::SRem | BinaryOp::UDiv | BinaryOp::URem), left, right } => {
+            let signed: bool = matches!(op, BinaryOp::SDiv | BinaryOp::SRem);
+
+            match (&**left, &**right) {
+                (Expr::Extend { operand: a, signed: a_signed, .. }, Expr::Extend { operand: b, signed: b_signed, .. })
+                    if *a_signed == signed && *b_signed == signed && a.width() == width && b.width() == width => {
+                    Some(Expr::binary(*op, (**a).clone(), (**b).clone()))
+                },
+                // Unsigned division of a constant register pair, which only
+                // fits in 128 bits
+                (Expr::Binary { op: BinaryOp::Concat, left: high, right: low }, Expr::Const { value: divisor, .. })
+                    if !signed && *divisor != 0 && width <= 64 => {
+                    let dividend: u128 = ((high.as_const()? as u128) << low.width()) | low.as_const()? as u128;
+                    let result: u128 = if *op == BinaryOp::UDiv { dividend / *divisor as u128 } else { dividend % *divisor as u128 };
+                    Some(Expr::constant(result as u64 & mask(width), width))
+                },
+                _ => None
+            }
+        },
+        // Low bits of arithmetic only depend on the low bits of the operands
+        Expr::Binary { op: op @ (BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::And | BinaryOp::Or | BinaryOp::Xor), left, right } => {
+            let narrows = |expr: &Expr| matches!(expr, Expr::Extend { .. } | Expr::Const { .. } | Expr::Truncate { .. });
+
+            if narrows(left) && narrows(right) {
+                Some(Expr::binary(*op, Expr::truncate((**left).clone(), width), Expr::truncate((**right).clone(), width)))
+            } else {
+                None
+            }
+        },
+        _ => None
+    }
+}
+
+
+// Evaluate an operator on two constants
+fn fold_binary(op: BinaryOp, a: u64, b: u64, width: u32, right_width: u32) -> Option<Expr> {
+    // Register pairs that fit in 64 bits, as 32-bit division reads
+    if op == BinaryOp::Concat {
+        return (width + right_width <= 64).then(|| Expr::constant((a << right_width) | b, width + right_width));
+    }
+
+    if width > 64 || right_width > 64 {
+        return None;
+    }
+
+    let sa: i64 = sign_extend_value(a, width);
+    let sb: i64 = sign_extend_value(b, width);
+    let bit = |value: bool| Some(Expr::constant(value as u64, 1));
+
+    let value: u64 = match op {
+        BinaryOp::Add => a.wrapping_add(b),
+        BinaryOp::Sub => a.wrapping_sub(b),
+        BinaryOp::Mul => a.wrapping_mul(b),
+        BinaryOp::UDiv if b != 0 => a / b,
+        BinaryOp::URem if b != 0 => a % b,
+        BinaryOp::SDiv if sb != 0 => sa.wrapping_div(sb) as u64,
+        BinaryOp::SRem if sb != 0 => sa.wrapping_rem(sb) as u64,
+        BinaryOp::And => a & b,
+        BinaryOp::Or => a | b,
+        BinaryOp::Xor => a ^ b,
+        BinaryOp::Shl if b < width as u64 => a << b,
+        BinaryOp::LShr if b < width as u64 => a >> b,
+        BinaryOp::AShr if b < width as u64 => (sa >> b) as u64,
+        BinaryOp::Eq => return bit(a == b),
+        BinaryOp::Ne => return bit(a != b),
+        BinaryOp::ULt => return bit(a < b),
+        BinaryOp::ULe => return bit(a <= b),
+        BinaryOp::UGt => return bit(a > b),
+        BinaryOp::UGe => return bit(a >= b),
+        BinaryOp::SLt => return bit(sa < sb),
+        BinaryOp::SLe => return bit(sa <= sb),
+        BinaryOp::SGt => return bit(sa > sb),
+        BinaryOp::SGe => return bit(sa >= sb),
+        BinaryOp::AddOverflow => return bit(sign_extend_value(a.wrapping_add(b), width) != sa.wrapping_add(sb) || sa.checked_add(sb).is_none()),
+        BinaryOp::SubOverflow => return bit(sign_extend_value(a.wrapping_sub(b), width) != sa.wrapping_sub(sb) || sa.checked_sub(sb).is_none()),
+        _ => return None
+    };
+
+    Some(Expr::constant(value, width))
+}
+
+
+impl fmt::Display for Flag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Flag::Carry => write!(f, "CF"),
+            Flag::Zero => write!(f, "ZF"),
+            Flag::Sign => write!(f, "SF"),
+            Flag::Overflow => write!(f, "OF")
+        }
+    }
+}
+
+
+impl fmt::Display for Var {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Var::Register(register) => write!(f, "{}", format!("{:?}", register).to_lowercase()),
+            Var::Flag(flag) => write!(f, "{}", flag),
+            Var::Temp(index, _) => write!(f, "t{}", index)
+        }
+    }
+}
+
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Const { value, width: 1 } => write!(f, "{}", value),
+            Expr::Const { value, .. } => write!(f, "{:#x}", value),
+            Expr::Var(var) => write!(f, "{}", var),
+            Expr::Load { address, width } => write!(f, "load{}({})", width, address),
+            Expr::Unary { op: UnaryOp::Neg, operand } => write!(f, "-{}", operand),
+            Expr::Unary { op: UnaryOp::Not, operand } if operand.width() == 1 => write!(f, "!{}", operand),
+            Expr::Unary { op: UnaryOp::Not, operand } => write!(f, "~{}", operand),
+            Expr::Binary { op, left, right } => {
+                let symbol: &str = match op {
+                    BinaryOp::Add => "+",
+                    BinaryOp::Sub => "-",
+                    BinaryOp::Mul => "*",
+                    BinaryOp::UDiv => "/u",
+                    BinaryOp::SDiv => "/s",
+                    BinaryOp::URem => "%u",
+                    BinaryOp::SRem => "%s",
+                    BinaryOp::And => "&",
+                    BinaryOp::Or => "|",
+                    BinaryOp::Xor => "^",
+                    BinaryOp::Shl => "<<",
+                    BinaryOp::LShr => ">>u",
+                    BinaryOp::AShr => ">>s",
+                    BinaryOp::Eq => "==",
+                    BinaryOp::Ne => "!=",
+                    BinaryOp::ULt => "<u",
+                    BinaryOp::ULe => "<=u",
+                    BinaryOp::UGt => ">u",
+                    BinaryOp::UGe => ">=u",
+                    BinaryOp::SLt => "<s",
+                    BinaryOp::SLe => "<=s",
+                    BinaryOp::SGt => ">s",
+                    BinaryOp::SGe => ">=s",
+                    BinaryOp::UMulHigh => return write!(f, "umulh({}, {})", left, right),
+                    BinaryOp::SMulHigh => return write!(f, "smulh({}, {})", left, right),
+                    BinaryOp::Concat => return write!(f, "concat({}, {})", left, right),
+                    BinaryOp::AddOverflow => return write!(f, "addof({}, {})", left, right),
+                    BinaryOp::SubOverflow => return write!(f, "subof({}, {})", left, right)
+                };
+
+                write!(f, "({} {} {})", left, symbol, right)
+            },
+            Expr::Extend { operand, width, signed: false } => write!(f, "zext{}({})", width, operand),
+            Expr::Extend { operand, width, signed: true } => write!(f, "sext{}({})", width, operand),
+            Expr::Truncate { operand, width } => write!(f, "trunc{}({})", width, operand),
+            Expr::Select { condition, then, otherwise } => write!(f, "({} ? {} : {})", condition, then, otherwise),
+            Expr::Undefined(width) => write!(f, "undef{}", width)
+        }
+    }
+}
+
+
+impl fmt::Display for Stmt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stmt::Assign { dest, value } => write!(f, "{} = {}", dest, value),
+            Stmt::Store { address, value } => write!(f, "store{}({}) = {}", value.width(), address, value),
+            Stmt::Jump { target } => write!(f, "jump {}", target),
+            Stmt::Branch { condition, target } => write!(f, "if {} jump {}", condition, target),
+            Stmt::Call { target } => write!(f, "call {}", target),
+            Stmt::Return => write!(f, "return"),
+            Stmt::Unsupported(mnemonic) => write!(f, "unsupported {}", mnemonic)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use iced_x86::Register;
+
+    use super::{simplify, BinaryOp, Expr, UnaryOp};
+
+
+    fn low32(register: Register) -> Expr {
+        Expr::truncate(Expr::register(register), 32)
+    }
+
+
+    // Flags of cmp eax,ebx as the lifter writes them
+    fn compare_flags() -> (Expr, Expr, Expr) {
+        let difference: Expr = Expr::binary(BinaryOp::Sub, low32(Register::RAX), low32(Register::RBX));
+
+        let zero: Expr = Expr::binary(BinaryOp::Eq, difference.clone(), Expr::constant(0, 32));
+        let sign: Expr = Expr::binary(BinaryOp::SLt, difference, Expr::constant(0, 32));
+        let overflow: Expr = Expr::binary(BinaryOp::SubOverflow, low32(Register::RAX), low32(Register::RBX));
+
+        (zero, sign, overflow)
+    }
+
+
+    #[test]
+    fn folds_signed_greater_than() {
+        let (zero, sign, overflow) = compare_flags();
+
+        // jg: !ZF & (SF == OF)
+        let condition: Expr = Expr::binary(
+            BinaryOp::And,
+            Expr::unary(UnaryOp::Not, zero),
+            Expr::binary(BinaryOp::Eq, sign, overflow)
+        );
+
+        let expected: Expr = Expr::binary(BinaryOp::SGt, low32(Register::RAX), low32(Register::RBX));
+        assert_eq!(simplify(condition), expected);
+    }
+
+
+    #[test]
+    fn folds_signed_less_than() {
+        let (_, sign, overflow) = compare_flags();
+
+        // jl: SF != OF
+        let condition: Expr = Expr::binary(BinaryOp::Ne, sign, overflow);
+
+        let expected: Expr = Expr::binary(BinaryOp::SLt, low32(Register::RAX), low32(Register::RBX));
+        assert_eq!(simplify(condition), expected);
+    }
+
+
+    #[test]
+    fn folds_constants() {
+        let sum: Expr = Expr::binary(BinaryOp::Add, Expr::constant(0xFFFF_FFFF, 32), Expr::constant(2, 32));
+        assert_eq!(simplify(sum), Expr::constant(1, 32));
+
+        let pair: Expr = Expr::binary(BinaryOp::Concat, Expr::constant(1, 32), Expr::constant(2, 32));
+        assert_eq!(simplify(pair), Expr::constant(0x1_0000_0002, 64));
+    }
+}
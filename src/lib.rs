@@ -0,0 +1,26 @@
+// lib.rs
+// Created: 10/17/2026
+// Description: Library entrypoint for chisel. Exposes the ELF object model,
+//              parsing helpers and disassembly routines to other Rust tools.
+
+pub mod binary;
+pub mod cfg;
+pub mod decompile;
+pub mod disasm;
+// The elf.rs section banners are written with /// lines
+#[allow(clippy::empty_line_after_doc_comments)]
+pub mod elf;
+pub mod error;
+pub mod export;
+pub mod functions;
+pub mod ir;
+pub mod lift;
+pub mod patcher;
+pub mod pseudoc;
+pub mod reassemble;
+pub mod symbols;
+pub mod util;
+pub mod xrefs;
+
+pub use binary::{CodeRegion, Elf};
+pub use error::ChiselError;
@@ -0,0 +1,770 @@
+// lift.rs
+// Created: 10/17/2026
+// Description: Lifter from decoded x86-64 instructions to the analysis IR.
+//              Covers the common integer subset: moves, stack operations,
+//              arithmetic and logic, shifts, multiplication and division,
+//              and control flow. Anything else is kept as an unsupported
+//              statement that clobbers what the instruction writes.
+
+use std::collections::BTreeMap;
+
+use iced_x86::{ConditionCode, FlowControl, Instruction, InstructionInfoFactory, Mnemonic, OpAccess, OpKind, Register, RflagsBits};
+
+use crate::cfg::ControlFlowGraph;
+use crate::ir::{self, BinaryOp, Expr, Flag, IrBlock, IrFunction, IrInstruction, Stmt, UnaryOp, Var};
+
+
+// Lift every block of a function's control flow graph
+pub fn lift_function(graph: &ControlFlowGraph) -> IrFunction {
+    let mut lifter: Lifter = Lifter::new();
+    let mut blocks: BTreeMap<u64, IrBlock> = BTreeMap::new();
+
+    for block in graph.blocks.values() {
+        let instructions: Vec<IrInstruction> = block.instructions
+            .iter()
+            .map(|instruction| lifter.lift(instruction))
+            .collect();
+
+        blocks.insert(block.start, IrBlock {
+            start: block.start,
+            end: block.end,
+            instructions
+        });
+    }
+
+    IrFunction {
+        name: graph.name.clone(),
+        entry: graph.entry,
+        blocks,
+        edges: graph.edges.clone()
+    }
+}
+
+
+// Lift a single instruction
+pub fn lift_instruction(instruction: &Instruction) -> IrInstruction {
+    Lifter::new().lift(instruction)
+}
+
+
+// Expression for a condition code in terms of the flags
+pub fn condition(code: ConditionCode) -> Expr {
+    let flag = |flag: Flag| Expr::flag(flag);
+    let not = |expr: Expr| Expr::unary(UnaryOp::Not, expr);
+    let and = |left: Expr, right: Expr| Expr::binary(BinaryOp::And, left, right);
+    let or = |left: Expr, right: Expr| Expr::binary(BinaryOp::Or, left, right);
+    let sign_is_overflow = || Expr::binary(BinaryOp::Eq, flag(Flag::Sign), flag(Flag::Overflow));
+
+    match code {
+        ConditionCode::o => flag(Flag::Overflow),
+        ConditionCode::no => not(flag(Flag::Overflow)),
+        ConditionCode::b => flag(Flag::Carry),
+        ConditionCode::ae => not(flag(Flag::Carry)),
+        ConditionCode::e => flag(Flag::Zero),
+        ConditionCode::ne => not(flag(Flag::Zero)),
+        ConditionCode::be => or(flag(Flag::Carry), flag(Flag::Zero)),
+        ConditionCode::a => and(not(flag(Flag::Carry)), not(flag(Flag::Zero))),
+        ConditionCode::s => flag(Flag::Sign),
+        ConditionCode::ns => not(flag(Flag::Sign)),
+        ConditionCode::l => not(sign_is_overflow()),
+        ConditionCode::ge => sign_is_overflow(),
+        ConditionCode::le => or(flag(Flag::Zero), not(sign_is_overflow())),
+        ConditionCode::g => and(not(flag(Flag::Zero)), sign_is_overflow()),
+        // Parity is not tracked
+        _ => Expr::Undefined(1)
+    }
+}
+
+
+struct Lifter {
+    factory: InstructionInfoFactory,
+    statements: Vec<Stmt>,
+    temps: u32
+}
+
+
+impl Lifter {
+    fn new() -> Lifter {
+        Lifter {
+            factory: InstructionInfoFactory::new(),
+            statements: Vec::new(),
+            temps: 0
+        }
+    }
+
+
+    fn lift(&mut self, instruction: &Instruction) -> IrInstruction {
+        self.statements.clear();
+        self.temps = 0;
+
+        if !self.lift_semantics(instruction) {
+            self.statements.clear();
+            self.unsupported(instruction);
+        }
+
+        let statements: Vec<Stmt> = self.statements
+            .drain(..)
+            .map(|statement| statement.map_exprs(&mut ir::simplify))
+            .collect();
+
+        IrInstruction {
+            address: instruction.ip(),
+            statements
+        }
+    }
+
+
+    // Emit the statements for an instruction, or return false when the
+    // lifter does not model it
+    fn lift_semantics(&mut self, instruction: &Instruction) -> bool {
+        let code: ConditionCode = instruction.condition_code();
+
+        match instruction.mnemonic() {
+            Mnemonic::Nop | Mnemonic::Endbr64 | Mnemonic::Endbr32 | Mnemonic::Pause => {},
+            Mnemonic::Mov => {
+                let value: Expr = self.read(instruction, 1);
+                self.write(instruction, 0, value);
+            },
+            Mnemonic::Movzx => {
+                let value: Expr = Expr::zero_extend(self.read(instruction, 1), self.width(instruction, 0));
+                self.write(instruction, 0, value);
+            },
+            Mnemonic::Movsx | Mnemonic::Movsxd => {
+                let value: Expr = Expr::sign_extend(self.read(instruction, 1), self.width(instruction, 0));
+                self.write(instruction, 0, value);
+            },
+            Mnemonic::Lea => {
+                let width: u32 = self.width(instruction, 0);
+                let value: Expr = Expr::truncate(self.address(instruction), width);
+                self.write(instruction, 0, value);
+            },
+            Mnemonic::Xchg => {
+                if instruction.op0_kind() == OpKind::Register && instruction.op1_kind() == OpKind::Register
+                    && instruction.op0_register() == instruction.op1_register() {
+                    // xchg of a 32-bit register with itself still clears the top half
+                    let value: Expr = self.read(instruction, 0);
+                    self.write(instruction, 0, value);
+                } else {
+                    let first: Expr = self.read(instruction, 0);
+                    let temp: Expr = self.temp(first);
+                    let second: Expr = self.read(instruction, 1);
+                    self.write(instruction, 0, second);
+                    self.write(instruction, 1, temp);
+                }
+            },
+            Mnemonic::Push => {
+                let width: u32 = self.width(instruction, 0);
+                let mut value: Expr = self.read(instruction, 0);
+
+                if value.uses(&Var::Register(Register::RSP)) {
+                    value = self.temp(value);
+                }
+
+                self.adjust_stack(BinaryOp::Sub, width);
+                self.emit(Stmt::Store { address: Expr::register(Register::RSP), value });
+            },
+            Mnemonic::Pop => {
+                let width: u32 = self.width(instruction, 0);
+                let value: Expr = Expr::load(Expr::register(Register::RSP), width);
+
+                if instruction.op0_kind() == OpKind::Register && instruction.op0_register().full_register() == Register::RSP {
+                    self.write(instruction, 0, value);
+                } else {
+                    let value: Expr = self.temp(value);
+                    self.adjust_stack(BinaryOp::Add, width);
+                    self.write(instruction, 0, value);
+                }
+            },
+            Mnemonic::Leave => {
+                self.assign(Var::Register(Register::RSP), Expr::register(Register::RBP));
+                self.assign(Var::Register(Register::RBP), Expr::load(Expr::register(Register::RSP), 64));
+                self.adjust_stack(BinaryOp::Add, 64);
+            },
+            Mnemonic::Add | Mnemonic::Sub | Mnemonic::Cmp => {
+                let left: Expr = self.read(instruction, 0);
+                let right: Expr = self.read(instruction, 1);
+
+                let op: BinaryOp = if instruction.mnemonic() == Mnemonic::Add { BinaryOp::Add } else { BinaryOp::Sub };
+                self.arithmetic_flags(op, &left, &right, true);
+
+                if instruction.mnemonic() != Mnemonic::Cmp {
+                    self.write(instruction, 0, Expr::binary(op, left, right));
+                }
+            },
+            Mnemonic::Inc | Mnemonic::Dec => {
+                let left: Expr = self.read(instruction, 0);
+                let right: Expr = Expr::constant(1, left.width());
+
+                let op: BinaryOp = if instruction.mnemonic() == Mnemonic::Inc { BinaryOp::Add } else { BinaryOp::Sub };
+                self.arithmetic_flags(op, &left, &right, false);
+                self.write(instruction, 0, Expr::binary(op, left, right));
+            },
+            Mnemonic::Neg => {
+                let operand: Expr = self.read(instruction, 0);
+                let zero: Expr = Expr::constant(0, operand.width());
+
+                self.arithmetic_flags(BinaryOp::Sub, &zero, &operand, true);
+                self.write(instruction, 0, Expr::unary(UnaryOp::Neg, operand));
+            },
+            Mnemonic::And | Mnemonic::Or | Mnemonic::Xor | Mnemonic::Test => {
+                let op: BinaryOp = match instruction.mnemonic() {
+                    Mnemonic::Or => BinaryOp::Or,
+                    Mnemonic::Xor => BinaryOp::Xor,
+                    _ => BinaryOp::And
+                };
+
+                let result: Expr = Expr::binary(op, self.read(instruction, 0), self.read(instruction, 1));
+                self.logic_flags(&result);
+
+                if instruction.mnemonic() != Mnemonic::Test {
+                    self.write(instruction, 0, result);
+                }
+            },
+            Mnemonic::Not => {
+                let value: Expr = Expr::unary(UnaryOp::Not, self.read(instruction, 0));
+                self.write(instruction, 0, value);
+            },
+            Mnemonic::Shl | Mnemonic::Sal | Mnemonic::Shr | Mnemonic::Sar => {
+                let op: BinaryOp = match instruction.mnemonic() {
+                    Mnemonic::Shr => BinaryOp::LShr,
+                    Mnemonic::Sar => BinaryOp::AShr,
+                    _ => BinaryOp::Shl
+                };
+
+                let value: Expr = self.read(instruction, 0);
+                let width: u32 = value.width();
+                let count_mask: u64 = if width == 64 { 0x3F } else { 0x1F };
+
+                // The count is masked, and narrowed to the operand width
+                let count: Expr = Expr::zero_extend(self.read(instruction, 1), width);
+                let count: Expr = ir::simplify(Expr::binary(BinaryOp::And, count, Expr::constant(count_mask, width)));
+
+                let result: Expr = Expr::binary(op, value, count.clone());
+
+                // Flags are left alone by a shift of zero, and the carry and
+                // overflow of the others are not modelled
+                match count.as_const() {
+                    Some(0) => {},
+                    Some(_) => {
+                        self.assign(Var::Flag(Flag::Carry), Expr::Undefined(1));
+                        self.result_flags(&result);
+                        self.assign(Var::Flag(Flag::Overflow), Expr::Undefined(1));
+                    },
+                    None => self.undefined_flags()
+                }
+
+                self.write(instruction, 0, result);
+            },
+            Mnemonic::Rol | Mnemonic::Ror => {
+                let value: Expr = self.read(instruction, 0);
+                let width: u32 = value.width();
+                let count_mask: u64 = if width == 64 { 0x3F } else { 0x1F };
+
+                // Only rotates by a constant are modelled, as two shifts
+                let count: u64 = match instruction.op1_kind() {
+                    OpKind::Immediate8 => (instruction.immediate8() as u64 & count_mask) % width as u64,
+                    _ => return false
+                };
+
+                if count != 0 {
+                    let (first, second): (BinaryOp, BinaryOp) = if instruction.mnemonic() == Mnemonic::Rol {
+                        (BinaryOp::Shl, BinaryOp::LShr)
+                    } else {
+                        (BinaryOp::LShr, BinaryOp::Shl)
+                    };
+
+                    let result: Expr = Expr::binary(
+                        BinaryOp::Or,
+                        Expr::binary(first, value.clone(), Expr::constant(count, width)),
+                        Expr::binary(second, value, Expr::constant(width as u64 - count, width))
+                    );
+
+                    self.assign(Var::Flag(Flag::Carry), Expr::Undefined(1));
+                    self.assign(Var::Flag(Flag::Overflow), Expr::Undefined(1));
+                    self.write(instruction, 0, result);
+                }
+            },
+            Mnemonic::Adc | Mnemonic::Sbb => {
+                let op: BinaryOp = if instruction.mnemonic() == Mnemonic::Adc { BinaryOp::Add } else { BinaryOp::Sub };
+                let left: Expr = self.read(instruction, 0);
+                let carry: Expr = Expr::zero_extend(Expr::flag(Flag::Carry), left.width());
+                let result: Expr = Expr::binary(op, Expr::binary(op, left, self.read(instruction, 1)), carry);
+
+                self.assign(Var::Flag(Flag::Carry), Expr::Undefined(1));
+                self.result_flags(&result);
+                self.assign(Var::Flag(Flag::Overflow), Expr::Undefined(1));
+                self.write(instruction, 0, result);
+            },
+            Mnemonic::Imul if instruction.op_count() > 1 => {
+                // Two and three operand forms keep the low half
+                let (left, right): (Expr, Expr) = if instruction.op_count() == 2 {
+                    (self.read(instruction, 0), self.read(instruction, 1))
+                } else {
+                    (self.read(instruction, 1), self.read(instruction, 2))
+                };
+
+                self.undefined_flags();
+                self.write(instruction, 0, Expr::binary(BinaryOp::Mul, left, right));
+            },
+            Mnemonic::Mul | Mnemonic::Imul => {
+                let high: BinaryOp = if instruction.mnemonic() == Mnemonic::Mul { BinaryOp::UMulHigh } else { BinaryOp::SMulHigh };
+                let source: Expr = self.read(instruction, 0);
+                let width: u32 = source.width();
+                let (low_register, high_register): (Register, Register) = accumulator_pair(width);
+
+                let left: Expr = self.temp(self.read_register(low_register));
+                let source: Expr = self.temp(source);
+
+                self.undefined_flags();
+                self.write_register(low_register, Expr::binary(BinaryOp::Mul, left.clone(), source.clone()));
+                self.write_register(high_register, Expr::binary(high, left, source));
+            },
+            Mnemonic::Div | Mnemonic::Idiv => {
+                let signed: bool = instruction.mnemonic() == Mnemonic::Idiv;
+                let source: Expr = self.read(instruction, 0);
+                let width: u32 = source.width();
+                let (low_register, high_register): (Register, Register) = accumulator_pair(width);
+
+                // The dividend is the register pair, or ax for byte division
+                let dividend: Expr = if width == 8 {
+                    self.read_register(Register::AX)
+                } else {
+                    Expr::binary(BinaryOp::Concat, self.read_register(high_register), self.read_register(low_register))
+                };
+                let dividend: Expr = self.temp(dividend);
+                let divisor: Expr = Expr::Extend { operand: Box::new(source), width: width * 2, signed };
+                let divisor: Expr = self.temp(divisor);
+
+                let (quotient, remainder): (BinaryOp, BinaryOp) = if signed {
+                    (BinaryOp::SDiv, BinaryOp::SRem)
+                } else {
+                    (BinaryOp::UDiv, BinaryOp::URem)
+                };
+
+                self.undefined_flags();
+                self.write_register(low_register, Expr::truncate(Expr::binary(quotient, dividend.clone(), divisor.clone()), width));
+                self.write_register(high_register, Expr::truncate(Expr::binary(remainder, dividend, divisor), width));
+            },
+            Mnemonic::Cbw | Mnemonic::Cwde | Mnemonic::Cdqe => {
+                let (from, to): (Register, Register) = match instruction.mnemonic() {
+                    Mnemonic::Cbw => (Register::AL, Register::AX),
+                    Mnemonic::Cwde => (Register::AX, Register::EAX),
+                    _ => (Register::EAX, Register::RAX)
+                };
+
+                let value: Expr = Expr::sign_extend(self.read_register(from), to.size() as u32 * 8);
+                self.write_register(to, value);
+            },
+            Mnemonic::Cwd | Mnemonic::Cdq | Mnemonic::Cqo => {
+                let (from, to): (Register, Register) = match instruction.mnemonic() {
+                    Mnemonic::Cwd => (Register::AX, Register::DX),
+                    Mnemonic::Cdq => (Register::EAX, Register::EDX),
+                    _ => (Register::RAX, Register::RDX)
+                };
+
+                let width: u32 = from.size() as u32 * 8;
+                let value: Expr = Expr::binary(BinaryOp::AShr, self.read_register(from), Expr::constant(width as u64 - 1, width));
+                self.write_register(to, value);
+            },
+            _ if code != ConditionCode::None && instruction.flow_control() == FlowControl::ConditionalBranch => {
+                let target: Expr = self.read(instruction, 0);
+                self.emit(Stmt::Branch { condition: condition(code), target });
+            },
+            _ if code != ConditionCode::None && is_setcc(instruction.mnemonic()) => {
+                self.write(instruction, 0, Expr::zero_extend(condition(code), 8));
+            },
+            _ if code != ConditionCode::None && is_cmovcc(instruction.mnemonic()) => {
+                // The destination is written, and a 32-bit one zero extended,
+                // even when the move does not happen
+                let value: Expr = Expr::select(condition(code), self.read(instruction, 1), self.read(instruction, 0));
+                self.write(instruction, 0, value);
+            },
+            Mnemonic::Jmp => {
+                let target: Expr = self.read(instruction, 0);
+                self.emit(Stmt::Jump { target });
+            },
+            Mnemonic::Call => {
+                let target: Expr = self.read(instruction, 0);
+                self.emit(Stmt::Call { target });
+            },
+            Mnemonic::Ret => {
+                self.emit(Stmt::Return);
+            },
+            _ => return false
+        }
+
+        true
+    }
+
+
+    // Record an instruction the lifter does not model, followed by
+    // undefined values for the registers and flags it writes
+    fn unsupported(&mut self, instruction: &Instruction) {
+        self.emit(Stmt::Unsupported(format!("{:?}", instruction.mnemonic()).to_lowercase()));
+
+        let mut written: Vec<Register> = self.factory
+            .info(instruction)
+            .used_registers()
+            .iter()
+            .filter(|used| matches!(used.access(), OpAccess::Write | OpAccess::CondWrite | OpAccess::ReadWrite | OpAccess::ReadCondWrite))
+            .map(|used| if used.register().is_gpr() { used.register().full_register() } else { used.register() })
+            .collect();
+        written.sort_unstable();
+        written.dedup();
+
+        for register in written {
+            let width: u32 = Var::Register(register).width();
+            self.assign(Var::Register(register), Expr::Undefined(width));
+        }
+
+        if instruction.rflags_modified() & (RflagsBits::CF | RflagsBits::ZF | RflagsBits::SF | RflagsBits::OF) != 0 {
+            self.undefined_flags();
+        }
+    }
+
+
+    fn emit(&mut self, statement: Stmt) {
+        self.statements.push(statement);
+    }
+
+
+    fn assign(&mut self, dest: Var, value: Expr) {
+        self.emit(Stmt::Assign { dest, value });
+    }
+
+
+    // Copy a value into a new temporary so later writes can not change it
+    fn temp(&mut self, value: Expr) -> Expr {
+        let var: Var = Var::Temp(self.temps, value.width());
+        self.temps += 1;
+        self.assign(var, value);
+        Expr::var(var)
+    }
+
+
+    fn adjust_stack(&mut self, op: BinaryOp, width: u32) {
+        let value: Expr = Expr::binary(op, Expr::register(Register::RSP), Expr::constant(width as u64 / 8, 64));
+        self.assign(Var::Register(Register::RSP), value);
+    }
+
+
+    // Width of an operand in bits. Immediates take the width of the
+    // destination they combine with.
+    fn width(&self, instruction: &Instruction, operand: u32) -> u32 {
+        match instruction.op_kind(operand) {
+            OpKind::Register => instruction.op_register(operand).size() as u32 * 8,
+            OpKind::Memory => instruction.memory_size().size() as u32 * 8,
+            OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => 64,
+            _ if operand > 0 => self.width(instruction, 0),
+            _ => instruction.memory_size().size() as u32 * 8
+        }
+    }
+
+
+    // Effective address of the memory operand
+    fn address(&self, instruction: &Instruction) -> Expr {
+        if instruction.is_ip_rel_memory_operand() {
+            return Expr::constant(instruction.ip_rel_memory_address(), 64);
+        }
+
+        let mut address: Expr = Expr::constant(instruction.memory_displacement64(), 64);
+
+        if instruction.memory_index() != Register::None {
+            let index: Expr = self.read_register(instruction.memory_index());
+            let index: Expr = Expr::zero_extend(index, 64);
+            let scaled: Expr = Expr::binary(BinaryOp::Mul, index, Expr::constant(instruction.memory_index_scale() as u64, 64));
+            address = Expr::binary(BinaryOp::Add, scaled, address);
+        }
+
+        if instruction.memory_base() != Register::None {
+            let base: Expr = Expr::zero_extend(self.read_register(instruction.memory_base()), 64);
+            address = Expr::binary(BinaryOp::Add, base, address);
+        }
+
+        // fs and gs point at thread local storage, the others are flat
+        let segment: Register = instruction.segment_prefix();
+
+        if segment == Register::FS || segment == Register::GS {
+            address = Expr::binary(BinaryOp::Add, Expr::register(segment), address);
+        }
+
+        address
+    }
+
+
+    fn read(&self, instruction: &Instruction, operand: u32) -> Expr {
+        let width: u32 = self.width(instruction, operand);
+
+        match instruction.op_kind(operand) {
+            OpKind::Register => self.read_register(instruction.op_register(operand)),
+            OpKind::Memory => Expr::load(self.address(instruction), width),
+            OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => {
+                Expr::constant(instruction.near_branch_target(), 64)
+            },
+            _ => Expr::constant(instruction.immediate(operand), width)
+        }
+    }
+
+
+    fn write(&mut self, instruction: &Instruction, operand: u32, value: Expr) {
+        match instruction.op_kind(operand) {
+            OpKind::Register => self.write_register(instruction.op_register(operand), value),
+            _ => {
+                let address: Expr = self.address(instruction);
+                self.emit(Stmt::Store { address, value });
+            }
+        }
+    }
+
+
+    // Value of a register, cut out of the full register it is part of
+    fn read_register(&self, register: Register) -> Expr {
+        if !register.is_gpr() {
+            return Expr::register(register);
+        }
+
+        let full: Expr = Expr::register(register.full_register());
+        let width: u32 = register.size() as u32 * 8;
+
+        if is_high_byte(register) {
+            Expr::truncate(Expr::binary(BinaryOp::LShr, full, Expr::constant(8, 64)), 8)
+        } else {
+            Expr::truncate(full, width)
+        }
+    }
+
+
+    // Write a register. 32-bit writes clear the top half of the full
+    // register, 8 and 16-bit writes leave the other bits alone.
+    fn write_register(&mut self, register: Register, value: Expr) {
+        if !register.is_gpr() {
+            self.assign(Var::Register(register), value);
+            return;
+        }
+
+        let full: Register = register.full_register();
+        let width: u32 = register.size() as u32 * 8;
+
+        let merged: Expr = match width {
+            64 => value,
+            32 => Expr::zero_extend(value, 64),
+            _ => {
+                let shift: u64 = if is_high_byte(register) { 8 } else { 0 };
+                let kept: u64 = !(ir::mask(width) << shift);
+                let placed: Expr = Expr::binary(BinaryOp::Shl, Expr::zero_extend(value, 64), Expr::constant(shift, 64));
+
+                Expr::binary(
+                    BinaryOp::Or,
+                    Expr::binary(BinaryOp::And, Expr::register(full), Expr::constant(kept, 64)),
+                    placed
+                )
+            }
+        };
+
+        self.assign(Var::Register(full), merged);
+    }
+
+
+    // Flags of an addition or subtraction, before the result is written.
+    // inc and dec leave the carry flag alone.
+    fn arithmetic_flags(&mut self, op: BinaryOp, left: &Expr, right: &Expr, sets_carry: bool) {
+        let result: Expr = Expr::binary(op, left.clone(), right.clone());
+        let zero: Expr = Expr::constant(0, left.width());
+
+        let (carry, overflow): (Expr, BinaryOp) = if op == BinaryOp::Add {
+            (Expr::binary(BinaryOp::ULt, result.clone(), left.clone()), BinaryOp::AddOverflow)
+        } else {
+            (Expr::binary(BinaryOp::ULt, left.clone(), right.clone()), BinaryOp::SubOverflow)
+        };
+
+        if sets_carry {
+            self.assign(Var::Flag(Flag::Carry), carry);
+        }
+        self.assign(Var::Flag(Flag::Zero), Expr::binary(BinaryOp::Eq, result.clone(), zero.clone()));
+        self.assign(Var::Flag(Flag::Sign), Expr::binary(BinaryOp::SLt, result, zero));
+        self.assign(Var::Flag(Flag::Overflow), Expr::binary(overflow, left.clone(), right.clone()));
+    }
+
+
+    // Flags of a logic instruction, which clear carry and overflow
+    fn logic_flags(&mut self, result: &Expr) {
+        self.assign(Var::Flag(Flag::Carry), Expr::constant(0, 1));
+        self.result_flags(result);
+        self.assign(Var::Flag(Flag::Overflow), Expr::constant(0, 1));
+    }
+
+
+    // Zero and sign flags, which only depend on the result
+    fn result_flags(&mut self, result: &Expr) {
+        let zero: Expr = Expr::constant(0, result.width());
+
+        self.assign(Var::Flag(Flag::Zero), Expr::binary(BinaryOp::Eq, result.clone(), zero.clone()));
+        self.assign(Var::Flag(Flag::Sign), Expr::binary(BinaryOp::SLt, result.clone(), zero));
+    }
+
+
+    fn undefined_flags(&mut self) {
+        for flag in [Flag::Carry, Flag::Zero, Flag::Sign, Flag::Overflow] {
+            self.assign(Var::Flag(flag), Expr::Undefined(1));
+        }
+    }
+}
+
+
+// Registers holding the low and high halves for mul and div
+fn accumulator_pair(width: u32) -> (Register, Register) {
+    match width {
+        8 => (Register::AL, Register::AH),
+        16 => (Register::AX, Register::DX),
+        32 => (Register::EAX, Register::EDX),
+        _ => (Register::RAX, Register::RDX)
+    }
+}
+
+
+fn is_high_byte(register: Register) -> bool {
+    matches!(register, Register::AH | Register::BH | Register::CH | Register::DH)
+}
+
+
+fn is_setcc(mnemonic: Mnemonic) -> bool {
+    matches!(
+        mnemonic,
+        Mnemonic::Seto | Mnemonic::Setno | Mnemonic::Setb | Mnemonic::Setae | Mnemonic::Sete | Mnemonic::Setne
+            | Mnemonic::Setbe | Mnemonic::Seta | Mnemonic::Sets | Mnemonic::Setns | Mnemonic::Setp | Mnemonic::Setnp
+            | Mnemonic::Setl | Mnemonic::Setge | Mnemonic::Setle | Mnemonic::Setg
+    )
+}
+
+
+fn is_cmovcc(mnemonic: Mnemonic) -> bool {
+    matches!(
+        mnemonic,
+        Mnemonic::Cmovo | Mnemonic::Cmovno | Mnemonic::Cmovb | Mnemonic::Cmovae | Mnemonic::Cmove | Mnemonic::Cmovne
+            | Mnemonic::Cmovbe | Mnemonic::Cmova | Mnemonic::Cmovs | Mnemonic::Cmovns | Mnemonic::Cmovp | Mnemonic::Cmovnp
+            | Mnemonic::Cmovl | Mnemonic::Cmovge | Mnemonic::Cmovle | Mnemonic::Cmovg
+    )
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use iced_x86::Register;
+
+    use crate::disasm::LinearSweep;
+    use crate::ir::{self, Expr, Flag, Stmt, Var};
+
+    use super::lift_instruction;
+
+
+    // Lift 64-bit code and evaluate it with the given register values,
+    // returning the constant each register and flag ends up holding
+    fn run(code: &[u8], registers: &[(Register, u64)]) -> HashMap<Var, Expr> {
+        let mut state: HashMap<Var, Expr> = registers.iter()
+            .map(|(register, value)| (Var::Register(*register), Expr::constant(*value, 64)))
+            .collect();
+
+        for instruction in LinearSweep::new(code, 0x1000, 64) {
+            for statement in lift_instruction(&instruction).statements {
+                if let Stmt::Assign { dest, value } = statement {
+                    let value: Expr = value.map(&mut |node| match node {
+                        Expr::Var(var) => state.get(&var).cloned().unwrap_or(Expr::Var(var)),
+                        other => other
+                    });
+                    state.insert(dest, ir::simplify(value));
+                }
+            }
+        }
+
+        state
+    }
+
+
+    fn value(state: &HashMap<Var, Expr>, var: Var) -> u64 {
+        match state.get(&var).and_then(Expr::as_const) {
+            Some(value) => value,
+            None => panic!("{} is not constant: {:?}", var, state.get(&var))
+        }
+    }
+
+
+    fn register(state: &HashMap<Var, Expr>, register: Register) -> u64 {
+        value(state, Var::Register(register))
+    }
+
+
+    fn flags(state: &HashMap<Var, Expr>) -> [u64; 4] {
+        [Flag::Carry, Flag::Zero, Flag::Sign, Flag::Overflow].map(|flag| value(state, Var::Flag(flag)))
+    }
+
+
+    #[test]
+    fn cmp_sets_flags() {
+        // cmp eax,ebx
+        let less: HashMap<Var, Expr> = run(&[0x39, 0xD8], &[(Register::RAX, 5), (Register::RBX, 7)]);
+        assert_eq!(flags(&less), [1, 0, 1, 0]);
+
+        let equal: HashMap<Var, Expr> = run(&[0x39, 0xD8], &[(Register::RAX, 9), (Register::RBX, 9)]);
+        assert_eq!(flags(&equal), [0, 1, 0, 0]);
+
+        // Only the low 32 bits are compared
+        let high: HashMap<Var, Expr> = run(&[0x39, 0xD8], &[(Register::RAX, 0x1_0000_0002), (Register::RBX, 1)]);
+        assert_eq!(flags(&high), [0, 0, 0, 0]);
+        assert_eq!(register(&high, Register::RAX), 0x1_0000_0002);
+    }
+
+
+    #[test]
+    fn sub_sets_flags() {
+        // sub eax,ebx
+        let overflow: HashMap<Var, Expr> = run(&[0x29, 0xD8], &[(Register::RAX, 0x8000_0000), (Register::RBX, 1)]);
+        assert_eq!(register(&overflow, Register::RAX), 0x7FFF_FFFF);
+        assert_eq!(flags(&overflow), [0, 0, 0, 1]);
+
+        // sub rax,rbx
+        let borrow: HashMap<Var, Expr> = run(&[0x48, 0x29, 0xD8], &[(Register::RAX, 1), (Register::RBX, 2)]);
+        assert_eq!(register(&borrow, Register::RAX), u64::MAX);
+        assert_eq!(flags(&borrow), [1, 0, 1, 0]);
+    }
+
+
+    #[test]
+    fn div_writes_quotient_and_remainder() {
+        // div ecx
+        let state: HashMap<Var, Expr> = run(&[0xF7, 0xF1], &[(Register::RAX, 100), (Register::RDX, 0), (Register::RCX, 7)]);
+        assert_eq!(register(&state, Register::RAX), 14);
+        assert_eq!(register(&state, Register::RDX), 2);
+
+        // div rcx, dividing rdx:rax
+        let wide: HashMap<Var, Expr> = run(&[0x48, 0xF7, 0xF1], &[(Register::RAX, 0), (Register::RDX, 1), (Register::RCX, 0x10)]);
+        assert_eq!(register(&wide, Register::RAX), 0x1000_0000_0000_0000);
+        assert_eq!(register(&wide, Register::RDX), 0);
+
+        // idiv ecx with -7 in edx:eax, the 32-bit results are zero extended
+        let signed: HashMap<Var, Expr> = run(
+            &[0xF7, 0xF9],
+            &[(Register::RAX, 0xFFFF_FFF9), (Register::RDX, 0xFFFF_FFFF), (Register::RCX, 2)]
+        );
+        assert_eq!(register(&signed, Register::RAX), 0xFFFF_FFFD);
+        assert_eq!(register(&signed, Register::RDX), 0xFFFF_FFFF);
+    }
+
+
+    #[test]
+    fn writes_to_32_bit_registers_zero_extend() {
+        // mov eax,ebx
+        let moved: HashMap<Var, Expr> = run(&[0x89, 0xD8], &[(Register::RAX, u64::MAX), (Register::RBX, 0x1_2345_6789)]);
+        assert_eq!(register(&moved, Register::RAX), 0x2345_6789);
+
+        // add eax,1
+        let added: HashMap<Var, Expr> = run(&[0x83, 0xC0, 0x01], &[(Register::RAX, u64::MAX)]);
+        assert_eq!(register(&added, Register::RAX), 0);
+        assert_eq!(flags(&added), [1, 1, 0, 0]);
+
+        // mov ax,bx keeps the upper bits
+        let narrow: HashMap<Var, Expr> = run(&[0x66, 0x89, 0xD8], &[(Register::RAX, u64::MAX), (Register::RBX, 0x1234)]);
+        assert_eq!(register(&narrow, Register::RAX), 0xFFFF_FFFF_FFFF_1234);
+    }
+}
@@ -4,263 +4,941 @@
 // Description: Main entrypoint script for chisel. Contains basic procedures
 //              for gathering ELF file and program data.
 
-use iced_x86::{Decoder, DecoderOptions, Formatter, NasmFormatter, Instruction};
-use std::collections::HashMap;
+use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command};
+use iced_x86::{Instruction, SymbolResolver};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path;
-use std::env;
+use std::rc::Rc;
 use std::fs;
 use std::process::exit;
 
-// Import modules
-mod elf;
-mod util;
-mod patcher;
+use chisel::{cfg, decompile, disasm, elf, export, functions, ir, lift, patcher, reassemble, symbols, util, xrefs, ChiselError, CodeRegion, Elf};
+
+
+// Incoming references listed above an instruction before eliding the rest
+const MAX_LISTED_XREFS: usize = 4;
+
+// Characters of a referenced string shown in a listing comment
+const MAX_STRING_COMMENT: usize = 64;
+
+
+// Options collected from the command line
+struct Options {
+    file_path: String,
+    patch_file_path: Option<String>,
+    show_symbols: bool,
+    show_dynamic: bool,
+    show_relocations: bool,
+    show_notes: bool,
+    base_address: Option<u64>,
+    recursive: bool,
+    show_functions: bool,
+    show_cfg: bool,
+    show_ir: bool,
+    show_decompile: bool,
+    export_path: Option<String>,
+    xref_targets: Vec<String>,
+    syntax: disasm::Syntax,
+    columns: ListingColumns,
+    selection: Selection,
+    asm_path: Option<String>,
+}
+
+
+// Part of the code to list, as chosen on the command line
+enum Selection {
+    All,
+    Range(u64, u64),
+    OffsetRange(u64, u64),
+    From(String, Option<usize>),
+    Function(String),
+}
+
+
+// Optional columns printed between the address and the instruction text
+#[derive(Clone, Copy, Default)]
+struct ListingColumns {
+    offset: bool,
+    section: bool,
+    length: bool,
+    bytes: bool,
+}
+
+
+// Analysis results consulted while printing the listing
+struct ListingContext {
+    functions: Vec<functions::Function>,
+    xrefs: xrefs::XrefDatabase,
+    strings: BTreeMap<u64, String>,
+    columns: ListingColumns,
+}
+
+
+fn build_cli() -> Command {
+    Command::new("chisel")
+        .about("Decompile and analyze x86/x86-64 ELF binaries")
+        .arg(Arg::new("executable")
+            .value_name("EXECUTABLE")
+            .help("ELF binary to analyze")
+            .required(true))
+        .arg(Arg::new("patch")
+            .short('p')
+            .value_name("PATCH_FILE")
+            .help("Toggle binary patching mode, injecting the given .bin file"))
+        .arg(Arg::new("symbols")
+            .short('s')
+            .long("symbols")
+            .action(ArgAction::SetTrue)
+            .help("Print the .symtab and .dynsym symbol tables"))
+        .arg(Arg::new("dynamic")
+            .short('d')
+            .long("dynamic")
+            .action(ArgAction::SetTrue)
+            .help("Print the dynamic section and shared library dependencies"))
+        .arg(Arg::new("relocs")
+            .short('r')
+            .long("relocs")
+            .action(ArgAction::SetTrue)
+            .help("Print the relocation tables"))
+        .arg(Arg::new("notes")
+            .short('n')
+            .long("notes")
+            .action(ArgAction::SetTrue)
+            .help("Print the build ID, ABI tag and GNU property notes"))
+        .arg(Arg::new("base")
+            .short('b')
+            .long("base")
+            .value_name("ADDRESS")
+            .value_parser(parse_address)
+            .help("Rebase a position independent binary to the given load address"))
+        .arg(Arg::new("mode")
+            .short('m')
+            .long("mode")
+            .value_name("MODE")
+            .value_parser(["linear", "recursive"])
+            .default_value("linear")
+            .help("Disassembly technique, linear sweep or recursive descent"))
+        .arg(Arg::new("functions")
+            .short('f')
+            .long("functions")
+            .action(ArgAction::SetTrue)
+            .help("Discover function boundaries and label them in the listing"))
+        .arg(Arg::new("cfg")
+            .short('g')
+            .long("cfg")
+            .action(ArgAction::SetTrue)
+            .help("Print the basic block control flow graph of each function"))
+        .arg(Arg::new("ir")
+            .short('i')
+            .long("ir")
+            .action(ArgAction::SetTrue)
+            .help("Print each function lifted to the analysis IR (x86-64 only)"))
+        .arg(Arg::new("decompile")
+            .short('D')
+            .long("decompile")
+            .action(ArgAction::SetTrue)
+            .help("Print pseudo-C for each function (x86-64 only)"))
+        .arg(Arg::new("export")
+            .short('e')
+            .long("export")
+            .value_name("DIRECTORY")
+            .help("Write per-function CFGs and the call graph as Graphviz DOT and JSON"))
+        .arg(Arg::new("xrefs")
+            .short('x')
+            .long("xrefs")
+            .value_name("ADDRESS|SYMBOL")
+            .action(ArgAction::Append)
+            .help("Print the cross references to an address, symbol or function"))
+        .arg(Arg::new("syntax")
+            .short('a')
+            .long("syntax")
+            .value_name("SYNTAX")
+            .value_parser(["nasm", "intel", "masm", "gas", "fast"])
+            .default_value("nasm")
+            .help("Assembly syntax of the listing"))
+        .arg(Arg::new("columns")
+            .short('c')
+            .long("columns")
+            .value_name("COLUMNS")
+            .value_parser(["offset", "section", "length", "bytes"])
+            .value_delimiter(',')
+            .action(ArgAction::Append)
+            .help("Extra listing columns, a comma separated list of offset, section, length and bytes"))
+        .arg(Arg::new("range")
+            .short('R')
+            .long("range")
+            .value_name("START:END")
+            .value_parser(parse_range)
+            .help("Only list the code between two virtual addresses"))
+        .arg(Arg::new("offset-range")
+            .short('O')
+            .long("offset-range")
+            .value_name("START:END")
+            .value_parser(parse_range)
+            .help("Only list the code between two file offsets"))
+        .arg(Arg::new("start")
+            .short('S')
+            .long("start")
+            .value_name("ADDRESS|SYMBOL")
+            .help("Only list the code from an address or symbol onwards"))
+        .arg(Arg::new("count")
+            .short('C')
+            .long("count")
+            .value_name("COUNT")
+            .value_parser(clap::value_parser!(usize))
+            .requires("start")
+            .help("Number of instructions to list with --start"))
+        .arg(Arg::new("function")
+            .short('F')
+            .long("function")
+            .value_name("NAME|ADDRESS")
+            .help("Only list the function with the given name or containing the given address"))
+        .arg(Arg::new("asm")
+            .short('A')
+            .long("asm")
+            .value_name("FILE")
+            .help("Write the program's code and data as a reassemblable NASM source file"))
+        .group(ArgGroup::new("selection")
+            .args(["range", "offset-range", "start", "function"]))
+}
+
+
+// Parse a hex (0x prefixed) or decimal address from the command line
+fn parse_address(value: &str) -> Result<u64, String> {
+    let parsed = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse::<u64>()
+    };
+
+    parsed.map_err(|_| format!("'{}' is not a valid address", value))
+}
+
+
+// Collect the listing columns requested with --columns
+fn parse_columns(matches: &ArgMatches) -> ListingColumns {
+    let mut columns: ListingColumns = ListingColumns::default();
+
+    for column in matches.get_many::<String>("columns").into_iter().flatten() {
+        match column.as_str() {
+            "offset" => columns.offset = true,
+            "section" => columns.section = true,
+            "length" => columns.length = true,
+            "bytes" => columns.bytes = true,
+            _ => {}
+        }
+    }
+
+    columns
+}
+
+
+// Parse a START:END pair of addresses or offsets from the command line
+fn parse_range(value: &str) -> Result<(u64, u64), String> {
+    let (start, end) = value.split_once(':')
+        .ok_or_else(|| format!("'{}' is not a START:END range", value))?;
+
+    let (start, end): (u64, u64) = (parse_address(start)?, parse_address(end)?);
+
+    if start >= end {
+        return Err(format!("range '{}' ends before it starts", value));
+    }
+
+    Ok((start, end))
+}
+
+
+// Work out which part of the code was selected for listing
+fn parse_selection(matches: &ArgMatches) -> Selection {
+    if let Some((start, end)) = matches.get_one::<(u64, u64)>("range") {
+        Selection::Range(*start, *end)
+    } else if let Some((start, end)) = matches.get_one::<(u64, u64)>("offset-range") {
+        Selection::OffsetRange(*start, *end)
+    } else if let Some(start) = matches.get_one::<String>("start") {
+        Selection::From(start.clone(), matches.get_one::<usize>("count").copied())
+    } else if let Some(name) = matches.get_one::<String>("function") {
+        Selection::Function(name.clone())
+    } else {
+        Selection::All
+    }
+}
 
 
 fn main() {
     // Collect our execution args
-    let args: Vec<String> = env::args().collect();
-    let mut inject_mode: bool = false;
-    let mut patch_file_path: &String = &"".to_string();
-
-    // Grab our filepath from our options
-    if &args.len() < &2 {
-        // No file given, terminate
-        util::print_help();
-        exit(0);
-    }
-
-    // Check if the arguments we have include the patching flag and file
-    if &args.len() > &2 {
-        if &args[2] == "-p" {
-            if &args.len() >= &4 {
-                inject_mode = true;
-                patch_file_path = &args[3];
-            } else {
-                util::print_help();
-                exit(0);    
-            }
-        } else {
-            // More than 1 arg but no patching flag given, terminate
-            util::print_help();
-            exit(0);
-        }
-    }
+    let matches: ArgMatches = build_cli().get_matches();
+
+    let options: Options = Options {
+        file_path: matches.get_one::<String>("executable").unwrap().clone(),
+        patch_file_path: matches.get_one::<String>("patch").cloned(),
+        show_symbols: matches.get_flag("symbols"),
+        show_dynamic: matches.get_flag("dynamic"),
+        show_relocations: matches.get_flag("relocs"),
+        show_notes: matches.get_flag("notes"),
+        base_address: matches.get_one::<u64>("base").copied(),
+        recursive: matches.get_one::<String>("mode").is_some_and(|mode| mode == "recursive"),
+        show_functions: matches.get_flag("functions"),
+        show_cfg: matches.get_flag("cfg"),
+        show_ir: matches.get_flag("ir"),
+        show_decompile: matches.get_flag("decompile"),
+        export_path: matches.get_one::<String>("export").cloned(),
+        xref_targets: matches.get_many::<String>("xrefs").map(|targets| targets.cloned().collect()).unwrap_or_default(),
+        syntax: match matches.get_one::<String>("syntax").map(String::as_str) {
+            Some("intel") => disasm::Syntax::Intel,
+            Some("masm") => disasm::Syntax::Masm,
+            Some("gas") => disasm::Syntax::Gas,
+            Some("fast") => disasm::Syntax::Fast,
+            _ => disasm::Syntax::Nasm
+        },
+        columns: parse_columns(&matches),
+        selection: parse_selection(&matches),
+        asm_path: matches.get_one::<String>("asm").cloned(),
+    };
+
+    let file_path: &String = &options.file_path;
 
-    let file_path: &String = &args[1];
-    
     if path::Path::new(file_path).exists() {
         println!("File exists, reading '{}'...", file_path);
-        
-        let contents: Result<Vec<u8>, std::io::Error> = fs::read(file_path);
-        
-        if contents.is_ok() {
 
-            let bytes: &Vec<u8> = &contents.expect("");
-            let magic_num: &[u8] = &bytes[0..4];
-        
-            // Check to see if our file contains the ELF magic number
-            if magic_num == elf::MAGIC_NUMBER {
+        if let Err(err) = run(&options) {
+            println!("[Error] {}", err);
+            exit(-1);
+        }
+    } else {
+        println!("[Error] File '{}' does not exist", file_path);
+        exit(-1);
+    }
+}
+
+
+fn run(options: &Options) -> Result<(), ChiselError> {
+    let file_path: &str = &options.file_path;
+    let bytes: Vec<u8> = fs::read(file_path)?;
 
-                println!("Found ELF Magic Number...");
-                println!("Parsing File Header...");
+    // Check to see if our file contains the ELF magic number
+    if !Elf::is_elf(&bytes) {
+        return Err(ChiselError::BadMagic);
+    }
+
+    println!("Found ELF Magic Number...");
+    println!("Parsing File Header...");
 
-                // Build the File Header data structure
-                let file_header: elf::FileHeader = util::build_file_header(bytes);
+    // Build the ELF object model from the file contents
+    let binary: Elf = Elf::parse(bytes)?;
+    let file_header: &elf::FileHeader = binary.file_header();
 
-                println!("\t- Found {} program header entries {} bytes in length", file_header.phnum, file_header.phentsize);
-                println!("\t- Found {} section header entries {} bytes in length", file_header.shnum, file_header.shentsize);
-                println!("\t- Found .shstrtab section at index {}", file_header.shstrndx); 
+    println!("\t- Found {} program header entries {} bytes in length", file_header.phnum, file_header.phentsize);
+    println!("\t- Found {} section header entries {} bytes in length", file_header.shnum, file_header.shentsize);
+    println!("\t- Found .shstrtab section at index {}", file_header.shstrndx);
 
-                println!("\n==== File Header ====");
-                util::pp_file_header(&file_header);
+    println!("\n==== File Header ====");
+    util::pp_file_header(file_header);
 
 
-                println!("\nParsing Section Headers...");
+    println!("\nParsing Section Headers...");
 
-                // Determine the shstrtab offset.
-                // This is found by taking the string table index and multiplying it by the section header entry size, then
-                // adding this to the initial section header offset.
-                let shstrtab_offset: u64 = file_header.shoff + (file_header.shentsize as u64 * file_header.shstrndx as u64);
+    println!("\n==== Sections ====");
 
-                // Build a read-only version of the .shstrtab section
-                let shstrtab_section: elf::SectionHeader = util::build_section_header(
-                    bytes,
-                    shstrtab_offset as usize,
-                    file_header.shstrndx,
-                    file_header.is_x86_64
-                );
+    for section_header in binary.sections() {
+        util::pp_section_header(section_header);
+    }
 
-                // Define all of our offsets for the shstrtab, and build a u8 buffer of the data
-                let shstrtab_start: u64 = shstrtab_section.offset;
-                let shstrtab_end: u64 = shstrtab_section.offset + shstrtab_section.size;
-                let shstrtab_data: Vec<u8> = bytes[shstrtab_start as usize..shstrtab_end as usize].to_vec();
+    println!("\nParsing Program Segments...");
 
-                println!("\t- Found .shstrtab section");
+    println!("\n==== Program Segments ====");
 
-                println!("\n==== Sections ====");
+    for program_header in binary.segments() {
+        util::pp_program_header(program_header);
+    }
 
-                let mut section_table_map: HashMap<String, elf::SectionHeader> = HashMap::new();
-                let mut section_table_offset: u64 = file_header.shoff;
-                let mut section_table_count: i32 = 0;
 
-                // Iterate through number of section headers
-                for i in 0..file_header.shnum {
+    if options.show_symbols {
+        println!("\n==== Symbol Tables ====");
+
+        for section in binary.sections() {
+            if section.section_type == elf::SHT_SYMTAB || section.section_type == elf::SHT_DYNSYM {
+                let symbols: Vec<elf::Symbol> = binary.symbol_table(section)?;
+                util::pp_symbol_table(section, &symbols);
+            }
+        }
+    }
+
+
+    if options.show_dynamic {
+        println!("\n==== Dynamic Section ====");
+
+        let entries: Vec<elf::DynamicEntry> = binary.dynamic_entries()?;
+
+        for library in binary.needed_libraries()? {
+            println!("- Required Library: {}", library);
+        }
+        println!();
 
-                    // Build section header data structure
-                    let section_header: elf::SectionHeader = util::build_section_header(
-                        bytes, 
-                        section_table_offset as usize,
-                        i,
-                        file_header.is_x86_64
-                    );
+        util::pp_dynamic_section(&entries);
+    }
+
+
+    if options.show_relocations {
+        println!("\n==== Relocation Tables ====");
+
+        for section in binary.sections() {
+            if section.section_type == elf::SHT_RELA || section.section_type == elf::SHT_REL {
+                let relocations: Vec<elf::Relocation> = binary.relocation_table(section)?;
+                util::pp_relocation_table(section, &relocations, file_header.is_x86_64);
+            }
+        }
+    }
+
+
+    if options.show_notes {
+        println!("\n==== Notes ====");
+
+        for (source, notes) in binary.notes()? {
+            util::pp_notes(&source, &notes);
+        }
+    }
 
-                    // Determine the section name for each section using the shstrtab data
-                    let section_name: String = util::parse_section_name(&shstrtab_data, section_header.name_idx as usize);
 
-                    util::pp_section_header(&section_header, section_table_count, &section_name);
+    // Now that we have all the sections, disassemble every executable region
+    let mut regions: Vec<CodeRegion> = binary.code_regions()?;
+    let mut seeds: Vec<u64> = binary.code_seeds()?;
 
-                    section_table_map.insert(section_name, section_header);
+    // Decode at the address the code runs at, shifted by the load bias when rebasing
+    let mut load_bias: u64 = 0;
 
-                    // Update the section table offset counter based on the section header size
-                    section_table_offset += file_header.shentsize as u64;
-                    section_table_count += 1;
+    if let Some(base_address) = options.base_address {
+        match binary.load_bias(base_address) {
+            Some(bias) => {
+                println!("Rebasing image to {:#x}...", base_address);
+                load_bias = bias;
+
+                for region in regions.iter_mut() {
+                    region.vaddr = region.vaddr.wrapping_add(bias);
+                }
+                for seed in seeds.iter_mut() {
+                    *seed = seed.wrapping_add(bias);
                 }
+            },
+            None => println!("[Warning] Binary is not position independent, ignoring base address {:#x}", base_address)
+        }
+    }
 
-                println!("\nParsing Program Segments...");
-                
-                println!("\n==== Program Segments ====");
-
-                let mut program_table_offset = file_header.phoff;
-                let mut program_table_count: i32 = 0;
-                let mut pt_note_index: usize = 0;
-                let mut pt_note_offset: usize = 0;
-
-                // Iterate through number of Program Headers
-                for i in 0..file_header.phnum {
-                    // Build Program Header data structure
-                    let program_header: elf::ProgramHeader = util::build_program_header(
-                        bytes, 
-                        program_table_offset as usize,
-                        i,
-                        file_header.is_x86_64
-                    );
-
-                    // Parse the program name using the program type
-                    let program_name: String = util::parse_program_segment_type(program_header.program_type);
-
-                    if (program_name == "PT_NOTE") && (pt_note_index == 0) {
-                        pt_note_offset = program_table_offset as usize;
-                        pt_note_index = i as usize;
-                    }
+    // Function starts are printed as labels in the listing
+    let mut context: ListingContext = ListingContext {
+        functions: Vec::new(),
+        xrefs: xrefs::XrefDatabase::default(),
+        strings: BTreeMap::new(),
+        columns: options.columns
+    };
+
+    let analyze_functions: bool = options.show_functions
+        || options.show_cfg
+        || options.show_ir
+        || options.show_decompile
+        || options.export_path.is_some()
+        || !options.xref_targets.is_empty()
+        || options.asm_path.is_some()
+        || matches!(options.selection, Selection::From(..) | Selection::Function(_));
+
+    if analyze_functions {
+        context.functions = functions::discover_functions(&binary, &regions, load_bias)?;
+    }
+
+    // Branch and memory operands are named after the symbols, PLT stubs,
+    // functions and sections they point into
+    let symbol_map: Rc<symbols::SymbolMap> = Rc::new(symbols::SymbolMap::from_binary(&binary, &regions, &context.functions, load_bias)?);
+    let resolver: Box<dyn SymbolResolver> = Box::new(symbols::ElfSymbolResolver::new(Rc::clone(&symbol_map)));
+
+    let mut formatter: disasm::AsmFormatter = disasm::AsmFormatter::new(options.syntax, Some(resolver));
+    let mut output = String::new();
+
+
+    let graphs: Vec<cfg::ControlFlowGraph> = if analyze_functions {
+        cfg::build_cfgs(&context.functions, &regions, binary.bitness())
+    } else {
+        Vec::new()
+    };
+
+    if analyze_functions {
+        if options.show_functions {
+            println!("\n==== Functions ====");
+            pp_functions(&context.functions);
+        }
 
-                    util::pp_program_header(&program_header, program_table_count, &program_name);
+        if options.show_cfg {
+            println!("\n==== Control Flow Graphs ====");
 
-                    // Update the program header table offset counter based on the program header size
-                    program_table_offset += file_header.phentsize as u64;
-                    program_table_count += 1;
+            for graph in graphs.iter() {
+                pp_cfg(graph, &mut formatter);
+            }
+        }
+
+        if options.show_ir {
+            println!("\n==== Lifted IR ====");
+
+            if file_header.is_x86_64 {
+                for graph in graphs.iter() {
+                    pp_ir_function(&lift::lift_function(graph));
                 }
+            } else {
+                println!("[Warning] Lifting is only supported for x86-64 binaries");
+            }
+        }
+
+        if let Some(export_path) = &options.export_path {
+            println!("\n==== Exporting Graphs ====\n");
+            export_graphs(export_path, &graphs, &mut formatter)?;
+        }
+    }
+
+    let code_map: Option<disasm::CodeMap> = if options.recursive {
+        println!("\nRecursive descent from {} seed addresses...", seeds.len());
+        Some(disasm::recursive_descent(&regions, &seeds, binary.bitness()))
+    } else {
+        None
+    };
 
-                let note_segment: elf::ProgramHeader = util::build_program_header(
-                    bytes, 
-                    pt_note_offset,
-                    pt_note_index as u16,
-                    file_header.is_x86_64
-                );
-
-
-                // Now that we have all the sections, spit out the .text section and start a linear disassembly
-                let text_section: &elf::SectionHeader = section_table_map.get(".text").unwrap();
-                let text_section_offset: usize = text_section.offset as usize;
-                let text_section_end: usize = text_section_offset + text_section.size as usize;
-
-                // Buffer of text section data
-                let text_section_buff: &[u8] = &bytes[text_section_offset..text_section_end];
-
-                // Offsets for resizing buffer
-                let mut instr_start: usize = 0;
-                let mut instr_end: usize = 1;
-                let mut ip_offset: u64 = text_section.offset;
-
-                // Define our instruction buffer
-                let mut instr_bytes: &[u8] = &text_section_buff[instr_start..instr_end];
-
-                // Define our decoder and icedx86 variables
-                let mut decoder: Decoder = Decoder::with_ip(64, instr_bytes, text_section.offset, DecoderOptions::NONE);
-                let mut formatter: NasmFormatter = NasmFormatter::new();
-                let mut instruction: Instruction = Instruction::default();
-                let mut output = String::new();
-
-                // Specify options for our NASM instruction formatter
-                // Formatting and linear sweep pattern partially borrowed from icedx86 docs
-                // https://docs.rs/iced-x86/latest/iced_x86/#disassemble-decode-and-format-instructions
-                formatter.options_mut().set_digit_separator("`");
-                formatter.options_mut().set_first_operand_char_index(10);
-
-                
-                println!("==== Text Section Analysis ====\n");
-
-                while decoder.can_decode() {
-                    // Decode the instruction sub-buffer
-                    decoder.decode_out(&mut instruction);
-            
-                    if instruction.is_invalid() {
-                        
-                        // Instruction invalid
-                        // Increase the buffer size by 1, then try to parse again
-                        instr_end = instr_end + 1;
-                        instr_bytes = &text_section_buff[instr_start..instr_end];
-                        decoder = Decoder::with_ip(64, instr_bytes, ip_offset, DecoderOptions::NONE);
-
-                    } else {
-
-                        // Got a valid instruction
-                        // Format the instruction for printing
-                        output.clear();
-                        formatter.format(&instruction, &mut output);
-
-                        // Print the instruction to an output assembly file
-                        println!("{:016X}\t{}", instruction.ip(), output);
-
-                        // Reset the buffer start to the end of the previous buffer, then try to parse again
-                        instr_start = instr_end;
-
-                        // If the instruction end index is less than the buffer, increase it
-                        if (instr_end + 1) < text_section_buff.len() {
-                            instr_end = instr_end + 1;
-                        }
-                    
-                        // Resize the instruction buffer and parse it again
-                        instr_bytes = &text_section_buff[instr_start..instr_end];
-                        ip_offset = text_section.offset + instr_start as u64;
-                        decoder = Decoder::with_ip(64, instr_bytes, ip_offset, DecoderOptions::NONE);
+    // Incoming references are noted above each instruction in the listing
+    context.xrefs = xrefs::build_xrefs(&binary, &regions, code_map.as_ref(), load_bias);
+
+    // Strings referenced by data operands are shown as comments
+    let data_targets: BTreeSet<u64> = context.xrefs.iter()
+        .filter(|xref| is_data_xref(xref.kind))
+        .map(|xref| xref.to)
+        .collect();
+
+    for target in data_targets {
+        if let Some(string) = binary.string_at(target.wrapping_sub(load_bias)) {
+            context.strings.insert(target, string);
+        }
+    }
+
+    if !options.xref_targets.is_empty() {
+        println!("\n==== Cross References ====");
+
+        for target in options.xref_targets.iter() {
+            let address: u64 = resolve_target(target, &binary, &context.functions, load_bias)?;
+            pp_xrefs(target, address, &context);
+        }
+    }
+
+    println!("\n==== Code Analysis ====");
+
+    if regions.is_empty() {
+        println!("\n[Warning] Binary has no executable sections or segments");
+    }
+
+    let listing_regions: Vec<(&CodeRegion, CodeRegion)> = select_regions(&options.selection, &regions, &binary, &context, load_bias)?;
+
+    if listing_regions.is_empty() && !regions.is_empty() {
+        println!("\n[Warning] Selection does not cover any executable code");
+    }
+
+    for (source, region) in listing_regions.iter() {
+        println!(
+            "\n{} @ {:#x} (offset {:#x}, {} bytes)\n",
+            region.name, region.vaddr, region.offset, region.data.len()
+        );
+
+        match &code_map {
+            Some(code_map) => pp_code_map(source, region, code_map, &context, &mut formatter),
+            None => {
+                // Decode whole instructions, letting the last one run past the selection
+                let code: &[u8] = &source.data[(region.vaddr - source.vaddr) as usize..];
+                let end: u64 = region.end();
+
+                for instruction in disasm::LinearSweep::new(code, region.vaddr, binary.bitness()) {
+                    if instruction.ip() >= end {
+                        break;
                     }
+                    pp_instruction(&instruction, source, &context, &mut formatter, &mut output);
                 }
+            }
+        }
+    }
 
 
-                if inject_mode {
+    if options.show_decompile {
+        println!("\n==== Decompilation ====");
 
-                    println!("\n==== Injecting Payload To Binary ====\n");
+        if file_header.is_x86_64 {
+            // Only the functions within the selection are decompiled
+            let selected = |address: u64| listing_regions.iter()
+                .any(|(_, region)| address >= region.vaddr && address < region.end());
 
-                    patcher::patch_binary(
-                        bytes.to_vec(),
-                        file_path.to_string(),
-                        &patch_file_path,
-                        &file_header,
-                        section_table_map,
-                        note_segment
-                    );
+            for (function, graph) in context.functions.iter().zip(graphs.iter()) {
+                if function.source == functions::FunctionSource::PltStub || !selected(graph.entry) {
+                    continue;
                 }
 
+                println!();
+                print!("{}", decompile::decompile(&lift::lift_function(graph), &symbol_map, &context.functions, &context.strings));
+            }
+        } else {
+            println!("[Warning] Decompilation is only supported for x86-64 binaries");
+        }
+    }
 
-            } else {
-                println!("[Error] Could not find magic number, is this an ELF executable?")
+
+    if let Some(asm_path) = &options.asm_path {
+        println!("\n==== Reassembly ====\n");
+
+        if file_header.elf_type == elf::ET_REL {
+            println!("[Warning] Relocatable objects can not be reassembled, skipping '{}'", asm_path);
+        } else {
+            if !context.functions.iter().any(|function| function.name == "main") {
+                println!("[Warning] No main function found, the output will not link against the C runtime");
             }
+
+            let source: String = reassemble::reassemble(&binary, &regions, &context.functions, load_bias)?;
+            fs::write(asm_path, source)?;
+            println!("Wrote NASM source to '{}'", asm_path);
+        }
+    }
+
+
+    if let Some(patch_file_path) = &options.patch_file_path {
+
+        println!("\n==== Injecting Payload To Binary ====\n");
+
+        patcher::patch_binary(
+            &binary,
+            file_path.to_string(),
+            patch_file_path
+        )?;
+    }
+
+    Ok(())
+}
+
+
+// Narrow the code regions down to the part selected on the command line.
+// Each selected part is paired with the region it was cut from.
+fn select_regions<'r, 'a>(
+    selection: &Selection,
+    regions: &'r [CodeRegion<'a>],
+    binary: &Elf,
+    context: &ListingContext,
+    load_bias: u64) -> Result<Vec<(&'r CodeRegion<'a>, CodeRegion<'a>)>, ChiselError> {
+
+    let (start, end): (u64, u64) = match selection {
+        Selection::All => return Ok(regions.iter().map(|region| (region, region.clone())).collect()),
+        Selection::OffsetRange(start, end) => {
+            return Ok(regions.iter()
+                .filter_map(|region| region.clip_offsets(*start, *end).map(|clipped| (region, clipped)))
+                .collect());
+        },
+        Selection::Range(start, end) => (*start, *end),
+        Selection::From(target, count) => {
+            let start: u64 = resolve_target(target, binary, &context.functions, load_bias)?;
+
+            // Stop after the given number of instructions, decoded from start
+            let end: u64 = match (count, disasm::find_region(regions, start)) {
+                (Some(count), Some(region)) => {
+                    let code: &[u8] = &region.data[(start - region.vaddr) as usize..];
+
+                    disasm::LinearSweep::new(code, start, binary.bitness())
+                        .take(*count)
+                        .last()
+                        .map_or(start, |instruction| instruction.next_ip())
+                },
+                _ => u64::MAX
+            };
+
+            (start, end)
+        },
+        Selection::Function(name) => {
+            let function: Option<&functions::Function> = match context.functions.iter().find(|function| function.name == *name) {
+                Some(function) => Some(function),
+                None => parse_address(name).ok().and_then(|address| functions::function_containing(&context.functions, address))
+            };
+
+            match function {
+                Some(function) => (function.start, function.end),
+                None => return Err(ChiselError::UnknownSymbol(name.to_string()))
+            }
+        }
+    };
+
+    Ok(regions.iter()
+        .filter_map(|region| region.clip(start, end).map(|clipped| (region, clipped)))
+        .collect())
+}
+
+
+// Print a single listing line, preceded by the function label and the
+// incoming references of the instruction's address
+fn pp_instruction(
+    instruction: &Instruction,
+    region: &CodeRegion,
+    context: &ListingContext,
+    formatter: &mut disasm::AsmFormatter,
+    output: &mut String) {
+
+    let address: u64 = instruction.ip();
+
+    if let Ok(index) = context.functions.binary_search_by_key(&address, |function| function.start) {
+        println!("\n{}:", context.functions[index].name);
+    }
+
+    let incoming: &[xrefs::Xref] = context.xrefs.refs_to(address);
+
+    if !incoming.is_empty() {
+        let sources: Vec<String> = incoming.iter()
+            .take(MAX_LISTED_XREFS)
+            .map(|xref| format!("{} ({})", describe_address(xref.from, &context.functions), xref.kind))
+            .collect();
+
+        let more: String = if incoming.len() > MAX_LISTED_XREFS {
+            format!(", +{} more", incoming.len() - MAX_LISTED_XREFS)
+        } else {
+            String::new()
+        };
+
+        println!("\t\t\t; xrefs: {}{}", sources.join(", "), more);
+    }
+
+    // Address first, followed by any of the optional columns
+    let mut line: String = format!("{:016X}", address);
+    let start: usize = (address - region.vaddr) as usize;
+    let columns: &ListingColumns = &context.columns;
+
+    if columns.offset {
+        line.push_str(&format!("\t{:08X}", region.offset + start as u64));
+    }
+    if columns.section {
+        line.push_str(&format!("\t{:<12}", region.name));
+    }
+    if columns.length {
+        line.push_str(&format!("\t{:>2}", instruction.len()));
+    }
+    if columns.bytes {
+        let bytes: Vec<String> = region.data[start..start + instruction.len()]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        line.push_str(&format!("\t{:<30}", bytes.join(" ")));
+    }
+
+    // Format the instruction for printing
+    output.clear();
+    formatter.format(instruction, output);
+
+    // Note the first string the instruction refers to
+    let string: Option<&String> = context.xrefs.refs_from(address)
+        .iter()
+        .filter(|xref| is_data_xref(xref.kind))
+        .find_map(|xref| context.strings.get(&xref.to));
+
+    if let Some(string) = string {
+        output.push_str(&format!("\t; \"{}\"", escape_string(string)));
+    }
+
+    // Print the instruction to an output assembly file
+    println!("{}\t{}", line, output);
+}
+
+
+// Whether a reference reads, writes or takes the address of data
+fn is_data_xref(kind: xrefs::XrefKind) -> bool {
+    !matches!(kind, xrefs::XrefKind::Call | xrefs::XrefKind::Jump | xrefs::XrefKind::ConditionalJump)
+}
+
+
+// Escape a string for a listing comment, eliding the end of long strings
+fn escape_string(string: &str) -> String {
+    let mut escaped: String = String::new();
+
+    for character in string.chars().take(MAX_STRING_COMMENT) {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c)
         }
+    }
+
+    if string.chars().count() > MAX_STRING_COMMENT {
+        format!("{}...", escaped)
     } else {
-        println!("[Error] File '{}' does not exist", file_path);
-        exit(-1);
+        escaped
     }
+}
+
+
+// Print the instructions reached by recursive descent within a region,
+// with the bytes that were never reached listed as data. The region may
+// be a selected part of source.
+fn pp_code_map(
+    source: &CodeRegion,
+    region: &CodeRegion,
+    code_map: &disasm::CodeMap,
+    context: &ListingContext,
+    formatter: &mut disasm::AsmFormatter) {
+
+    let mut output: String = String::new();
+    let mut data_ranges = code_map.data_ranges(region).into_iter().peekable();
+    let region_end: u64 = region.end();
+    let mut code_bytes: u64 = 0;
+    let mut data_bytes: u64 = 0;
+
+    for instruction in code_map.instructions() {
+        if instruction.ip() < region.vaddr || instruction.ip() >= region_end {
+            continue;
+        }
+
+        while let Some((start, end)) = data_ranges.next_if(|(start, _)| *start < instruction.ip()) {
+            println!("{:016X}\t(data, {} bytes)", start, end - start);
+            data_bytes += end - start;
+        }
+
+        pp_instruction(instruction, source, context, formatter, &mut output);
+        code_bytes += instruction.len() as u64;
+    }
+
+    for (start, end) in data_ranges {
+        println!("{:016X}\t(data, {} bytes)", start, end - start);
+        data_bytes += end - start;
+    }
+
+    println!("\n\t- Code: {} bytes, Data: {} bytes", code_bytes, data_bytes);
+}
+
+
+// Print every reference to an address, and the references made from it
+fn pp_xrefs(target: &str, address: u64, context: &ListingContext) {
+    let incoming: &[xrefs::Xref] = context.xrefs.refs_to(address);
+    let outgoing: &[xrefs::Xref] = context.xrefs.refs_from(address);
+
+    println!("{} @ {:#x}", target, address);
+    println!("\t- References to ({}):", incoming.len());
+
+    for xref in incoming {
+        println!("\t\t{:016x}  {:<16}  {}", xref.from, xref.kind, describe_address(xref.from, &context.functions));
+    }
+
+    println!("\t- References from ({}):", outgoing.len());
+
+    for xref in outgoing {
+        println!("\t\t{:016x}  {:<16}  {}", xref.to, xref.kind, describe_address(xref.to, &context.functions));
+    }
+    println!();
+}
+
+
+// Name an address relative to the function that holds it, e.g. main+0x1c
+fn describe_address(address: u64, functions: &[functions::Function]) -> String {
+    match functions::function_containing(functions, address) {
+        Some(function) if function.start == address => function.name.clone(),
+        Some(function) => format!("{}+{:#x}", function.name, address - function.start),
+        None => format!("{:#x}", address)
+    }
+}
+
+
+// Resolve an xref target given on the command line. Accepts addresses,
+// symbol names and discovered function names such as sub_1149.
+fn resolve_target(target: &str, binary: &Elf, functions: &[functions::Function], load_bias: u64) -> Result<u64, ChiselError> {
+    if let Ok(address) = parse_address(target) {
+        return Ok(address);
+    }
+
+    for symbol in binary.symbols()?.iter().chain(binary.dynamic_symbols()?.iter()) {
+        if symbol.name == target && symbol.shndx != elf::SHN_UNDEF {
+            return Ok(symbol.value.wrapping_add(load_bias));
+        }
+    }
+
+    match functions.iter().find(|function| function.name == target) {
+        Some(function) => Ok(function.start),
+        None => Err(ChiselError::UnknownSymbol(target.to_string()))
+    }
+}
+
+
+fn pp_functions(functions: &[functions::Function]) {
+    println!("{} functions", functions.len());
+    println!("\t{:<16}  {:<16}  {:>6}  {:<8}  Name", "Start", "End", "Size", "Source");
+
+    for function in functions {
+        println!(
+            "\t{:016x}  {:016x}  {:>6}  {:<8}  {}",
+            function.start,
+            function.end,
+            function.size(),
+            function.source,
+            function.name
+        );
+    }
+    println!();
+}
+
+
+// Print the blocks of a control flow graph along with their outgoing edges
+fn pp_cfg(graph: &cfg::ControlFlowGraph, formatter: &mut disasm::AsmFormatter) {
+    let mut output: String = String::new();
+
+    println!("{} @ {:#x} ({} blocks, {} edges)", graph.name, graph.entry, graph.blocks.len(), graph.edges.len());
+
+    for block in graph.blocks.values() {
+        println!("\tblock_{:x} [{:#x} - {:#x})", block.start, block.start, block.end);
+
+        for instruction in block.instructions.iter() {
+            output.clear();
+            formatter.format(instruction, &mut output);
+            println!("\t\t{:016X}\t{}", instruction.ip(), output);
+        }
+
+        for edge in graph.successors(block.start) {
+            println!("\t\t-> block_{:x} ({})", edge.to, edge.kind);
+        }
+    }
+    println!();
+}
+
+
+// Print the IR statements of each block, under the instruction they were
+// lifted from
+fn pp_ir_function(function: &ir::IrFunction) {
+    println!("{} @ {:#x} ({} blocks)", function.name, function.entry, function.blocks.len());
+
+    for block in function.blocks.values() {
+        println!("\tblock_{:x}:", block.start);
+
+        for instruction in block.instructions.iter() {
+            for statement in instruction.statements.iter() {
+                println!("\t\t{:016X}\t{}", instruction.address, statement);
+            }
+        }
+
+        for edge in function.successors(block.start) {
+            println!("\t\t-> block_{:x} ({})", edge.to, edge.kind);
+        }
+    }
+    println!();
+}
+
+
+// Write a DOT file per function CFG, the call graph as DOT, and both the
+// CFGs and the call graph as JSON into the export directory
+fn export_graphs(directory: &str, graphs: &[cfg::ControlFlowGraph], formatter: &mut disasm::AsmFormatter) -> Result<(), ChiselError> {
+    let directory: &path::Path = path::Path::new(directory);
+    fs::create_dir_all(directory)?;
+
+    for graph in graphs {
+        let file_path: path::PathBuf = directory.join(format!("{}.dot", export::graph_file_stem(graph)));
+        fs::write(&file_path, export::cfg_to_dot(graph, formatter))?;
+    }
+
+    let call_graph: cfg::CallGraph = cfg::build_call_graph(graphs);
+
+    fs::write(directory.join("callgraph.dot"), export::call_graph_to_dot(&call_graph))?;
+    fs::write(directory.join("cfg.json"), export::cfgs_to_json(graphs, formatter))?;
+    fs::write(directory.join("callgraph.json"), export::call_graph_to_json(&call_graph))?;
+
+    println!("Wrote {} control flow graphs and the call graph to '{}'", graphs.len(), directory.display());
 
-    return;
-}
\ No newline at end of file
+    Ok(())
+}
@@ -5,27 +5,24 @@
 
 use std::path;
 use std::fs;
-use std::collections::HashMap;
 use std::io::Write;
-use std::io::Error;
 
+use crate::binary::Elf;
+use crate::error::ChiselError;
 use crate::util;
 use crate::elf;
 
 
 pub fn patch_binary(
-    binary_contents: Vec<u8>,
+    binary: &Elf,
     binary_name: String,
-    patch_file_path: &String,
-    file_header: &elf::FileHeader,
-    section_header_map: HashMap<String, elf::SectionHeader>,
-    note_segment: elf::ProgramHeader) {
-    
+    patch_file_path: &str) -> Result<(), ChiselError> {
+
+    let file_header: &elf::FileHeader = binary.file_header();
 
-    let patch_result: Result<Vec<u8>,Error> = read_patch_file(patch_file_path);
-    let patch_data: &Vec<u8> = &patch_result.as_ref().unwrap();
+    let patch_data: &Vec<u8> = &read_patch_file(patch_file_path)?;
     
-    let mut program_data: Vec<u8> = binary_contents;
+    let mut program_data: Vec<u8> = binary.bytes().to_vec();
 
 
     // Apply patch to end of binary
@@ -42,8 +39,10 @@ pub fn patch_binary(
 
     // Locate a note segment
     print!("Pulling .note.ABI-tag segment data...");
-    let note_section: &elf::SectionHeader = section_header_map.get(".note.ABI-tag")
-        .expect("[Error] Failed to pull ABI-tag section from binary!");
+    let note_section: &elf::SectionHeader = binary.section_by_name(".note.ABI-tag")
+        .ok_or(ChiselError::MissingSection(".note.ABI-tag".to_string()))?;
+    let note_segment: &elf::ProgramHeader = binary.segment_by_type(elf::PT_NOTE)
+        .ok_or(ChiselError::MissingSegment("PT_NOTE".to_string()))?;
     println!("Done!\n");
     
     println!("Note section address: {:#04x}", note_section.addr);
@@ -56,14 +55,14 @@ pub fn patch_binary(
 
 
     // Rewrite the section header
-    let mut injected_section: elf::SectionHeader = elf::SectionHeader::from(note_section.clone());
+    let mut injected_section: elf::SectionHeader = note_section.clone();
 
     injected_section.section_type = 1;
     injected_section.addr = injection_addr as u64;
     injected_section.offset = injection_offset as u64;
     injected_section.size = injection_size as u64;
     injected_section.addralign = 16;
-    injected_section.flags = 6;
+    injected_section.flags = elf::SectionFlags::ALLOC | elf::SectionFlags::EXECINSTR;
     
     util::overwrite_section_header(
         &mut program_data,
@@ -71,12 +70,13 @@ pub fn patch_binary(
         file_header.shentsize as usize,
         injected_section.id as usize,
         &injected_section,
-        file_header.is_x86_64
+        file_header.is_x86_64,
+        file_header.endian
     );
 
 
     // Rewrite the program segment
-    let mut injected_segment: elf::ProgramHeader = elf::ProgramHeader::from(note_segment.clone());
+    let mut injected_segment: elf::ProgramHeader = note_segment.clone();
 
     injected_segment.program_type = 1;
     injected_segment.offset = injection_offset as u64;
@@ -84,7 +84,7 @@ pub fn patch_binary(
     injected_segment.paddr = injection_offset as u64;
     injected_segment.filesz = injection_size as u64;
     injected_segment.memsz = injection_size as u64;
-    injected_segment.flags = 5;
+    injected_segment.flags = elf::SegmentFlags::R | elf::SegmentFlags::X;
     injected_segment.align = 0x1000;
 
     util::overwrite_segment_header(
@@ -93,12 +93,13 @@ pub fn patch_binary(
         file_header.phentsize as usize,
         injected_segment.id as usize,
         &injected_segment,
-        file_header.is_x86_64
+        file_header.is_x86_64,
+        file_header.endian
     );
 
 
     // Rewrite the program entrypoint
-    util::overwrite_entrypoint(&mut program_data, injection_offset);
+    util::overwrite_entrypoint(&mut program_data, injection_offset, file_header.is_x86_64, file_header.endian);
 
 
     // Spit everything back out
@@ -106,25 +107,22 @@ pub fn patch_binary(
 
     println!("Writing '{}' to disk...", out_file_name);
 
-    let mut file = std::fs::File::create(out_file_name)
-        .expect("[Error] Could not write patched binary to disk");
+    let mut file = std::fs::File::create(out_file_name)?;
+
+    file.write_all(&program_data)?;
 
-    file.write_all(&program_data)
-        .expect("[Error] Could not write to patched binary data file");
+    Ok(())
 }
 
 
-fn read_patch_file(patch_path: &String) -> Result<Vec<u8>, std::io::Error> {
+fn read_patch_file(patch_path: &str) -> Result<Vec<u8>, ChiselError> {
 
     if path::Path::new(patch_path).exists() && patch_path.ends_with(".bin") {
         println!("Patch file exists, reading '{}'...", patch_path);
         
-        let contents: Result<Vec<u8>, std::io::Error> = fs::read(patch_path);
-        
-        return contents;
+        Ok(fs::read(patch_path)?)
 
     } else {
-        println!("[Error] Patch file '{}' is invalid or cannot be read, exiting...", patch_path);
-        std::process::exit(0);    
+        Err(ChiselError::InvalidPatchFile(patch_path.to_string()))
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,592 @@
+// pseudoc.rs
+// Created: 10/17/2026
+// Description: Statement tree for decompiled functions and the printer
+//              that renders it as pseudo-C. Expressions are the analysis
+//              IR's, printed with C operators, casts and precedence.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::functions::Function;
+use crate::ir::{self, BinaryOp, Expr, UnaryOp, Var};
+use crate::symbols::SymbolMap;
+
+
+// Direct or indirect call with the arguments passed in registers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSite {
+    pub target: Expr,
+    pub arguments: Vec<Expr>
+}
+
+
+// Right hand side of an assignment or return
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Expr(Expr),
+    Call(CallSite)
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopKind {
+    While,
+    DoWhile
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CStmt {
+    Assign { dest: Var, value: Value },
+    // Write through a pointer that is not a known stack slot
+    Store { address: Expr, value: Expr },
+    Call(CallSite),
+    Return(Option<Value>),
+    // Indirect jump, e.g. through a jump table
+    Jump(Expr),
+    Comment(String),
+    If { condition: Expr, then: Vec<CStmt>, otherwise: Vec<CStmt> },
+    Loop { kind: LoopKind, condition: Expr, body: Vec<CStmt> },
+    Break,
+    Continue,
+    Goto(u64),
+    Label(u64)
+}
+
+
+impl CStmt {
+    // Whether control never continues to the next statement
+    pub fn is_jump(&self) -> bool {
+        matches!(self, CStmt::Return(_) | CStmt::Jump(_) | CStmt::Break | CStmt::Continue | CStmt::Goto(_))
+    }
+
+
+    // Call f on every expression in the statement and the statements
+    // nested inside it
+    pub fn visit_exprs(&self, f: &mut dyn FnMut(&Expr)) {
+        let call = |site: &CallSite, f: &mut dyn FnMut(&Expr)| {
+            f(&site.target);
+            for argument in site.arguments.iter() {
+                f(argument);
+            }
+        };
+
+        match self {
+            CStmt::Assign { value: Value::Expr(value), .. } | CStmt::Return(Some(Value::Expr(value))) => f(value),
+            CStmt::Assign { value: Value::Call(site), .. } | CStmt::Call(site) | CStmt::Return(Some(Value::Call(site))) => call(site, f),
+            CStmt::Store { address, value } => {
+                f(address);
+                f(value);
+            },
+            CStmt::Jump(target) => f(target),
+            CStmt::If { condition, then, otherwise } => {
+                f(condition);
+                then.iter().chain(otherwise.iter()).for_each(|statement| statement.visit_exprs(f));
+            },
+            CStmt::Loop { condition, body, .. } => {
+                f(condition);
+                body.iter().for_each(|statement| statement.visit_exprs(f));
+            },
+            _ => {}
+        }
+    }
+
+
+    // Rebuild the statement with f applied to every expression in it
+    pub fn map_exprs(self, f: &mut dyn FnMut(Expr) -> Expr) -> CStmt {
+        let call = |site: CallSite, f: &mut dyn FnMut(Expr) -> Expr| CallSite {
+            target: f(site.target),
+            arguments: site.arguments.into_iter().map(&mut *f).collect()
+        };
+        let value = |value: Value, f: &mut dyn FnMut(Expr) -> Expr| match value {
+            Value::Expr(expr) => Value::Expr(f(expr)),
+            Value::Call(site) => Value::Call(call(site, f))
+        };
+        let body = |body: Vec<CStmt>, f: &mut dyn FnMut(Expr) -> Expr| -> Vec<CStmt> {
+            body.into_iter().map(|statement| statement.map_exprs(f)).collect()
+        };
+
+        match self {
+            CStmt::Assign { dest, value: assigned } => CStmt::Assign { dest, value: value(assigned, f) },
+            CStmt::Store { address, value } => CStmt::Store { address: f(address), value: f(value) },
+            CStmt::Call(site) => CStmt::Call(call(site, f)),
+            CStmt::Return(returned) => CStmt::Return(returned.map(|returned| value(returned, f))),
+            CStmt::Jump(target) => CStmt::Jump(f(target)),
+            CStmt::If { condition, then, otherwise } => CStmt::If {
+                condition: f(condition),
+                then: body(then, f),
+                otherwise: body(otherwise, f)
+            },
+            CStmt::Loop { kind, condition, body: statements } => CStmt::Loop {
+                kind,
+                condition: f(condition),
+                body: body(statements, f)
+            },
+            other => other
+        }
+    }
+}
+
+
+// Whether a list of statements ends by transferring control elsewhere
+pub fn ends_in_jump(statements: &[CStmt]) -> bool {
+    statements.last().is_some_and(CStmt::is_jump)
+}
+
+
+// C type of a value of the given width
+pub fn type_name(width: u32) -> &'static str {
+    match width {
+        1 => "bool",
+        8 => "char",
+        16 => "short",
+        32 => "int",
+        64 => "long",
+        128 => "__int128",
+        _ => "int"
+    }
+}
+
+
+// How a variable is printed and declared
+#[derive(Debug, Clone)]
+pub struct Name {
+    pub text: String,
+    pub width: u32
+}
+
+
+// Operator precedence, higher binds tighter
+const PRIMARY: u8 = 16;
+const UNARY: u8 = 14;
+const TERNARY: u8 = 3;
+
+
+pub struct Printer<'a> {
+    pub names: &'a HashMap<Var, Name>,
+    pub symbols: &'a SymbolMap,
+    pub functions: &'a [Function],
+    pub strings: &'a BTreeMap<u64, String>
+}
+
+
+impl Printer<'_> {
+    // Print statements indented by depth levels
+    pub fn statements(&self, statements: &[CStmt], depth: usize, out: &mut String) {
+        for statement in statements {
+            self.statement(statement, depth, out);
+        }
+    }
+
+
+    fn statement(&self, statement: &CStmt, depth: usize, out: &mut String) {
+        let indent: String = "    ".repeat(depth);
+
+        match statement {
+            CStmt::Assign { dest, value } => {
+                let name: String = self.var(dest);
+                out.push_str(&format!("{}{};\n", indent, self.assignment(&name, value)));
+            },
+            CStmt::Store { address, value } => {
+                let target: String = self.load(address, value.width()).0;
+                out.push_str(&format!("{}{};\n", indent, self.assignment(&target, &Value::Expr(value.clone()))));
+            },
+            CStmt::Call(site) => {
+                out.push_str(&format!("{}{};\n", indent, self.call(site)));
+            },
+            CStmt::Return(None) => {
+                out.push_str(&format!("{}return;\n", indent));
+            },
+            CStmt::Return(Some(value)) => {
+                out.push_str(&format!("{}return {};\n", indent, self.value(value)));
+            },
+            CStmt::Jump(target) => {
+                out.push_str(&format!("{}goto *{};\n", indent, self.expr_within(target, UNARY)));
+            },
+            CStmt::Comment(text) => {
+                out.push_str(&format!("{}// {}\n", indent, text));
+            },
+            CStmt::If { .. } => {
+                out.push_str(&indent);
+                self.if_chain(statement, depth, out);
+            },
+            CStmt::Loop { kind: LoopKind::While, condition, body } => {
+                out.push_str(&format!("{}while ({}) {{\n", indent, self.expr(condition)));
+                self.statements(body, depth + 1, out);
+                out.push_str(&format!("{}}}\n", indent));
+            },
+            CStmt::Loop { kind: LoopKind::DoWhile, condition, body } => {
+                out.push_str(&format!("{}do {{\n", indent));
+                self.statements(body, depth + 1, out);
+                out.push_str(&format!("{}}} while ({});\n", indent, self.expr(condition)));
+            },
+            CStmt::Break => {
+                out.push_str(&format!("{}break;\n", indent));
+            },
+            CStmt::Continue => {
+                out.push_str(&format!("{}continue;\n", indent));
+            },
+            CStmt::Goto(target) => {
+                out.push_str(&format!("{}goto label_{:x};\n", indent, target));
+            },
+            CStmt::Label(target) => {
+                // Labels sit one level out from the code they name
+                out.push_str(&format!("{}label_{:x}:\n", "    ".repeat(depth.saturating_sub(1)), target));
+            }
+        }
+    }
+
+
+    // Print an if statement, folding a lone if in the else branch into an
+    // else if
+    fn if_chain(&self, statement: &CStmt, depth: usize, out: &mut String) {
+        let indent: String = "    ".repeat(depth);
+
+        if let CStmt::If { condition, then, otherwise } = statement {
+            out.push_str(&format!("if ({}) {{\n", self.expr(condition)));
+            self.statements(then, depth + 1, out);
+
+            match otherwise.as_slice() {
+                [] => {
+                    out.push_str(&format!("{}}}\n", indent));
+                },
+                [nested @ CStmt::If { .. }] => {
+                    out.push_str(&format!("{}}} else ", indent));
+                    self.if_chain(nested, depth, out);
+                },
+                _ => {
+                    out.push_str(&format!("{}}} else {{\n", indent));
+                    self.statements(otherwise, depth + 1, out);
+                    out.push_str(&format!("{}}}\n", indent));
+                }
+            }
+        }
+    }
+
+
+    // Assignment, using a compound operator when the target is updated in
+    // place, e.g. x = x + 1 becomes x += 1
+    fn assignment(&self, target: &str, value: &Value) -> String {
+        if let Value::Expr(Expr::Binary { op, left, right }) = value {
+            let operator: Option<&str> = match op {
+                BinaryOp::Add => Some("+="),
+                BinaryOp::Sub => Some("-="),
+                BinaryOp::Mul => Some("*="),
+                BinaryOp::SDiv => Some("/="),
+                BinaryOp::SRem => Some("%="),
+                BinaryOp::And if left.width() > 1 => Some("&="),
+                BinaryOp::Or if left.width() > 1 => Some("|="),
+                BinaryOp::Xor => Some("^="),
+                BinaryOp::Shl => Some("<<="),
+                BinaryOp::AShr => Some(">>="),
+                _ => None
+            };
+
+            if let Some(operator) = operator {
+                if self.expr(left) == target {
+                    return format!("{} {} {}", target, operator, self.expr_within(right, TERNARY));
+                }
+            }
+        }
+
+        format!("{} = {}", target, self.value(value))
+    }
+
+
+    fn value(&self, value: &Value) -> String {
+        match value {
+            Value::Expr(expr) => self.expr(expr),
+            Value::Call(site) => self.call(site)
+        }
+    }
+
+
+    fn call(&self, site: &CallSite) -> String {
+        let target: String = match &site.target {
+            Expr::Const { value, .. } => self.function_name(*value).unwrap_or_else(|| format!("(*(code *){:#x})", value)),
+            Expr::Load { address, .. } => match address.as_const().and_then(|slot| self.symbols.name_at(slot)) {
+                // Calls through the GOT are named after the import
+                Some(name) => name.trim_end_matches("@got").to_string(),
+                None => format!("(*{})", self.expr_within(&site.target, UNARY))
+            },
+            other => format!("(*{})", self.expr_within(other, UNARY))
+        };
+
+        let arguments: Vec<String> = site.arguments.iter().map(|argument| self.expr_within(argument, TERNARY)).collect();
+        format!("{}({})", target, arguments.join(", "))
+    }
+
+
+    pub fn var(&self, var: &Var) -> String {
+        match self.names.get(var) {
+            Some(name) => name.text.clone(),
+            None => var.to_string()
+        }
+    }
+
+
+    pub fn expr(&self, expr: &Expr) -> String {
+        self.render(expr).0
+    }
+
+
+    // Render an expression, parenthesized when it binds looser than the
+    // context requires
+    fn expr_within(&self, expr: &Expr, precedence: u8) -> String {
+        let (text, own): (String, u8) = self.render(expr);
+
+        if own < precedence {
+            format!("({})", text)
+        } else {
+            text
+        }
+    }
+
+
+    fn render(&self, expr: &Expr) -> (String, u8) {
+        match expr {
+            Expr::Const { value, width } => (self.constant(*value, *width), PRIMARY),
+            Expr::Var(var) => (self.var(var), PRIMARY),
+            Expr::Load { address, width } => self.load(address, *width),
+            Expr::Unary { op: UnaryOp::Neg, operand } => (format!("-{}", self.expr_within(operand, UNARY)), UNARY),
+            Expr::Unary { op: UnaryOp::Not, operand } if operand.width() == 1 => (format!("!{}", self.expr_within(operand, UNARY)), UNARY),
+            Expr::Unary { op: UnaryOp::Not, operand } => (format!("~{}", self.expr_within(operand, UNARY)), UNARY),
+            Expr::Binary { op, left, right } => self.binary(*op, left, right),
+            // Widening happens implicitly in C
+            Expr::Extend { operand, .. } => self.render(operand),
+            Expr::Truncate { operand, width } => {
+                if let Expr::Var(var) = &**operand {
+                    if self.names.get(var).is_some_and(|name| name.width == *width) {
+                        return (self.var(var), PRIMARY);
+                    }
+                }
+
+                (format!("({}){}", type_name(*width), self.expr_within(operand, UNARY)), UNARY)
+            },
+            Expr::Select { condition, then, otherwise } => {
+                let text: String = format!(
+                    "{} ? {} : {}",
+                    self.expr_within(condition, TERNARY + 1),
+                    self.expr_within(then, TERNARY + 1),
+                    self.expr_within(otherwise, TERNARY)
+                );
+                (text, TERNARY)
+            },
+            Expr::Undefined(_) => ("undefined".to_string(), PRIMARY)
+        }
+    }
+
+
+    fn binary(&self, op: BinaryOp, left: &Expr, right: &Expr) -> (String, u8) {
+        let logical: bool = left.width() == 1;
+
+        let (symbol, precedence, unsigned): (&str, u8, bool) = match op {
+            BinaryOp::Mul => ("*", 13, false),
+            BinaryOp::SDiv => ("/", 13, false),
+            BinaryOp::SRem => ("%", 13, false),
+            BinaryOp::UDiv => ("/", 13, true),
+            BinaryOp::URem => ("%", 13, true),
+            BinaryOp::Add => ("+", 12, false),
+            BinaryOp::Sub => ("-", 12, false),
+            BinaryOp::Shl => ("<<", 11, false),
+            BinaryOp::AShr => (">>", 11, false),
+            BinaryOp::LShr => (">>", 11, true),
+            BinaryOp::SLt => ("<", 10, false),
+            BinaryOp::SLe => ("<=", 10, false),
+            BinaryOp::SGt => (">", 10, false),
+            BinaryOp::SGe => (">=", 10, false),
+            BinaryOp::ULt => ("<", 10, true),
+            BinaryOp::ULe => ("<=", 10, true),
+            BinaryOp::UGt => (">", 10, true),
+            BinaryOp::UGe => (">=", 10, true),
+            BinaryOp::Eq => ("==", 9, false),
+            BinaryOp::Ne => ("!=", 9, false),
+            BinaryOp::And if logical => ("&&", 5, false),
+            BinaryOp::And => ("&", 8, false),
+            BinaryOp::Xor if logical => ("!=", 9, false),
+            BinaryOp::Xor => ("^", 7, false),
+            BinaryOp::Or if logical => ("||", 4, false),
+            BinaryOp::Or => ("|", 6, false),
+            // No C operator, printed as helper calls
+            BinaryOp::UMulHigh => return (format!("__umulh({}, {})", self.expr(left), self.expr(right)), PRIMARY),
+            BinaryOp::SMulHigh => return (format!("__smulh({}, {})", self.expr(left), self.expr(right)), PRIMARY),
+            BinaryOp::Concat => return (format!("CONCAT({}, {})", self.expr(left), self.expr(right)), PRIMARY),
+            BinaryOp::AddOverflow => return (format!("__addof({}, {})", self.expr(left), self.expr(right)), PRIMARY),
+            BinaryOp::SubOverflow => return (format!("__subof({}, {})", self.expr(left), self.expr(right)), PRIMARY)
+        };
+
+        let operand = |expr: &Expr, precedence: u8| -> String {
+            if unsigned && !matches!(expr, Expr::Const { .. }) {
+                format!("(unsigned {}){}", type_name(expr.width()), self.expr_within(expr, UNARY))
+            } else {
+                self.expr_within(expr, precedence)
+            }
+        };
+
+        // Operators are left associative
+        let text: String = format!("{} {} {}", operand(left, precedence), symbol, operand(right, precedence + 1));
+        (text, precedence)
+    }
+
+
+    // Memory read, or the target of a store. Named globals are printed by
+    // name, anything else is a dereferenced pointer.
+    fn load(&self, address: &Expr, width: u32) -> (String, u8) {
+        if let Some(name) = address.as_const().and_then(|address| self.symbols.name_at(address)) {
+            if self.function_name(address.as_const().unwrap_or_default()).is_none() {
+                return (name.to_string(), PRIMARY);
+            }
+        }
+
+        (format!("*({} *){}", type_name(width), self.expr_within(address, UNARY)), UNARY)
+    }
+
+
+    // Small values are printed in decimal, addresses are named after the
+    // string, function or symbol they point to
+    fn constant(&self, value: u64, width: u32) -> String {
+        if width == 1 {
+            return value.to_string();
+        }
+
+        let signed: i64 = ir::sign_extend_value(value, width);
+
+        if signed.unsigned_abs() < 0x1000 {
+            return signed.to_string();
+        }
+
+        if width == 64 {
+            if let Some(string) = self.strings.get(&value) {
+                return c_string(string);
+            }
+
+            if let Some(name) = self.function_name(value) {
+                return name;
+            }
+
+            if let Some(name) = self.symbols.name_at(value) {
+                return format!("&{}", name);
+            }
+        }
+
+        format!("{:#x}", value)
+    }
+
+
+    // Name of the function starting at an address, with PLT stubs named
+    // after the import they call
+    fn function_name(&self, address: u64) -> Option<String> {
+        self.functions.iter()
+            .find(|function| function.start == address)
+            .map(|function| function.name.trim_end_matches("@plt").to_string())
+    }
+}
+
+
+// Quote a string as a C literal
+pub fn c_string(string: &str) -> String {
+    let mut quoted: String = String::from("\"");
+
+    for c in string.chars() {
+        match c {
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => {
+                quoted.push_str(&format!("\\x{:02x}", c as u32));
+            },
+            c => quoted.push(c)
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use iced_x86::Register;
+
+    use crate::ir::{BinaryOp, Expr, UnaryOp, Var};
+    use crate::symbols::SymbolMap;
+
+    use super::{c_string, CStmt, Name, Printer, Value};
+
+
+    fn printer_test(test: impl FnOnce(&Printer)) {
+        let names: HashMap<Var, Name> = HashMap::from([
+            (Var::Register(Register::RAX), Name { text: "a".to_string(), width: 32 }),
+            (Var::Register(Register::RBX), Name { text: "b".to_string(), width: 32 })
+        ]);
+        let symbols: SymbolMap = SymbolMap::new();
+        let strings: BTreeMap<u64, String> = BTreeMap::new();
+
+        test(&Printer { names: &names, symbols: &symbols, functions: &[], strings: &strings });
+    }
+
+
+    fn a() -> Expr {
+        Expr::truncate(Expr::register(Register::RAX), 32)
+    }
+
+
+    fn b() -> Expr {
+        Expr::truncate(Expr::register(Register::RBX), 32)
+    }
+
+
+    #[test]
+    fn parenthesizes_by_precedence() {
+        printer_test(|printer| {
+            let sum: Expr = Expr::binary(BinaryOp::Add, a(), b());
+            let product: Expr = Expr::binary(BinaryOp::Mul, sum.clone(), Expr::constant(3, 32));
+            assert_eq!(printer.expr(&product), "(a + b) * 3");
+
+            let nested: Expr = Expr::binary(BinaryOp::Add, Expr::binary(BinaryOp::Mul, a(), b()), Expr::constant(1, 32));
+            assert_eq!(printer.expr(&nested), "a * b + 1");
+
+            let difference: Expr = Expr::binary(BinaryOp::Sub, a(), sum);
+            assert_eq!(printer.expr(&difference), "a - (a + b)");
+        });
+    }
+
+
+    #[test]
+    fn prints_conditions() {
+        printer_test(|printer| {
+            let less: Expr = Expr::binary(BinaryOp::SLt, a(), b());
+            let below: Expr = Expr::binary(BinaryOp::ULt, a(), b());
+            let either: Expr = Expr::binary(BinaryOp::Or, less.clone(), Expr::unary(UnaryOp::Not, below.clone()));
+
+            assert_eq!(printer.expr(&less), "a < b");
+            assert_eq!(printer.expr(&below), "(unsigned int)a < (unsigned int)b");
+            assert_eq!(printer.expr(&either), "a < b || !((unsigned int)a < (unsigned int)b)");
+        });
+    }
+
+
+    #[test]
+    fn prints_compound_assignments() {
+        printer_test(|printer| {
+            let statement: CStmt = CStmt::Assign {
+                dest: Var::Register(Register::RAX),
+                value: Value::Expr(Expr::binary(BinaryOp::Add, a(), Expr::constant(1, 32)))
+            };
+
+            let mut out: String = String::new();
+            printer.statements(&[statement], 1, &mut out);
+            assert_eq!(out, "    a += 1;\n");
+        });
+    }
+
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(c_string("say \"hi\"\n"), "\"say \\\"hi\\\"\\n\"");
+        assert_eq!(c_string("tab\there\\"), "\"tab\\there\\\\\"");
+    }
+}
@@ -0,0 +1,831 @@
+// reassemble.rs
+// Created: 10/17/2026
+// Description: Reassemblable NASM output. Emits the program's own code and
+//              data as a NASM source file with labels in place of absolute
+//              addresses, which can be assembled and linked back into a
+//              working executable.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::rc::Rc;
+
+use iced_x86::{Formatter, Instruction, Mnemonic, NasmFormatter, OpKind, Register, SymbolResolver, SymbolResult};
+
+use crate::binary::{CodeRegion, Elf};
+use crate::disasm;
+use crate::elf;
+use crate::error::ChiselError;
+use crate::functions::Function;
+use crate::symbols;
+use crate::util;
+
+
+// C runtime functions the compiler driver links into the program again,
+// so they are left out of the output
+const RUNTIME_FUNCTIONS: [&str; 10] = [
+    "_start", "_init", "_fini", "deregister_tm_clones", "register_tm_clones", "__do_global_dtors_aux",
+    "frame_dummy", "__libc_csu_init", "__libc_csu_fini", "_dl_relocate_static_pie"
+];
+
+// NASM keywords that can not be used as label names
+const RESERVED_NAMES: [&str; 24] = [
+    "abs", "rel", "byte", "word", "dword", "qword", "tword", "oword", "yword", "zword", "near", "far",
+    "short", "strict", "seg", "wrt", "ptr", "times", "equ", "db", "dw", "dd", "dq", "nosplit"
+];
+
+// Bytes listed on each db line
+const BYTES_PER_LINE: usize = 16;
+
+
+// Contents of a section in the output, in address order
+enum Chunk {
+    Code(Vec<Instruction>),
+    Bytes(u64, u64)
+}
+
+
+struct OutputSection<'a> {
+    name: String,
+    attributes: String,
+    vaddr: u64,
+    data: &'a [u8],
+    exec: bool,
+    nobits: bool,
+    chunks: Vec<Chunk>
+}
+
+
+// Value stored in a data slot that refers to another address
+enum DataValue {
+    Pointer(u64),
+    Extern(String, i64),
+    TableEntry { table: u64, target: u64 }
+}
+
+
+// Address supplied by a shared library. The text is what the address is
+// written as, e.g. printf wrt ..plt for a PLT stub.
+struct Import {
+    name: String,
+    text: String,
+    size: u64
+}
+
+
+// Label names and the external symbols references resolve to
+#[derive(Default)]
+struct Labels {
+    names: BTreeMap<u64, String>,
+    spans: Vec<(u64, u64)>,
+    imports: BTreeMap<u64, Import>,
+    absolute: bool
+}
+
+
+impl Labels {
+    // Import covering an address
+    fn import(&self, address: u64) -> Option<(u64, &Import)> {
+        self.imports.range(..=address)
+            .next_back()
+            .filter(|(base, import)| address - **base < import.size.max(1))
+            .map(|(base, import)| (*base, import))
+    }
+
+
+    // Name to refer to an address by, and the address the name stands for
+    fn reference(&self, address: u64) -> Option<(&str, u64)> {
+        if let Some((base, import)) = self.import(address) {
+            return Some((import.text.as_str(), base));
+        }
+
+        let index: usize = self.spans.partition_point(|(start, _)| *start <= address);
+        let (start, end): (u64, u64) = *self.spans.get(index.checked_sub(1)?)?;
+
+        if address >= end {
+            return None;
+        }
+
+        self.names.range(start..=address)
+            .next_back()
+            .map(|(base, name)| (name.as_str(), *base))
+    }
+
+
+    // Operand text for an address, such as name or name+0x10
+    fn operand(&self, address: u64) -> Option<String> {
+        self.reference(address).map(|(name, base)| {
+            if base == address { name.to_string() } else { format!("{}+{:#x}", name, address - base) }
+        })
+    }
+
+
+    fn is_emitted(&self, address: u64) -> bool {
+        let index: usize = self.spans.partition_point(|(start, _)| *start <= address);
+        index > 0 && address < self.spans[index - 1].1
+    }
+}
+
+
+// Formatter symbol resolver that names operands after the output labels
+struct LabelResolver {
+    labels: Rc<Labels>
+}
+
+
+impl SymbolResolver for LabelResolver {
+    fn symbol(
+        &mut self,
+        instruction: &Instruction,
+        _operand: u32,
+        instruction_operand: Option<u32>,
+        address: u64,
+        _address_size: u32) -> Option<SymbolResult<'_>> {
+
+        // Branches and RIP-relative operands always hold addresses. Other
+        // constants only do in images that are not position independent.
+        let position_dependent: bool = match instruction_operand.map(|operand| instruction.op_kind(operand)) {
+            Some(OpKind::NearBranch16) | Some(OpKind::NearBranch32) | Some(OpKind::NearBranch64) => false,
+            Some(OpKind::Memory) => !instruction.is_ip_rel_memory_operand(),
+            _ => true
+        };
+
+        if position_dependent && !self.labels.absolute {
+            return None;
+        }
+
+        self.labels.reference(address).map(|(name, base)| SymbolResult::with_str(base, name))
+    }
+}
+
+
+// Build a NASM source file holding the code and data of a linked binary.
+// Runtime startup code, the PLT and the dynamic linking tables are left
+// for the linker to recreate. Addresses are shifted by load_bias to match
+// the regions and functions.
+pub fn reassemble(
+    binary: &Elf,
+    regions: &[CodeRegion],
+    functions: &[Function],
+    load_bias: u64) -> Result<String, ChiselError> {
+
+    binary.section_by_name(".text").ok_or_else(|| ChiselError::MissingSection(".text".to_string()))?;
+
+    let is_x86_64: bool = binary.file_header().is_x86_64;
+    let pointer_size: usize = if is_x86_64 { 8 } else { 4 };
+    let mut sections: Vec<OutputSection> = Vec::new();
+
+    // Code of every executable section but the ones the linker generates
+    for region in regions {
+        if region.name == ".init" || region.name == ".fini" || region.name.starts_with(".plt") {
+            continue;
+        }
+
+        let header: &elf::SectionHeader = match binary.section_by_name(&region.name) {
+            Some(header) => header,
+            None => continue
+        };
+
+        sections.push(OutputSection {
+            name: region.name.clone(),
+            attributes: format!("progbits alloc exec nowrite align={}", header.addralign.max(1)),
+            vaddr: region.vaddr,
+            data: region.data,
+            exec: true,
+            nobits: false,
+            chunks: code_chunks(region, functions, binary.bitness())
+        });
+    }
+
+    // Data the program defines itself
+    for header in binary.sections() {
+        let is_data: bool = [".rodata", ".data", ".bss"].iter().any(|prefix| header.name.starts_with(prefix));
+
+        if !is_data
+            || !header.flags.contains(elf::SectionFlags::ALLOC)
+            || header.flags.contains(elf::SectionFlags::EXECINSTR)
+            || header.flags.contains(elf::SectionFlags::TLS)
+            || header.size == 0 {
+            continue;
+        }
+
+        let nobits: bool = header.section_type == elf::SHT_NOBITS;
+        let write: &str = if header.flags.contains(elf::SectionFlags::WRITE) { "write" } else { "nowrite" };
+        let vaddr: u64 = header.addr.wrapping_add(load_bias);
+
+        sections.push(OutputSection {
+            name: header.name.clone(),
+            attributes: format!(
+                "{} alloc noexec {} align={}",
+                if nobits { "nobits" } else { "progbits" }, write, header.addralign.max(1)
+            ),
+            vaddr,
+            data: binary.section_data(header)?,
+            exec: false,
+            nobits,
+            chunks: vec![Chunk::Bytes(vaddr, vaddr + header.size)]
+        });
+    }
+
+    let mut labels: Labels = Labels {
+        absolute: binary.file_header().elf_type == elf::ET_EXEC,
+        ..Labels::default()
+    };
+
+    // Contiguous ranges of output within a section, each of which starts
+    // with a label
+    for section in sections.iter() {
+        let mut previous_end: Option<u64> = None;
+
+        for chunk in section.chunks.iter() {
+            let (start, end): (u64, u64) = chunk_range(chunk);
+
+            match labels.spans.last_mut() {
+                Some(span) if previous_end == Some(start) => span.1 = end,
+                _ => labels.spans.push((start, end))
+            }
+            previous_end = Some(end);
+        }
+    }
+    labels.spans.sort_unstable();
+
+    // Imported functions and objects
+    for (address, name) in symbols::plt_stubs(binary, regions, load_bias)? {
+        let name: String = name.trim_end_matches("@plt").to_string();
+        labels.imports.insert(address, Import { text: format!("{} wrt ..plt", name), name, size: 1 });
+    }
+
+    if is_x86_64 {
+        for (address, name) in symbols::got_slots(binary, load_bias)? {
+            labels.imports.insert(address, Import { text: format!("{} wrt ..gotpcrel", name), name, size: 1 });
+        }
+    }
+
+    let mut data_values: BTreeMap<u64, DataValue> = BTreeMap::new();
+    relocated_values(binary, load_bias, &mut labels, &mut data_values)?;
+
+    // Code instructions, used to place labels and find jump tables
+    let instructions: Vec<&Instruction> = sections.iter()
+        .flat_map(|section| section.chunks.iter())
+        .filter_map(|chunk| match chunk {
+            Chunk::Code(instructions) => Some(instructions),
+            Chunk::Bytes(..) => None
+        })
+        .flatten()
+        .filter(|instruction| !instruction.is_invalid())
+        .collect();
+
+    let instruction_starts: BTreeSet<u64> = instructions.iter().map(|instruction| instruction.ip()).collect();
+
+    // Pointers held in the data of position dependent images
+    if labels.absolute {
+        for section in sections.iter().filter(|section| !section.nobits && !section.exec) {
+            for offset in (0..section.data.len().saturating_sub(pointer_size - 1)).step_by(pointer_size) {
+                let address: u64 = section.vaddr + offset as u64;
+                let value: u64 = util::addr_from_buffer(section.data, offset, is_x86_64, binary.file_header().endian);
+
+                if !data_values.contains_key(&address) && labels.is_emitted(value) {
+                    data_values.insert(address, DataValue::Pointer(value));
+                }
+            }
+        }
+    }
+
+    // Addresses referred to by the code
+    let mut targets: BTreeSet<u64> = BTreeSet::new();
+
+    for instruction in instructions.iter() {
+        if let Some(target) = disasm::branch_target(instruction) {
+            targets.insert(target);
+        }
+
+        if instruction.is_ip_rel_memory_operand() {
+            targets.insert(instruction.ip_rel_memory_address());
+        } else if labels.absolute {
+            for operand in 0..instruction.op_count() {
+                let value: Option<u64> = match instruction.op_kind(operand) {
+                    OpKind::Memory => Some(instruction.memory_displacement64()),
+                    OpKind::Immediate32 => Some(instruction.immediate32() as u64),
+                    OpKind::Immediate32to64 => Some(instruction.immediate32to64() as u64),
+                    OpKind::Immediate64 => Some(instruction.immediate64()),
+                    _ => None
+                };
+
+                if let Some(value) = value.filter(|value| labels.is_emitted(*value)) {
+                    targets.insert(value);
+                }
+            }
+        }
+    }
+
+    // Jump tables of offsets relative to the table, found through the lea
+    // that loads their address
+    if is_x86_64 {
+        let tables: Vec<u64> = instructions.iter()
+            .filter(|instruction| instruction.mnemonic() == Mnemonic::Lea && instruction.is_ip_rel_memory_operand())
+            .map(|instruction| instruction.ip_rel_memory_address())
+            .collect();
+
+        for table in tables {
+            find_jump_table(table, &sections, &targets, &instruction_starts, &mut data_values);
+        }
+    }
+
+    let mut extern_names: BTreeSet<String> = BTreeSet::new();
+
+    for value in data_values.values() {
+        match value {
+            DataValue::Pointer(target) => { targets.insert(*target); },
+            DataValue::TableEntry { table, target } => {
+                targets.insert(*table);
+                targets.insert(*target);
+            },
+            DataValue::Extern(name, _) => { extern_names.insert(name.clone()); }
+        }
+    }
+
+    // Only the imports the output refers to are declared
+    for target in targets.iter() {
+        if let Some((_, import)) = labels.import(*target) {
+            extern_names.insert(import.name.clone());
+        }
+    }
+
+    // Place labels at span starts, functions and every target that can
+    // hold one: any address in data, but only instruction starts in code
+    let mut label_addresses: BTreeSet<u64> = labels.spans.iter().map(|(start, _)| *start).collect();
+
+    for function in functions {
+        if instruction_starts.contains(&function.start) {
+            label_addresses.insert(function.start);
+        }
+    }
+
+    for target in targets {
+        let in_code: bool = sections.iter()
+            .filter(|section| section.exec)
+            .any(|section| target >= section.vaddr && target - section.vaddr < section.data.len() as u64);
+
+        if labels.is_emitted(target) && (!in_code || instruction_starts.contains(&target)) {
+            label_addresses.insert(target);
+        }
+    }
+
+    labels.names = name_labels(binary, functions, &label_addresses, &labels.imports, load_bias)?;
+
+    render(binary, &sections, labels, &extern_names, &data_values)
+}
+
+
+// Split an executable region into the code of each function and the bytes
+// between them, leaving out runtime startup functions
+fn code_chunks(region: &CodeRegion, functions: &[Function], bitness: u32) -> Vec<Chunk> {
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let region_end: u64 = region.end();
+    let mut cursor: u64 = region.vaddr;
+    let mut after_runtime: bool = false;
+
+    for function in functions.iter().filter(|function| function.start >= region.vaddr && function.start < region_end) {
+        if function.start < cursor {
+            continue;
+        }
+
+        // The padding that follows a left out function goes with it
+        if function.start > cursor && !after_runtime {
+            chunks.push(Chunk::Bytes(cursor, function.start));
+        }
+
+        let end: u64 = function.end.min(region_end);
+        after_runtime = RUNTIME_FUNCTIONS.contains(&function.name.as_str());
+
+        if after_runtime {
+            cursor = end;
+            continue;
+        }
+
+        let code: &[u8] = &region.data[(function.start - region.vaddr) as usize..(end - region.vaddr) as usize];
+        let instructions: Vec<Instruction> = disasm::linear_sweep(code, function.start, bitness);
+
+        cursor = instructions.last().map_or(function.start, |instruction| instruction.next_ip());
+
+        if !instructions.is_empty() {
+            chunks.push(Chunk::Code(instructions));
+        }
+
+        if cursor < end {
+            chunks.push(Chunk::Bytes(cursor, end));
+            cursor = end;
+        }
+    }
+
+    if cursor < region_end && !after_runtime {
+        chunks.push(Chunk::Bytes(cursor, region_end));
+    }
+
+    chunks
+}
+
+
+fn chunk_range(chunk: &Chunk) -> (u64, u64) {
+    match chunk {
+        Chunk::Code(instructions) => (
+            instructions.first().map_or(0, |instruction| instruction.ip()),
+            instructions.last().map_or(0, |instruction| instruction.next_ip())
+        ),
+        Chunk::Bytes(start, end) => (*start, *end)
+    }
+}
+
+
+// Values the dynamic linker writes into the data: relative pointers,
+// pointers to symbols and objects copied out of shared libraries
+fn relocated_values(
+    binary: &Elf,
+    load_bias: u64,
+    labels: &mut Labels,
+    data_values: &mut BTreeMap<u64, DataValue>) -> Result<(), ChiselError> {
+
+    let is_x86_64: bool = binary.file_header().is_x86_64;
+
+    let (absolute, copy, relative): (u32, u32, u32) = if is_x86_64 {
+        (elf::R_X86_64_64, elf::R_X86_64_COPY, elf::R_X86_64_RELATIVE)
+    } else {
+        (elf::R_386_32, elf::R_386_COPY, elf::R_386_RELATIVE)
+    };
+
+    for section in binary.sections() {
+        if section.section_type != elf::SHT_RELA && section.section_type != elf::SHT_REL {
+            continue;
+        }
+
+        for relocation in binary.relocation_table(section)? {
+            let slot: u64 = relocation.offset.wrapping_add(load_bias);
+
+            // REL entries keep their addend in the slot itself
+            let addend: i64 = match relocation.addend {
+                Some(addend) => addend,
+                None => binary.vaddr_to_offset(relocation.offset)
+                    .filter(|offset| *offset as usize + 4 <= binary.bytes().len())
+                    .map_or(0, |offset| util::u32_from_buffer(binary.bytes(), offset as usize, binary.file_header().endian) as i32 as i64)
+            };
+
+            if relocation.rel_type == relative {
+                data_values.insert(slot, DataValue::Pointer((addend as u64).wrapping_add(load_bias)));
+                continue;
+            }
+
+            let symbol: &elf::Symbol = match &relocation.symbol {
+                Some(symbol) if !symbol.name.is_empty() => symbol,
+                _ => continue
+            };
+
+            if relocation.rel_type == copy {
+                labels.imports.insert(slot, Import { name: symbol.name.clone(), text: symbol.name.clone(), size: symbol.size });
+            } else if relocation.rel_type == absolute {
+                if symbol.shndx == elf::SHN_UNDEF {
+                    data_values.insert(slot, DataValue::Extern(symbol.name.clone(), addend));
+                } else {
+                    let target: u64 = symbol.value.wrapping_add(addend as u64).wrapping_add(load_bias);
+                    data_values.insert(slot, DataValue::Pointer(target));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+
+// Record the entries of a table of 32-bit offsets from the table start to
+// instructions, as emitted for switch statements in position independent
+// code. Scanning stops at the first entry that is not such an offset or
+// at the next address the code refers to.
+fn find_jump_table(
+    table: u64,
+    sections: &[OutputSection],
+    targets: &BTreeSet<u64>,
+    instruction_starts: &BTreeSet<u64>,
+    data_values: &mut BTreeMap<u64, DataValue>) {
+
+    let section: &OutputSection = match sections.iter()
+        .filter(|section| !section.nobits && !section.exec)
+        .find(|section| table >= section.vaddr && table - section.vaddr < section.data.len() as u64) {
+        Some(section) => section,
+        None => return
+    };
+
+    let mut entries: Vec<(u64, u64)> = Vec::new();
+    let mut slot: u64 = table;
+
+    while slot.saturating_add(4) <= section.vaddr.saturating_add(section.data.len() as u64) {
+        if slot != table && targets.contains(&slot) || data_values.contains_key(&slot) {
+            break;
+        }
+
+        let index: usize = (slot - section.vaddr) as usize;
+        let entry: i32 = i32::from_le_bytes([
+            section.data[index], section.data[index + 1], section.data[index + 2], section.data[index + 3]
+        ]);
+        let target: u64 = table.wrapping_add(entry as i64 as u64);
+
+        if !instruction_starts.contains(&target) {
+            break;
+        }
+
+        entries.push((slot, target));
+        slot += 4;
+    }
+
+    if entries.len() >= 2 {
+        for (slot, target) in entries {
+            data_values.insert(slot, DataValue::TableEntry { table, target });
+        }
+    }
+}
+
+
+// Name each label after the symbol or function at its address, falling
+// back on L_<address> where the name can not be used in NASM source
+fn name_labels(
+    binary: &Elf,
+    functions: &[Function],
+    addresses: &BTreeSet<u64>,
+    imports: &BTreeMap<u64, Import>,
+    load_bias: u64) -> Result<BTreeMap<u64, String>, ChiselError> {
+
+    let mut known: HashMap<u64, String> = HashMap::new();
+
+    for symbol in binary.symbols()?.iter().chain(binary.dynamic_symbols()?.iter()) {
+        let named: bool = symbol.sym_type == elf::STT_FUNC
+            || symbol.sym_type == elf::STT_OBJECT
+            || symbol.sym_type == elf::STT_NOTYPE;
+
+        if named && symbol.shndx != elf::SHN_UNDEF && symbol.shndx != elf::SHN_ABS {
+            known.entry(symbol.value.wrapping_add(load_bias)).or_insert_with(|| symbol.name.clone());
+        }
+    }
+
+    for function in functions {
+        known.entry(function.start).or_insert_with(|| function.name.clone());
+    }
+
+    let mut names: BTreeMap<u64, String> = BTreeMap::new();
+    let mut used: BTreeSet<String> = imports.values().map(|import| import.name.clone()).collect();
+
+    for address in addresses {
+        if imports.contains_key(address) {
+            continue;
+        }
+
+        let name: String = match known.get(address) {
+            Some(name) if is_label_name(name) && !used.contains(name) => name.clone(),
+            _ => format!("L_{:x}", address)
+        };
+
+        used.insert(name.clone());
+        names.insert(*address, name);
+    }
+
+    Ok(names)
+}
+
+
+// Whether NASM accepts a name as a global, non-local label
+fn is_label_name(name: &str) -> bool {
+    let valid_characters: bool = name.chars()
+        .all(|character| character.is_ascii_alphanumeric() || "_$#@~.?".contains(character));
+
+    let starts_well: bool = name.chars()
+        .next()
+        .is_some_and(|character| character.is_ascii_alphabetic() || character == '_');
+
+    let lowercase: String = name.to_lowercase();
+    let is_register: bool = Register::values().any(|register| format!("{:?}", register).to_lowercase() == lowercase);
+
+    valid_characters && starts_well && !RESERVED_NAMES.contains(&lowercase.as_str()) && !is_register
+}
+
+
+// Write out the NASM source
+fn render(
+    binary: &Elf,
+    sections: &[OutputSection],
+    labels: Labels,
+    extern_names: &BTreeSet<String>,
+    data_values: &BTreeMap<u64, DataValue>) -> Result<String, ChiselError> {
+
+    let is_x86_64: bool = binary.file_header().is_x86_64;
+    let labels: Rc<Labels> = Rc::new(labels);
+    let mut source: String = String::new();
+
+    let resolver: Box<dyn SymbolResolver> = Box::new(LabelResolver { labels: Rc::clone(&labels) });
+    let mut formatter: NasmFormatter = NasmFormatter::with_options(Some(resolver), None);
+    formatter.options_mut().set_show_branch_size(false);
+    formatter.options_mut().set_first_operand_char_index(10);
+
+    let format: &str = if is_x86_64 { "elf64" } else { "elf32" };
+
+    source.push_str("; Generated by chisel. Assemble and link with:\n");
+    source.push_str(&format!(";   nasm -f {} <file>.asm -o <file>.o && gcc -no-pie <file>.o -o <file>\n\n", format));
+    source.push_str(if is_x86_64 { "bits 64\ndefault rel\n\n" } else { "bits 32\n\n" });
+
+    if labels.names.values().any(|name| name == "main") {
+        source.push_str("global main\n\n");
+    }
+
+    for name in extern_names {
+        source.push_str(&format!("extern {}\n", name));
+    }
+
+    let mut output: String = String::new();
+    let mut unresolved: usize = 0;
+
+    for section in sections {
+        source.push_str(&format!("\n\nsection {} {}\n", section.name, section.attributes));
+
+        for chunk in section.chunks.iter() {
+            match chunk {
+                Chunk::Code(instructions) => {
+                    for instruction in instructions {
+                        push_label(&mut source, &labels, instruction.ip());
+
+                        if is_raw(instruction) {
+                            let start: usize = (instruction.ip() - section.vaddr) as usize;
+                            push_bytes(&mut source, &section.data[start..start + instruction.len()]);
+                            continue;
+                        }
+
+                        output.clear();
+                        formatter.format(instruction, &mut output);
+
+                        // Addresses outside of the output can not be expressed
+                        let target: Option<u64> = disasm::branch_target(instruction).or_else(|| {
+                            Some(instruction.ip_rel_memory_address()).filter(|_| instruction.is_ip_rel_memory_operand())
+                        });
+
+                        match target.filter(|target| labels.reference(*target).is_none()) {
+                            Some(target) => {
+                                unresolved += 1;
+                                source.push_str(&format!("\t{}\t; [Warning] unresolved reference to {:#x}\n", output, target));
+                            },
+                            None => source.push_str(&format!("\t{}\n", output))
+                        }
+                    }
+                },
+                Chunk::Bytes(start, end) => {
+                    render_data(&mut source, section, *start, *end, &labels, data_values, is_x86_64);
+                }
+            }
+        }
+    }
+
+    source.push_str("\n\nsection .note.GNU-stack noalloc noexec nowrite progbits\n");
+
+    if unresolved > 0 {
+        source.insert_str(0, &format!("; [Warning] {} references point outside of the reassembled code and data\n", unresolved));
+    }
+
+    Ok(source)
+}
+
+
+// Instructions written as bytes: undecodable bytes, multi-byte nops whose
+// operand forms NASM does not accept and notrack branches
+fn is_raw(instruction: &Instruction) -> bool {
+    instruction.is_invalid()
+        || (instruction.mnemonic() == Mnemonic::Nop && instruction.op_count() > 0)
+        || (instruction.segment_prefix() == Register::DS && instruction.op0_kind() == OpKind::Register
+            && matches!(instruction.mnemonic(), Mnemonic::Jmp | Mnemonic::Call))
+}
+
+
+// Write a range of data, splitting it at labels and writing referring
+// slots symbolically
+fn render_data(
+    source: &mut String,
+    section: &OutputSection,
+    start: u64,
+    end: u64,
+    labels: &Labels,
+    data_values: &BTreeMap<u64, DataValue>,
+    is_x86_64: bool) {
+
+    let pointer_size: u64 = if is_x86_64 { 8 } else { 4 };
+    let pointer_directive: &str = if is_x86_64 { "dq" } else { "dd" };
+    let mut cursor: u64 = start;
+
+    while cursor < end {
+        push_label(source, labels, cursor);
+
+        // Stop each line at the next label or referring slot
+        let next_label: u64 = labels.names.range(cursor + 1..end).next().map_or(end, |(address, _)| *address);
+        let next_value: u64 = data_values.range(cursor + 1..end).next().map_or(end, |(address, _)| *address);
+
+        if section.nobits {
+            source.push_str(&format!("\tresb {}\n", next_label - cursor));
+            cursor = next_label;
+            continue;
+        }
+
+        if data_values.contains_key(&cursor) {
+            let value: Option<(u64, String)> = match &data_values[&cursor] {
+                DataValue::Pointer(target) => labels.operand(*target)
+                    .map(|operand| (pointer_size, format!("{} {}", pointer_directive, operand))),
+                DataValue::Extern(name, 0) => Some((pointer_size, format!("{} {}", pointer_directive, name))),
+                DataValue::Extern(name, addend) => Some((pointer_size, format!("{} {}{:+}", pointer_directive, name, addend))),
+                DataValue::TableEntry { table, target } => labels.names.get(target)
+                    .zip(labels.names.get(table))
+                    .map(|(target, table)| (4, format!("dd {} - {}", target, table)))
+            };
+
+            if let Some((size, text)) = value.filter(|(size, _)| cursor + size <= end && cursor + size <= next_label) {
+                source.push_str(&format!("\t{}\n", text));
+                cursor += size;
+                continue;
+            }
+        }
+
+        let stop: u64 = next_label.min(next_value).min(cursor + BYTES_PER_LINE as u64);
+
+        let offset: usize = (cursor - section.vaddr) as usize;
+        push_bytes(source, &section.data[offset..offset + (stop - cursor) as usize]);
+        cursor = stop;
+    }
+}
+
+
+fn push_label(source: &mut String, labels: &Labels, address: u64) {
+    if let Some(name) = labels.names.get(&address) {
+        source.push_str(&format!("{}:\n", name));
+    }
+}
+
+
+fn push_bytes(source: &mut String, bytes: &[u8]) {
+    let values: Vec<String> = bytes.iter().map(|byte| format!("0x{:02x}", byte)).collect();
+    source.push_str(&format!("\tdb {}\n", values.join(", ")));
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::{Command, Output};
+
+    use crate::binary::{CodeRegion, Elf};
+    use crate::functions::{self, Function};
+
+    use super::reassemble;
+
+
+    fn reassemble_file(path: &str) -> String {
+        let binary: Elf = Elf::from_file(path).unwrap();
+        let regions: Vec<CodeRegion> = binary.code_regions().unwrap();
+        let functions: Vec<Function> = functions::discover_functions(&binary, &regions, 0).unwrap();
+
+        reassemble(&binary, &regions, &functions, 0).unwrap()
+    }
+
+
+    #[test]
+    fn writes_hello_source() {
+        let source: String = reassemble_file("testing/hello");
+
+        assert!(source.contains("global main\n"));
+        assert!(source.contains("extern printf\n"));
+        assert!(source.contains("\nmain:\n"));
+        assert!(source.contains("section .rodata"));
+
+        // The C runtime startup code is left to the linker
+        assert!(!source.contains("\n_start:\n"));
+    }
+
+
+    // Assembles and links the output with nasm and gcc, then checks the
+    // program still runs. Run with `cargo test -- --ignored`.
+    #[test]
+    #[ignore = "needs nasm and gcc"]
+    fn reassembled_hello_runs() {
+        let directory: PathBuf = env::temp_dir().join(format!("chisel-reassemble-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        let source: PathBuf = directory.join("hello.asm");
+        let object: PathBuf = directory.join("hello.o");
+        let program: PathBuf = directory.join("hello");
+        fs::write(&source, reassemble_file("testing/hello")).unwrap();
+
+        let assembled: Output = Command::new("nasm").arg("-f").arg("elf64").arg(&source).arg("-o").arg(&object).output().unwrap();
+        assert!(assembled.status.success(), "nasm failed: {}", String::from_utf8_lossy(&assembled.stderr));
+
+        let linked: Output = Command::new("gcc").arg("-no-pie").arg(&object).arg("-o").arg(&program).output().unwrap();
+        assert!(linked.status.success(), "gcc failed: {}", String::from_utf8_lossy(&linked.stderr));
+
+        let original: Output = Command::new("testing/hello").output().unwrap();
+        let rebuilt: Output = Command::new(&program).output().unwrap();
+
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(rebuilt.status.code(), original.status.code());
+        assert_eq!(rebuilt.stdout, original.stdout);
+    }
+}
@@ -0,0 +1,390 @@
+// symbols.rs
+// Created: 10/17/2026
+// Description: Address to name resolution for the disassembly listing.
+//              Gathers symbols, PLT stubs, discovered functions and section
+//              names, and plugs them into the icedx86 formatters.
+
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use iced_x86::{FlowControl, Instruction, OpKind, Register, SymbolResolver, SymbolResult};
+
+use crate::binary::{CodeRegion, Elf};
+use crate::disasm;
+use crate::elf;
+use crate::error::ChiselError;
+use crate::functions::Function;
+
+
+#[derive(Debug, Clone)]
+struct NamedRange {
+    name: String,
+    size: u64
+}
+
+
+// Names for addresses in the image. Exact names come from symbols, PLT
+// stubs and functions; anything else inside an allocated section is named
+// relative to the section.
+#[derive(Debug, Default)]
+pub struct SymbolMap {
+    names: BTreeMap<u64, NamedRange>,
+    sections: BTreeMap<u64, NamedRange>,
+    resolve_immediates: bool
+}
+
+
+impl SymbolMap {
+    pub fn new() -> SymbolMap {
+        SymbolMap::default()
+    }
+
+
+    // Collect names from the binary and the discovered functions. Addresses
+    // in the binary are shifted by load_bias to match the regions.
+    pub fn from_binary(
+        binary: &Elf,
+        regions: &[CodeRegion],
+        functions: &[Function],
+        load_bias: u64) -> Result<SymbolMap, ChiselError> {
+
+        let mut map: SymbolMap = SymbolMap::new();
+
+        // Position independent images and object files start at zero, so
+        // small immediates would be named after whatever sits at the start
+        map.resolve_immediates = binary.file_header().elf_type == elf::ET_EXEC;
+
+        for section in binary.sections() {
+            if section.flags.contains(elf::SectionFlags::ALLOC) && section.addr != 0 && !section.name.is_empty() {
+                map.sections.insert(section.addr.wrapping_add(load_bias), NamedRange {
+                    name: section.name.clone(),
+                    size: section.size
+                });
+            }
+        }
+
+        for symbol in binary.symbols()?.iter().chain(binary.dynamic_symbols()?.iter()) {
+            let named: bool = symbol.sym_type == elf::STT_FUNC
+                || symbol.sym_type == elf::STT_OBJECT
+                || symbol.sym_type == elf::STT_NOTYPE;
+
+            if named && symbol.shndx != elf::SHN_UNDEF && symbol.shndx != elf::SHN_ABS && !symbol.name.is_empty() {
+                map.insert(symbol.value.wrapping_add(load_bias), &symbol.name, symbol.size);
+            }
+        }
+
+        for (address, name) in plt_stubs(binary, regions, load_bias)?.into_iter().chain(lazy_plt_stubs(binary, regions)?) {
+            map.insert(address, &name, 0);
+        }
+
+        // GOT slots read by indirect calls and PLT stubs
+        let pointer_size: u64 = binary.bitness() as u64 / 8;
+
+        for (address, name) in got_slots(binary, load_bias)? {
+            map.insert(address, &format!("{}@got", name), pointer_size);
+        }
+
+        for function in functions {
+            map.insert(function.start, &function.name, function.size());
+        }
+
+        Ok(map)
+    }
+
+
+    // Add a name, keeping the first one recorded for an address
+    pub fn insert(&mut self, address: u64, name: &str, size: u64) {
+        self.names.entry(address).or_insert(NamedRange {
+            name: name.to_string(),
+            size
+        });
+    }
+
+
+    // Name recorded for exactly this address
+    pub fn name_at(&self, address: u64) -> Option<&str> {
+        self.names.get(&address).map(|named| named.name.as_str())
+    }
+
+
+    // Name and base address to describe an address with, as in name+offset.
+    // Unsized names such as local labels cover the rest of their section,
+    // otherwise the address is named after the section holding it.
+    pub fn lookup(&self, address: u64) -> Option<(&str, u64)> {
+        let section: Option<(&str, u64)> = range_containing(&self.sections, address);
+
+        if let Some((start, named)) = self.names.range(..=address).next_back() {
+            let unsized_within: bool = named.size == 0 && section.is_some_and(|(_, base)| *start >= base);
+
+            if *start == address || address - *start < named.size || unsized_within {
+                return Some((named.name.as_str(), *start));
+            }
+        }
+
+        section
+    }
+
+
+    // Describe an address as name or name+0xoffset, or as plain hex
+    pub fn describe(&self, address: u64) -> String {
+        match self.lookup(address) {
+            Some((name, base)) if base == address => name.to_string(),
+            Some((name, base)) => format!("{}+{:#x}", name, address - base),
+            None => format!("{:#x}", address)
+        }
+    }
+}
+
+
+// Named range sized to cover an address
+fn range_containing(ranges: &BTreeMap<u64, NamedRange>, address: u64) -> Option<(&str, u64)> {
+    ranges.range(..=address)
+        .next_back()
+        .filter(|(start, named)| address - **start < named.size)
+        .map(|(start, named)| (named.name.as_str(), *start))
+}
+
+
+// SymbolResolver handed to the icedx86 formatters
+pub struct ElfSymbolResolver {
+    map: Rc<SymbolMap>
+}
+
+
+impl ElfSymbolResolver {
+    pub fn new(map: Rc<SymbolMap>) -> ElfSymbolResolver {
+        ElfSymbolResolver { map }
+    }
+}
+
+
+impl SymbolResolver for ElfSymbolResolver {
+    fn symbol(
+        &mut self,
+        instruction: &Instruction,
+        _operand: u32,
+        instruction_operand: Option<u32>,
+        address: u64,
+        _address_size: u32) -> Option<SymbolResult<'_>> {
+
+        // Immediates are only treated as addresses in non-PIE images
+        if let Some(operand) = instruction_operand {
+            let immediate: bool = matches!(
+                instruction.op_kind(operand),
+                OpKind::Immediate8 | OpKind::Immediate16 | OpKind::Immediate32
+                    | OpKind::Immediate64 | OpKind::Immediate8to16 | OpKind::Immediate8to32
+                    | OpKind::Immediate8to64 | OpKind::Immediate32to64
+            );
+
+            if immediate && !self.map.resolve_immediates {
+                return None;
+            }
+        }
+
+        self.map.lookup(address).map(|(name, base)| SymbolResult::with_str(base, name))
+    }
+}
+
+
+// Name the PLT stubs that jump through a GOT slot filled in by a
+// JUMP_SLOT or GLOB_DAT relocation, e.g. puts@plt
+pub fn plt_stubs(binary: &Elf, regions: &[CodeRegion], load_bias: u64) -> Result<Vec<(u64, String)>, ChiselError> {
+    let mut stubs: Vec<(u64, String)> = Vec::new();
+    let slots: HashMap<u64, String> = got_slots(binary, load_bias)?;
+
+    if slots.is_empty() {
+        return Ok(stubs);
+    }
+
+    let got_base: Option<u64> = plt_got(binary)?.map(|base| base.wrapping_add(load_bias));
+
+    for region in regions.iter().filter(|region| region.name.starts_with(".plt")) {
+        let mut stub_start: Option<u64> = None;
+
+        for instruction in disasm::LinearSweep::new(region.data, region.vaddr, binary.bitness()) {
+            // A stub may open with an endbr before its jump
+            if stub_start.is_none() {
+                stub_start = Some(instruction.ip());
+            }
+
+            if instruction.flow_control() != FlowControl::IndirectBranch || instruction.op0_kind() != OpKind::Memory {
+                if !matches!(instruction.mnemonic(), iced_x86::Mnemonic::Endbr64 | iced_x86::Mnemonic::Endbr32) {
+                    stub_start = None;
+                }
+                continue;
+            }
+
+            // x86-64 stubs jump through a RIP-relative slot, i386 ones
+            // through an absolute slot or, in PIC code, relative to the
+            // GOT address held in ebx
+            let slot: Option<u64> = if instruction.memory_index() != Register::None {
+                None
+            } else if instruction.is_ip_rel_memory_operand() {
+                Some(instruction.ip_rel_memory_address())
+            } else if instruction.memory_base() == Register::None {
+                Some(instruction.memory_displacement64())
+            } else if instruction.memory_base() == Register::EBX {
+                got_base.map(|base| base.wrapping_add(instruction.memory_displacement32() as u64) & 0xFFFF_FFFF)
+            } else {
+                None
+            };
+
+            if let Some(name) = slot.and_then(|slot| slots.get(&slot)) {
+                stubs.push((stub_start.unwrap_or(instruction.ip()), format!("{}@plt", name)));
+            }
+
+            stub_start = None;
+        }
+    }
+
+    Ok(stubs)
+}
+
+
+// Name the lazy binding entries of IBT .plt sections, e.g. puts@plt.lazy.
+// Calls go through the .plt.sec stubs, while each lazy entry pushes the
+// index of its JUMP_SLOT relocation and jumps to the resolver at the start
+// of .plt. Entries without an endbr also jump through their GOT slot, so
+// plt_stubs already names them.
+pub fn lazy_plt_stubs(binary: &Elf, regions: &[CodeRegion]) -> Result<Vec<(u64, String)>, ChiselError> {
+    let mut stubs: Vec<(u64, String)> = Vec::new();
+
+    let jmprel: u64 = match binary.dynamic_entries()?.iter().find(|entry| entry.tag == elf::DT_JMPREL) {
+        Some(entry) => entry.value,
+        None => return Ok(stubs)
+    };
+
+    let section: &elf::SectionHeader = match binary.sections().find(|section| {
+        section.addr == jmprel && (section.section_type == elf::SHT_RELA || section.section_type == elf::SHT_REL)
+    }) {
+        Some(section) => section,
+        None => return Ok(stubs)
+    };
+
+    let relocations: Vec<elf::Relocation> = binary.relocation_table(section)?;
+    let is_x86_64: bool = binary.file_header().is_x86_64;
+    let jump_slot: u32 = if is_x86_64 { elf::R_X86_64_JUMP_SLOT } else { elf::R_386_JUMP_SLOT };
+
+    // i386 pushes the byte offset of the relocation rather than its index
+    let scale: u64 = if is_x86_64 {
+        1
+    } else if section.entsize != 0 {
+        section.entsize
+    } else {
+        elf::REL_ENTRY_SIZE[0] as u64
+    };
+
+    for region in regions.iter().filter(|region| region.name == ".plt") {
+        let instructions: Vec<Instruction> = disasm::LinearSweep::new(region.data, region.vaddr, binary.bitness()).collect();
+
+        for entry in instructions.windows(3) {
+            let (endbr, push, jump): (&Instruction, &Instruction, &Instruction) = (&entry[0], &entry[1], &entry[2]);
+
+            if !matches!(endbr.mnemonic(), iced_x86::Mnemonic::Endbr64 | iced_x86::Mnemonic::Endbr32)
+                || push.mnemonic() != iced_x86::Mnemonic::Push
+                || !matches!(push.op0_kind(), OpKind::Immediate8to32 | OpKind::Immediate8to64 | OpKind::Immediate32 | OpKind::Immediate32to64)
+                || disasm::branch_target(jump) != Some(region.vaddr) {
+                continue;
+            }
+
+            let name: Option<&str> = relocations.get((push.immediate(0) / scale) as usize)
+                .filter(|relocation| relocation.rel_type == jump_slot)
+                .and_then(|relocation| relocation.symbol.as_ref())
+                .map(|symbol| symbol.name.as_str())
+                .filter(|name| !name.is_empty());
+
+            if let Some(name) = name {
+                stubs.push((endbr.ip(), format!("{}@plt.lazy", name)));
+            }
+        }
+    }
+
+    Ok(stubs)
+}
+
+
+// Address of the GOT that PLT stubs index from, given by DT_PLTGOT or
+// the .got.plt section
+fn plt_got(binary: &Elf) -> Result<Option<u64>, ChiselError> {
+    let pltgot: Option<u64> = binary.dynamic_entries()?
+        .iter()
+        .find(|entry| entry.tag == elf::DT_PLTGOT)
+        .map(|entry| entry.value);
+
+    Ok(pltgot.or_else(|| binary.section_by_name(".got.plt").map(|section| section.addr)))
+}
+
+
+// GOT slots patched by the dynamic linker, keyed by address, with the
+// name of the symbol each one resolves to
+pub fn got_slots(binary: &Elf, load_bias: u64) -> Result<HashMap<u64, String>, ChiselError> {
+    let mut slots: HashMap<u64, String> = HashMap::new();
+    let is_x86_64: bool = binary.file_header().is_x86_64;
+
+    let (jump_slot, glob_dat): (u32, u32) = if is_x86_64 {
+        (elf::R_X86_64_JUMP_SLOT, elf::R_X86_64_GLOB_DAT)
+    } else {
+        (elf::R_386_JUMP_SLOT, elf::R_386_GLOB_DAT)
+    };
+
+    for section in binary.sections() {
+        if section.section_type != elf::SHT_RELA && section.section_type != elf::SHT_REL {
+            continue;
+        }
+
+        for relocation in binary.relocation_table(section)? {
+            if relocation.rel_type != jump_slot && relocation.rel_type != glob_dat {
+                continue;
+            }
+
+            if let Some(symbol) = relocation.symbol.filter(|symbol| !symbol.name.is_empty()) {
+                slots.insert(relocation.offset.wrapping_add(load_bias), symbol.name);
+            }
+        }
+    }
+
+    Ok(slots)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::binary::{CodeRegion, Elf};
+
+    use super::{lazy_plt_stubs, plt_stubs};
+
+
+    // PLT stubs and lazy entries, sorted by address
+    fn stubs(path: &str) -> Vec<(u64, String)> {
+        let binary: Elf = Elf::from_file(path).unwrap();
+        let regions: Vec<CodeRegion> = binary.code_regions().unwrap();
+
+        let mut stubs: Vec<(u64, String)> = plt_stubs(&binary, &regions, 0).unwrap();
+        stubs.extend(lazy_plt_stubs(&binary, &regions).unwrap());
+        stubs.sort_unstable();
+        stubs
+    }
+
+
+    // Calls go through .plt.sec and .plt.got, the lazy entries in .plt are
+    // only reached through the GOT
+    #[test]
+    fn names_plt_stubs() {
+        assert_eq!(stubs("testing/hello"), vec![
+            (0x1030, "printf@plt.lazy".to_string()),
+            (0x1040, "__cxa_finalize@plt".to_string()),
+            (0x1050, "printf@plt".to_string())
+        ]);
+    }
+
+
+    // i386 PIC stubs jump through the GOT address held in ebx, and lazy
+    // entries push a byte offset into .rel.plt
+    #[test]
+    fn names_32_bit_plt_stubs() {
+        assert_eq!(stubs("testing/hello32"), vec![
+            (0x1010, "greet@plt.lazy".to_string()),
+            (0x1020, "greet@plt".to_string())
+        ]);
+    }
+}
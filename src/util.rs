@@ -4,75 +4,95 @@
 // Description: Utility script for storing common-use and helper
 //              functions.
 
+use std::collections::HashMap;
 use std::mem;
 // use std::io::{self, BufReader, BufRead};
 // use std::fs::File;
 // use std::num::ParseIntError;
 
 use crate::elf::{self, EndianType, ArchitectureType};
+use crate::error::ChiselError;
 
 
-pub fn build_file_header(data: &Vec<u8>) -> elf::FileHeader {
+pub fn build_file_header(data: &[u8]) -> Result<elf::FileHeader, ChiselError> {
+
+    check_bounds(data, 0, elf::IDENT_SIZE as u64, "ELF identification")?;
 
     // Determine x86 or x64 architecture
     // 0 : x86
     // 1 : x64
-    let arch: usize = (data[elf::ARCH_OFFSET as usize] - 1).into();
+    let arch: usize = match data[elf::ARCH_OFFSET as usize] {
+        0x01 => 0,
+        0x02 => 1,
+        class => return Err(ChiselError::InvalidClass(class))
+    };
+    let is_x86_64: bool = arch != 0;
+    // Every later read depends on the byte order, so it has to be known
+    let endian: EndianType = match parse_endian(data[elf::ENDIAN_OFFSET as usize]) {
+        EndianType::Unknown => return Err(ChiselError::InvalidDataEncoding(data[elf::ENDIAN_OFFSET as usize])),
+        endian => endian
+    };
 
-    let file_header: elf::FileHeader = elf::FileHeader {
+    check_bounds(data, 0, elf::FILE_HEADER_SIZE[arch] as u64, "file header")?;
+
+    Ok(elf::FileHeader {
         arch: parse_architecture(data[elf::ARCH_OFFSET as usize]),
-        is_x86_64: arch != 0,
-        endian: parse_endian(data[elf::ENDIAN_OFFSET as usize]),
+        is_x86_64,
+        endian,
         abi: data[elf::ABI_OFFSET as usize],
         abi_str: parse_abi(data[elf::ABI_OFFSET as usize]),
-        elf_type: u16_from_buffer(data, elf::TYPE_OFFSET as usize),
-        isa: u16_from_buffer(data, elf::MACHINE_OFFSET as usize),
-        isa_str: parse_isa(u16_from_buffer(data, elf::MACHINE_OFFSET as usize)),
-        entryoff: u64_from_buffer(data, elf::ENTRYPOINT_OFFSET as usize),
-        phoff: u64_from_buffer(data, elf::PHOFF_OFFSET[arch] as usize),
-        shoff: u64_from_buffer(data, elf::SHOFF_OFFSET[arch] as usize),
-        ehsize: u16_from_buffer(data, elf::EHSIZE_OFFSET[arch] as usize),
-        phentsize: u16_from_buffer(data, elf::PHENTSIZE_OFFSET[arch] as usize),
-        phnum: u16_from_buffer(data, elf::PHNUM_OFFSET[arch] as usize),
-        shentsize: u16_from_buffer(data, elf::SHENTSIZE_OFFSET[arch] as usize),
-        shnum: u16_from_buffer(data, elf::SHNUM_OFFSET[arch] as usize),
-        shstrndx: u16_from_buffer(data, elf::SHSTRNDX_OFFSET[arch] as usize),
-    };
-
-    return file_header;
+        elf_type: u16_from_buffer(data, elf::TYPE_OFFSET as usize, endian),
+        isa: u16_from_buffer(data, elf::MACHINE_OFFSET as usize, endian),
+        isa_str: parse_isa(u16_from_buffer(data, elf::MACHINE_OFFSET as usize, endian)),
+        entryoff: addr_from_buffer(data, elf::ENTRYPOINT_OFFSET as usize, is_x86_64, endian),
+        phoff: addr_from_buffer(data, elf::PHOFF_OFFSET[arch] as usize, is_x86_64, endian),
+        shoff: addr_from_buffer(data, elf::SHOFF_OFFSET[arch] as usize, is_x86_64, endian),
+        ehsize: u16_from_buffer(data, elf::EHSIZE_OFFSET[arch] as usize, endian),
+        phentsize: u16_from_buffer(data, elf::PHENTSIZE_OFFSET[arch] as usize, endian),
+        phnum: u16_from_buffer(data, elf::PHNUM_OFFSET[arch] as usize, endian),
+        shentsize: u16_from_buffer(data, elf::SHENTSIZE_OFFSET[arch] as usize, endian),
+        shnum: u16_from_buffer(data, elf::SHNUM_OFFSET[arch] as usize, endian),
+        shstrndx: u16_from_buffer(data, elf::SHSTRNDX_OFFSET[arch] as usize, endian),
+    })
 }
 
 
-pub fn build_program_header(data: &Vec<u8>, phoffset: usize, id: u16, is_x86_64: bool) -> elf::ProgramHeader {
+pub fn build_program_header(
+    data: &[u8],
+    phoffset: usize,
+    id: u16,
+    is_x86_64: bool,
+    endian: EndianType) -> Result<elf::ProgramHeader, ChiselError> {
 
     // Cast the supplied is_x86_64 bool to an array offset
     // 0 : x86
     // 1 : x64
     let arch: usize = is_x86_64.into();
 
-    let program_header: elf::ProgramHeader = elf::ProgramHeader {
-        id: id,
-        program_type: u32_from_buffer(data, phoffset + elf::PH_TYPE_OFFSET as usize),
-        flags: u32_from_buffer(data, phoffset + elf::PH_FLAGS_OFFSET[arch] as usize),
-        offset: u64_from_buffer(data, phoffset + elf::PH_OFFSET_OFFSET[arch] as usize),
-        vaddr: u64_from_buffer(data, phoffset + elf::PH_VADDR_OFFSET[arch] as usize),
-        paddr: u64_from_buffer(data, phoffset + elf::PH_PADDR_OFFSET[arch] as usize),
-        filesz: u64_from_buffer(data, phoffset + elf::PH_FILESZ_OFFSET[arch] as usize),
-        memsz: u64_from_buffer(data, phoffset + elf::PH_MEMSZ_OFFSET[arch] as usize),
-        align: u64_from_buffer(data, phoffset + elf::PH_ALIGN_OFFSET[arch] as usize)
-    };
-
-    return program_header;
+    check_bounds(data, phoffset as u64, elf::PH_ENTRY_SIZE[arch] as u64, &format!("program header [{}]", id))?;
+
+    Ok(elf::ProgramHeader {
+        id,
+        program_type: u32_from_buffer(data, phoffset + elf::PH_TYPE_OFFSET as usize, endian),
+        flags: elf::SegmentFlags(u32_from_buffer(data, phoffset + elf::PH_FLAGS_OFFSET[arch] as usize, endian)),
+        offset: addr_from_buffer(data, phoffset + elf::PH_OFFSET_OFFSET[arch] as usize, is_x86_64, endian),
+        vaddr: addr_from_buffer(data, phoffset + elf::PH_VADDR_OFFSET[arch] as usize, is_x86_64, endian),
+        paddr: addr_from_buffer(data, phoffset + elf::PH_PADDR_OFFSET[arch] as usize, is_x86_64, endian),
+        filesz: addr_from_buffer(data, phoffset + elf::PH_FILESZ_OFFSET[arch] as usize, is_x86_64, endian),
+        memsz: addr_from_buffer(data, phoffset + elf::PH_MEMSZ_OFFSET[arch] as usize, is_x86_64, endian),
+        align: addr_from_buffer(data, phoffset + elf::PH_ALIGN_OFFSET[arch] as usize, is_x86_64, endian)
+    })
 }
 
 
 pub fn overwrite_segment_header(
-    program_data: &mut Vec<u8>,
+    program_data: &mut [u8],
     phoffset: usize,
     phentsize: usize,
     phentidx: usize,
     new_segment: &elf::ProgramHeader,
-    is_x86_64: bool) {
+    is_x86_64: bool,
+    endian: EndianType) {
 
     // Cast the supplied is_x86_64 bool to an array offset
     // 0 : x86
@@ -92,72 +112,71 @@ pub fn overwrite_segment_header(
     let segment_flags_offset: usize = phoffset + (phentsize * phentidx) + elf::PH_FLAGS_OFFSET[arch] as usize;
     let segment_align_offset: usize = phoffset + (phentsize * phentidx) + elf::PH_ALIGN_OFFSET[arch] as usize;
 
-    program_data[segment_type_offset..segment_type_offset+4].copy_from_slice(
-            &new_segment.program_type.to_ne_bytes().to_vec());
+    u32_to_buffer(program_data, segment_type_offset, new_segment.program_type, endian);
     println!("Overwriting segment type with {:#04x}", new_segment.program_type);
 
-    program_data[segment_offset_offset..segment_offset_offset+8].copy_from_slice(
-        &new_segment.offset.to_ne_bytes().to_vec());
+    addr_to_buffer(program_data, segment_offset_offset, new_segment.offset, is_x86_64, endian);
     println!("Overwriting segment offset with {:#04x}", new_segment.offset);
 
-    program_data[segment_vaddr_offset..segment_vaddr_offset+8].copy_from_slice(
-        &new_segment.vaddr.to_ne_bytes().to_vec());
+    addr_to_buffer(program_data, segment_vaddr_offset, new_segment.vaddr, is_x86_64, endian);
     println!("Overwriting segment vaddr with {:#04x}", new_segment.vaddr);
 
-    program_data[segment_paddr_offset..segment_paddr_offset+8].copy_from_slice(
-        &new_segment.paddr.to_ne_bytes().to_vec());
+    addr_to_buffer(program_data, segment_paddr_offset, new_segment.paddr, is_x86_64, endian);
     println!("Overwriting segment paddr with {:#04x}", new_segment.paddr);
 
-    program_data[segment_filesz_offset..segment_filesz_offset+8].copy_from_slice(
-        &new_segment.filesz.to_ne_bytes().to_vec());
+    addr_to_buffer(program_data, segment_filesz_offset, new_segment.filesz, is_x86_64, endian);
     println!("Overwriting segment filesz with {:#04x}", new_segment.filesz);
 
-    program_data[segment_memsz_offset..segment_memsz_offset+8].copy_from_slice(
-        &new_segment.memsz.to_ne_bytes().to_vec());
+    addr_to_buffer(program_data, segment_memsz_offset, new_segment.memsz, is_x86_64, endian);
     println!("Overwriting segment memsz with {:#04x}", new_segment.memsz);
 
-    program_data[segment_flags_offset..segment_flags_offset+4].copy_from_slice(
-        &new_segment.flags.to_ne_bytes().to_vec());
-    println!("Overwriting segment flag with {:#04x}", new_segment.flags);
+    u32_to_buffer(program_data, segment_flags_offset, new_segment.flags.bits(), endian);
+    println!("Overwriting segment flag with {:#04x}", new_segment.flags.bits());
 
-    program_data[segment_align_offset..segment_align_offset+8].copy_from_slice(
-        &new_segment.align.to_ne_bytes().to_vec());
+    addr_to_buffer(program_data, segment_align_offset, new_segment.align, is_x86_64, endian);
     println!("Overwriting segment alignment with {:#04x}\n", new_segment.align);
 
 }
 
 
-pub fn build_section_header(data: &Vec<u8>, stoffset: usize, id: u16, is_x86_64: bool) -> elf::SectionHeader {
+pub fn build_section_header(
+    data: &[u8],
+    stoffset: usize,
+    id: u16,
+    is_x86_64: bool,
+    endian: EndianType) -> Result<elf::SectionHeader, ChiselError> {
 
     // Cast the supplied is_x86_64 bool to an array offset
     // 0 : x86
     // 1 : x64
     let arch: usize = is_x86_64.into();
 
-    let section_header: elf::SectionHeader = elf::SectionHeader {
-        id: id,
-        name_idx: u32_from_buffer(data, stoffset + elf::SH_NAME_OFFSET as usize),
-        section_type: u32_from_buffer(data, stoffset + elf::SH_TYPE_OFFSET as usize),
-        flags: u64_from_buffer(data, stoffset + elf::SH_FLAGS_OFFSET as usize),
-        addr: u64_from_buffer(data, stoffset + elf::SH_ADDR_OFFSET[arch] as usize),
-        offset: u64_from_buffer(data, stoffset + elf::SH_OFFSET_OFFSET[arch] as usize),
-        size: u64_from_buffer(data, stoffset + elf::SH_SIZE_OFFSET[arch] as usize),
-        link: u32_from_buffer(data, stoffset + elf::SH_LINK_OFFSET[arch] as usize),
-        info: u32_from_buffer(data, stoffset + elf::SH_INFO_OFFSET[arch] as usize),
-        addralign: u64_from_buffer(data, stoffset + elf::SH_ADDRALIGN_OFFSET[arch] as usize),
-        entsize: u64_from_buffer(data, stoffset + elf::SH_ENTSIZE_OFFSET[arch] as usize)
-    };
-
-    return section_header;
+    check_bounds(data, stoffset as u64, elf::SH_ENTRY_SIZE[arch] as u64, &format!("section header [{}]", id))?;
+
+    Ok(elf::SectionHeader {
+        id,
+        name: String::new(),
+        name_idx: u32_from_buffer(data, stoffset + elf::SH_NAME_OFFSET as usize, endian),
+        section_type: u32_from_buffer(data, stoffset + elf::SH_TYPE_OFFSET as usize, endian),
+        flags: elf::SectionFlags(addr_from_buffer(data, stoffset + elf::SH_FLAGS_OFFSET as usize, is_x86_64, endian)),
+        addr: addr_from_buffer(data, stoffset + elf::SH_ADDR_OFFSET[arch] as usize, is_x86_64, endian),
+        offset: addr_from_buffer(data, stoffset + elf::SH_OFFSET_OFFSET[arch] as usize, is_x86_64, endian),
+        size: addr_from_buffer(data, stoffset + elf::SH_SIZE_OFFSET[arch] as usize, is_x86_64, endian),
+        link: u32_from_buffer(data, stoffset + elf::SH_LINK_OFFSET[arch] as usize, endian),
+        info: u32_from_buffer(data, stoffset + elf::SH_INFO_OFFSET[arch] as usize, endian),
+        addralign: addr_from_buffer(data, stoffset + elf::SH_ADDRALIGN_OFFSET[arch] as usize, is_x86_64, endian),
+        entsize: addr_from_buffer(data, stoffset + elf::SH_ENTSIZE_OFFSET[arch] as usize, is_x86_64, endian)
+    })
 }
 
 
-pub fn overwrite_section_header(program_data: &mut Vec<u8>,
+pub fn overwrite_section_header(program_data: &mut [u8],
                                 stoffset: usize,
                                 shentsize: usize,
                                 shentidx: usize,
                                 new_section: &elf::SectionHeader,
-                                is_x86_64: bool) {
+                                is_x86_64: bool,
+                                endian: EndianType) {
 
     // Cast the supplied is_x86_64 bool to an array offset
     // 0 : x86
@@ -175,59 +194,504 @@ pub fn overwrite_section_header(program_data: &mut Vec<u8>,
     let section_flag_offset: usize = stoffset + (shentsize * shentidx) + elf::SH_FLAGS_OFFSET as usize;
     let section_align_offset: usize = stoffset + (shentsize * shentidx) + elf::SH_ADDRALIGN_OFFSET[arch] as usize;
 
-    program_data[section_addr_offset..section_addr_offset+8].copy_from_slice(
-                &new_section.addr.to_ne_bytes().to_vec());
+    addr_to_buffer(program_data, section_addr_offset, new_section.addr, is_x86_64, endian);
     println!("Overwriting section addr with {:#04x}", new_section.addr);
 
-    program_data[section_offset_offset..section_offset_offset+8].copy_from_slice(
-        &new_section.offset.to_ne_bytes().to_vec());
+    addr_to_buffer(program_data, section_offset_offset, new_section.offset, is_x86_64, endian);
     println!("Overwriting section offset with {:#04x}", new_section.offset as usize);
 
-    program_data[section_size_offset..section_size_offset+8].copy_from_slice(
-        &new_section.size.to_ne_bytes().to_vec());
+    addr_to_buffer(program_data, section_size_offset, new_section.size, is_x86_64, endian);
     println!("Overwriting section size with {:#04x}", new_section.size as usize);
 
-    program_data[section_type_offset..section_type_offset+4].copy_from_slice(
-        &new_section.section_type.to_ne_bytes().to_vec());
+    u32_to_buffer(program_data, section_type_offset, new_section.section_type, endian);
     println!("Overwriting section type with {:#04x}", new_section.section_type as usize);
 
-    program_data[section_flag_offset..section_flag_offset+8].copy_from_slice(
-        &new_section.flags.to_ne_bytes().to_vec());
-    println!("Overwriting section flags with {:#04x}", new_section.flags as usize);
+    addr_to_buffer(program_data, section_flag_offset, new_section.flags.bits(), is_x86_64, endian);
+    println!("Overwriting section flags with {:#04x}", new_section.flags.bits());
 
-    program_data[section_align_offset..section_align_offset+8].copy_from_slice(
-        &new_section.addralign.to_ne_bytes().to_vec());
+    addr_to_buffer(program_data, section_align_offset, new_section.addralign, is_x86_64, endian);
     println!("Overwriting section address alignment with {:#04x}\n", new_section.addralign as usize);
 
     // return section_header;
 }
 
 
-pub fn overwrite_entrypoint(program_data: &mut Vec<u8>,
-                           new_entry_point: usize) {
+pub fn build_symbol(
+    data: &[u8],
+    symoffset: usize,
+    id: u32,
+    is_x86_64: bool,
+    endian: EndianType) -> Result<elf::Symbol, ChiselError> {
+
+    // Cast the supplied is_x86_64 bool to an array offset
+    // 0 : x86
+    // 1 : x64
+    let arch: usize = is_x86_64.into();
+
+    check_bounds(data, symoffset as u64, elf::ST_ENTRY_SIZE[arch] as u64, &format!("symbol [{}]", id))?;
+
+    // st_info packs the binding in the high nibble and the type in the low nibble,
+    // st_other holds the visibility in its low two bits
+    let info: u8 = data[symoffset + elf::ST_INFO_OFFSET[arch] as usize];
+    let other: u8 = data[symoffset + elf::ST_OTHER_OFFSET[arch] as usize];
+
+    Ok(elf::Symbol {
+        id,
+        name: String::new(),
+        name_idx: u32_from_buffer(data, symoffset + elf::ST_NAME_OFFSET as usize, endian),
+        value: addr_from_buffer(data, symoffset + elf::ST_VALUE_OFFSET[arch] as usize, is_x86_64, endian),
+        size: addr_from_buffer(data, symoffset + elf::ST_SIZE_OFFSET[arch] as usize, is_x86_64, endian),
+        sym_type: info & 0x0F,
+        binding: info >> 4,
+        visibility: other & 0x03,
+        shndx: u16_from_buffer(data, symoffset + elf::ST_SHNDX_OFFSET[arch] as usize, endian)
+    })
+}
+
+
+pub fn build_dynamic_entry(
+    data: &[u8],
+    dynoffset: usize,
+    id: u32,
+    is_x86_64: bool,
+    endian: EndianType) -> Result<elf::DynamicEntry, ChiselError> {
+
+    // Cast the supplied is_x86_64 bool to an array offset
+    // 0 : x86
+    // 1 : x64
+    let arch: usize = is_x86_64.into();
+
+    check_bounds(data, dynoffset as u64, elf::DYN_ENTRY_SIZE[arch] as u64, &format!("dynamic entry [{}]", id))?;
+
+    Ok(elf::DynamicEntry {
+        id,
+        tag: addr_from_buffer(data, dynoffset + elf::D_TAG_OFFSET as usize, is_x86_64, endian),
+        value: addr_from_buffer(data, dynoffset + elf::D_VAL_OFFSET[arch] as usize, is_x86_64, endian),
+        string: None
+    })
+}
+
+
+pub fn build_relocation(
+    data: &[u8],
+    reloffset: usize,
+    id: u32,
+    has_addend: bool,
+    is_x86_64: bool,
+    endian: EndianType) -> Result<elf::Relocation, ChiselError> {
+
+    // Cast the supplied is_x86_64 bool to an array offset
+    // 0 : x86
+    // 1 : x64
+    let arch: usize = is_x86_64.into();
+
+    let entry_size: u8 = if has_addend { elf::RELA_ENTRY_SIZE[arch] } else { elf::REL_ENTRY_SIZE[arch] };
+    check_bounds(data, reloffset as u64, entry_size as u64, &format!("relocation [{}]", id))?;
+
+    let info: u64 = addr_from_buffer(data, reloffset + elf::R_INFO_OFFSET[arch] as usize, is_x86_64, endian);
+
+    // r_info packs the symbol index and relocation type differently per class
+    // x86    : sym << 8 | type (8 bits)
+    // x86_64 : sym << 32 | type (32 bits)
+    let (sym_idx, rel_type): (u32, u32) = if is_x86_64 {
+        ((info >> 32) as u32, (info & 0xFFFFFFFF) as u32)
+    } else {
+        ((info >> 8) as u32, (info & 0xFF) as u32)
+    };
+
+    // Addends are signed and sign-extended from 32 bits on x86
+    let addend: Option<i64> = if !has_addend {
+        None
+    } else if is_x86_64 {
+        Some(u64_from_buffer(data, reloffset + elf::R_ADDEND_OFFSET[arch] as usize, endian) as i64)
+    } else {
+        Some(u32_from_buffer(data, reloffset + elf::R_ADDEND_OFFSET[arch] as usize, endian) as i32 as i64)
+    };
+
+    Ok(elf::Relocation {
+        id,
+        offset: addr_from_buffer(data, reloffset + elf::R_OFFSET_OFFSET as usize, is_x86_64, endian),
+        info,
+        rel_type,
+        sym_idx,
+        addend,
+        symbol: None
+    })
+}
+
+
+// Build a single note entry. Returns the note along with the number of
+// bytes it occupies, including the padding of its name and descriptor.
+pub fn build_note(
+    data: &[u8],
+    noteoffset: usize,
+    align: u64,
+    is_x86_64: bool,
+    endian: EndianType) -> Result<(elf::Note, u64), ChiselError> {
+
+    check_bounds(data, noteoffset as u64, elf::NOTE_HEADER_SIZE as u64, "note header")?;
+
+    let namesz: u64 = u32_from_buffer(data, noteoffset + elf::N_NAMESZ_OFFSET as usize, endian) as u64;
+    let descsz: u64 = u32_from_buffer(data, noteoffset + elf::N_DESCSZ_OFFSET as usize, endian) as u64;
+    let note_type: u32 = u32_from_buffer(data, noteoffset + elf::N_TYPE_OFFSET as usize, endian);
+
+    // The name and the descriptor are each padded to the note alignment,
+    // 4 bytes for most notes and 8 for those in 8-byte aligned segments
+    let name_offset: u64 = noteoffset as u64 + elf::NOTE_HEADER_SIZE as u64;
+    let desc_offset: u64 = align_up(name_offset + namesz, align);
+    let note_end: u64 = align_up(desc_offset + descsz, align);
+
+    check_bounds(data, name_offset, namesz, "note name")?;
+    check_bounds(data, desc_offset, descsz, "note descriptor")?;
+
+    // Names are NUL terminated within namesz
+    let name_bytes: &[u8] = &data[name_offset as usize..(name_offset + namesz) as usize];
+    let name: String = String::from_utf8_lossy(name_bytes).trim_end_matches('\0').to_string();
+    let desc: Vec<u8> = data[desc_offset as usize..(desc_offset + descsz) as usize].to_vec();
+
+    let note_data: elf::NoteData = parse_note_data(&name, note_type, &desc, is_x86_64, endian);
+
+    let note: elf::Note = elf::Note {
+        name,
+        note_type,
+        desc,
+        data: note_data
+    };
+
+    Ok((note, note_end - noteoffset as u64))
+}
+
+
+// Interpret the descriptor of the GNU notes that chisel understands
+pub fn parse_note_data(name: &str, note_type: u32, desc: &[u8], is_x86_64: bool, endian: EndianType) -> elf::NoteData {
+    if name != "GNU" {
+        return elf::NoteData::Unknown;
+    }
+
+    match note_type {
+        elf::NT_GNU_BUILD_ID => {
+            let id: String = desc.iter().map(|byte| format!("{:02x}", byte)).collect();
+            elf::NoteData::BuildId(id)
+        },
+        elf::NT_GNU_ABI_TAG if desc.len() >= 16 => elf::NoteData::AbiTag {
+            os: u32_from_buffer(desc, 0x00, endian),
+            major: u32_from_buffer(desc, 0x04, endian),
+            minor: u32_from_buffer(desc, 0x08, endian),
+            patch: u32_from_buffer(desc, 0x0C, endian)
+        },
+        elf::NT_GNU_PROPERTY_TYPE_0 => {
+            let mut properties: Vec<elf::GnuProperty> = Vec::new();
+            let mut offset: usize = 0;
+
+            // Property arrays are padded to the native word size
+            let align: u64 = if is_x86_64 { 8 } else { 4 };
+
+            while offset + 8 <= desc.len() {
+                let pr_type: u32 = u32_from_buffer(desc, offset, endian);
+                let pr_datasz: usize = u32_from_buffer(desc, offset + 4, endian) as usize;
+                let data_start: usize = offset + 8;
+
+                if pr_datasz > desc.len() - data_start {
+                    break;
+                }
+
+                let data: Vec<u8> = desc[data_start..data_start + pr_datasz].to_vec();
+                let value: u32 = if data.len() >= 4 { u32_from_buffer(&data, 0, endian) } else { 0 };
+
+                properties.push(elf::GnuProperty { pr_type, value, data });
+                offset = align_up((data_start + pr_datasz) as u64, align) as usize;
+            }
+
+            elf::NoteData::GnuProperties(properties)
+        },
+        _ => elf::NoteData::Unknown
+    }
+}
+
+
+// Build the frame description entries of an .eh_frame section. The vaddr
+// argument is the address the section is loaded at, which pc-relative
+// pointers are resolved against.
+pub fn build_frame_entries(
+    data: &[u8],
+    vaddr: u64,
+    is_x86_64: bool,
+    endian: EndianType) -> Result<Vec<elf::FrameDescriptionEntry>, ChiselError> {
+
+    let mut entries: Vec<elf::FrameDescriptionEntry> = Vec::new();
+
+    // FDE pointer encoding of each CIE, keyed by the offset of the CIE.
+    // CIEs that can not be read are kept as None so their FDEs are skipped.
+    let mut cie_encodings: HashMap<u64, Option<u8>> = HashMap::new();
+    let mut offset: usize = 0;
+
+    while offset + 4 <= data.len() {
+        let mut length: u64 = u32_from_buffer(data, offset, endian) as u64;
+        let mut id_offset: usize = offset + 4;
+        let mut id_size: usize = 4;
+
+        // A zero length entry terminates the section
+        if length == 0 {
+            break;
+        }
+
+        if length == 0xFFFFFFFF {
+            check_bounds(data, id_offset as u64, 8, ".eh_frame extended length")?;
+            length = u64_from_buffer(data, id_offset, endian);
+            id_offset += 8;
+            id_size = 8;
+        }
+
+        check_bounds(data, id_offset as u64, length, ".eh_frame entry")?;
+
+        // Limit reads to the current entry, which must hold at least its id
+        let entry_end: usize = id_offset + length as usize;
+        let entry: &[u8] = &data[..entry_end];
+        check_bounds(entry, id_offset as u64, id_size as u64, ".eh_frame entry id")?;
+
+        let id: u64 = if id_size == 8 {
+            u64_from_buffer(entry, id_offset, endian)
+        } else {
+            u32_from_buffer(entry, id_offset, endian) as u64
+        };
+
+        if id == 0 {
+            let encoding: Option<u8> = parse_cie_encoding(entry, id_offset + id_size, is_x86_64, endian).ok();
+            cie_encodings.insert(offset as u64, encoding);
+        } else {
+            // The CIE pointer counts back from the pointer field itself
+            let cie_offset: u64 = (id_offset as u64).wrapping_sub(id);
+            let encoding: Option<u8> = cie_encodings.get(&cie_offset).copied().unwrap_or(Some(elf::DW_EH_PE_ABSPTR));
+
+            // Entries chisel can not read are left out, the rest of the
+            // table is still usable
+            let range: Option<(u64, u64)> = encoding.and_then(|encoding| {
+                parse_fde_range(entry, id_offset + id_size, encoding, vaddr, is_x86_64, endian).ok()
+            });
+
+            if let Some((pc_begin, pc_range)) = range {
+                entries.push(elf::FrameDescriptionEntry {
+                    offset: offset as u64,
+                    pc_begin,
+                    pc_range
+                });
+            }
+        }
+
+        offset = entry_end;
+    }
+
+    Ok(entries)
+}
+
+
+// Read the pc_begin and pc_range fields of an FDE, which start at index
+fn parse_fde_range(
+    entry: &[u8],
+    index: usize,
+    encoding: u8,
+    vaddr: u64,
+    is_x86_64: bool,
+    endian: EndianType) -> Result<(u64, u64), ChiselError> {
+
+    let (pc_begin, size): (u64, usize) = encoded_pointer_from_buffer(
+        entry, index, encoding, vaddr.wrapping_add(index as u64), is_x86_64, endian
+    )?;
+
+    // The range uses the same format, but is never relative
+    let (pc_range, _): (u64, usize) = encoded_pointer_from_buffer(
+        entry, index + size, encoding & 0x0F, 0, is_x86_64, endian
+    )?;
+
+    Ok((pc_begin, pc_range))
+}
+
+
+// Walk the augmentation data of a CIE to find the encoding its FDEs use
+// for their pc_begin and pc_range fields
+fn parse_cie_encoding(entry: &[u8], index: usize, is_x86_64: bool, endian: EndianType) -> Result<u8, ChiselError> {
+    check_bounds(entry, index as u64, 1, ".eh_frame CIE")?;
+
+    let version: u8 = entry[index];
+    let augmentation: String = parse_string(entry, index + 1, ".eh_frame CIE")?;
+
+    // parse_string guarantees the terminator is present
+    let augmentation_size: usize = entry[index + 1..].iter().position(|&byte| byte == 0x00).unwrap_or(0);
+    let mut index: usize = index + 1 + augmentation_size + 1;
+
+    // GCC 2.x "eh" augmentation carries a pointer before the alignment fields
+    if augmentation.contains("eh") {
+        index += if is_x86_64 { 8 } else { 4 };
+    }
+
+    // Code alignment, data alignment and return address register
+    index += uleb128_from_buffer(entry, index)?.1;
+    index += sleb128_from_buffer(entry, index)?.1;
+    index += if version == 1 { 1 } else { uleb128_from_buffer(entry, index)?.1 };
+
+    if !augmentation.starts_with('z') {
+        return Ok(elf::DW_EH_PE_ABSPTR);
+    }
+
+    index += uleb128_from_buffer(entry, index)?.1;
+
+    for character in augmentation.chars().skip(1) {
+        check_bounds(entry, index as u64, 1, ".eh_frame CIE augmentation")?;
+
+        match character {
+            'R' => return Ok(entry[index]),
+            'P' => {
+                let encoding: u8 = entry[index];
+                index += 1 + encoded_pointer_from_buffer(entry, index + 1, encoding, 0, is_x86_64, endian)?.1;
+            },
+            'L' => index += 1,
+            'S' | 'B' => {},
+            _ => break
+        }
+    }
+
+    Ok(elf::DW_EH_PE_ABSPTR)
+}
+
+
+// Read a pointer stored with a DW_EH_PE encoding. The pc argument is the
+// address of the field, used by pc-relative encodings. Returns the value
+// along with the number of bytes it occupies.
+pub fn encoded_pointer_from_buffer(
+    buff: &[u8],
+    index: usize,
+    encoding: u8,
+    pc: u64,
+    is_x86_64: bool,
+    endian: EndianType) -> Result<(u64, usize), ChiselError> {
+
+    if encoding == elf::DW_EH_PE_OMIT {
+        return Ok((0, 0));
+    }
+
+    let fixed_size: usize = match encoding & 0x0F {
+        elf::DW_EH_PE_ABSPTR => if is_x86_64 { 8 } else { 4 },
+        elf::DW_EH_PE_UDATA2 | elf::DW_EH_PE_SDATA2 => 2,
+        elf::DW_EH_PE_UDATA4 | elf::DW_EH_PE_SDATA4 => 4,
+        elf::DW_EH_PE_UDATA8 | elf::DW_EH_PE_SDATA8 => 8,
+        _ => 0
+    };
+
+    if fixed_size != 0 {
+        check_bounds(buff, index as u64, fixed_size as u64, "encoded pointer")?;
+    }
+
+    let (value, size): (u64, usize) = match encoding & 0x0F {
+        elf::DW_EH_PE_ABSPTR => (addr_from_buffer(buff, index, is_x86_64, endian), fixed_size),
+        elf::DW_EH_PE_ULEB128 => uleb128_from_buffer(buff, index)?,
+        elf::DW_EH_PE_SLEB128 => {
+            let (value, size): (i64, usize) = sleb128_from_buffer(buff, index)?;
+            (value as u64, size)
+        },
+        elf::DW_EH_PE_UDATA2 => (u16_from_buffer(buff, index, endian) as u64, 2),
+        elf::DW_EH_PE_UDATA4 => (u32_from_buffer(buff, index, endian) as u64, 4),
+        elf::DW_EH_PE_UDATA8 => (u64_from_buffer(buff, index, endian), 8),
+        elf::DW_EH_PE_SDATA2 => (u16_from_buffer(buff, index, endian) as i16 as u64, 2),
+        elf::DW_EH_PE_SDATA4 => (u32_from_buffer(buff, index, endian) as i32 as u64, 4),
+        elf::DW_EH_PE_SDATA8 => (u64_from_buffer(buff, index, endian), 8),
+        _ => return Err(ChiselError::UnsupportedEncoding {
+            structure: "encoded pointer".to_string(),
+            encoding
+        })
+    };
+
+    match encoding & 0x70 {
+        elf::DW_EH_PE_PCREL => Ok((pc.wrapping_add(value), size)),
+        _ => Ok((value, size))
+    }
+}
+
+
+// Read an unsigned LEB128 value. Returns the value and its length in bytes.
+pub fn uleb128_from_buffer(buff: &[u8], index: usize) -> Result<(u64, usize), ChiselError> {
+    let mut value: u64 = 0;
+    let mut shift: u32 = 0;
+    let mut size: usize = 0;
+
+    loop {
+        check_bounds(buff, (index + size) as u64, 1, "LEB128 value")?;
+        let byte: u8 = buff[index + size];
+        size += 1;
+
+        if shift < 64 {
+            value |= ((byte & 0x7F) as u64) << shift;
+        }
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok((value, size));
+        }
+    }
+}
+
+
+// Read a signed LEB128 value. Returns the value and its length in bytes.
+pub fn sleb128_from_buffer(buff: &[u8], index: usize) -> Result<(i64, usize), ChiselError> {
+    let mut value: i64 = 0;
+    let mut shift: u32 = 0;
+    let mut size: usize = 0;
+
+    loop {
+        check_bounds(buff, (index + size) as u64, 1, "LEB128 value")?;
+        let byte: u8 = buff[index + size];
+        size += 1;
+
+        if shift < 64 {
+            value |= ((byte & 0x7F) as i64) << shift;
+        }
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            // Sign extend from the last byte read
+            if shift < 64 && byte & 0x40 != 0 {
+                value |= -1i64 << shift;
+            }
+            return Ok((value, size));
+        }
+    }
+}
+
+
+// Round a value up to the next multiple of align
+pub fn align_up(value: u64, align: u64) -> u64 {
+    if align <= 1 {
+        return value;
+    }
+
+    value.div_ceil(align) * align
+}
+
+
+pub fn overwrite_entrypoint(program_data: &mut [u8],
+                           new_entry_point: usize,
+                           is_x86_64: bool,
+                           endian: EndianType) {
     
     let offset: usize = elf::ENTRYPOINT_OFFSET as usize;
-    program_data[offset..offset+8].copy_from_slice(
-        &new_entry_point.to_ne_bytes().to_vec()
-    );
-    println!("Overwriting program entrypoint with {:#04x}\n", new_entry_point as usize);
+    addr_to_buffer(program_data, offset, new_entry_point as u64, is_x86_64, endian);
+    println!("Overwriting program entrypoint with {:#04x}\n", new_entry_point);
 }
 
 
 pub fn parse_endian(endian: u8) -> elf::EndianType {
     match endian {
-        0x00 => return EndianType::Big,
-        0x01 => return EndianType::Little,
-        _ => return EndianType::Unknown
+        0x01 => EndianType::Little,
+        0x02 => EndianType::Big,
+        _ => EndianType::Unknown
     }
 }
 
 
 pub fn parse_architecture(arch: u8) -> elf::ArchitectureType {
     match arch {
-        0x01 => return ArchitectureType::X86,
-        0x02 => return ArchitectureType::X86_64,
-        _ => return ArchitectureType::Unknown
+        0x01 => ArchitectureType::X86,
+        0x02 => ArchitectureType::X86_64,
+        _ => ArchitectureType::Unknown
     }
 }
 
@@ -271,56 +735,123 @@ pub fn parse_isa(isa: u16) -> String {
 }
 
 
-pub fn u16_from_buffer(buff: &Vec<u8>, index: usize) -> u16 {
+pub fn u16_from_buffer(buff: &[u8], index: usize, endian: EndianType) -> u16 {
     const SIZE: usize = mem::size_of::<u16>();
 
     let mut slice: [u8; SIZE] = [0; SIZE];
     slice.copy_from_slice(&buff[index..index+SIZE]);
 
-    let value: u16 = u16::from_ne_bytes(slice);
-
-    return value;
+    match endian {
+        EndianType::Big => u16::from_be_bytes(slice),
+        _ => u16::from_le_bytes(slice)
+    }
 }
 
 
-pub fn u32_from_buffer(buff: &Vec<u8>, index: usize) -> u32 {
+pub fn u32_from_buffer(buff: &[u8], index: usize, endian: EndianType) -> u32 {
     const SIZE: usize = mem::size_of::<u32>();
 
     let mut slice: [u8; SIZE] = [0; SIZE];
     slice.copy_from_slice(&buff[index..index+SIZE]);
 
-    let value: u32 = u32::from_ne_bytes(slice);
-
-    return value;
+    match endian {
+        EndianType::Big => u32::from_be_bytes(slice),
+        _ => u32::from_le_bytes(slice)
+    }
 }
 
 
-pub fn u64_from_buffer(buff: &Vec<u8>, index: usize) -> u64 {
+pub fn u64_from_buffer(buff: &[u8], index: usize, endian: EndianType) -> u64 {
     const SIZE: usize = mem::size_of::<u64>();
 
     let mut slice: [u8; SIZE] = [0; SIZE];
     slice.copy_from_slice(&buff[index..index+SIZE]);
 
-    let value: u64 = u64::from_ne_bytes(slice);
+    match endian {
+        EndianType::Big => u64::from_be_bytes(slice),
+        _ => u64::from_le_bytes(slice)
+    }
+}
+
 
-    return value;
+// Read an address, offset or size field whose width depends on the
+// ELF class (Elf32_Addr is 4 bytes, Elf64_Addr is 8 bytes)
+pub fn addr_from_buffer(buff: &[u8], index: usize, is_x86_64: bool, endian: EndianType) -> u64 {
+    if is_x86_64 {
+        u64_from_buffer(buff, index, endian)
+    } else {
+        u32_from_buffer(buff, index, endian) as u64
+    }
 }
 
 
-pub fn parse_section_name(buff: &Vec<u8>, index: usize) -> String {
-    let mut name: Vec<u8> = Vec::new();
-    let mut char_ctr: usize = index;
-    let mut char: u8 = buff[index];
-    
-    while char != 0x00 {
-        name.push(char);
-        char_ctr += 1;
-        char = buff[char_ctr];
+pub fn u32_to_buffer(buff: &mut [u8], index: usize, value: u32, endian: EndianType) {
+    let bytes: [u8; 4] = match endian {
+        EndianType::Big => value.to_be_bytes(),
+        _ => value.to_le_bytes()
+    };
+
+    buff[index..index+4].copy_from_slice(&bytes);
+}
+
+
+pub fn u64_to_buffer(buff: &mut [u8], index: usize, value: u64, endian: EndianType) {
+    let bytes: [u8; 8] = match endian {
+        EndianType::Big => value.to_be_bytes(),
+        _ => value.to_le_bytes()
+    };
+
+    buff[index..index+8].copy_from_slice(&bytes);
+}
+
+
+// Write an address, offset or size field at the width of the ELF class
+pub fn addr_to_buffer(buff: &mut [u8], index: usize, value: u64, is_x86_64: bool, endian: EndianType) {
+    if is_x86_64 {
+        u64_to_buffer(buff, index, value, endian);
+    } else {
+        u32_to_buffer(buff, index, value as u32, endian);
     }
+}
 
-    let result = String::from_utf8(name).expect("[Error] Failed to parse section name!");
-    
-    return result;
+
+// Ensure that a structure of the given size at the given file offset
+// lies entirely within the buffer
+pub fn check_bounds(data: &[u8], offset: u64, size: u64, structure: &str) -> Result<(), ChiselError> {
+    match offset.checked_add(size) {
+        Some(end) if end <= data.len() as u64 => Ok(()),
+        _ => Err(ChiselError::Truncated {
+            structure: structure.to_string(),
+            offset,
+            size,
+            file_size: data.len()
+        })
+    }
+}
+
+
+pub fn parse_section_name(buff: &[u8], index: usize) -> Result<String, ChiselError> {
+    parse_string(buff, index, ".shstrtab")
+}
+
+
+// Read a NUL-terminated string out of a string table section
+pub fn parse_string(buff: &[u8], index: usize, table: &str) -> Result<String, ChiselError> {
+    if index >= buff.len() {
+        return Err(ChiselError::StringOutOfBounds {
+            table: table.to_string(),
+            index: index as u64,
+            size: buff.len() as u64
+        });
+    }
+
+    match buff[index..].iter().position(|&char| char == 0x00) {
+        Some(length) => Ok(String::from_utf8_lossy(&buff[index..index + length]).to_string()),
+        None => Err(ChiselError::UnterminatedString {
+            table: table.to_string(),
+            index: index as u64
+        })
+    }
 }
 
 
@@ -370,29 +901,389 @@ pub fn parse_section_type(section_type: u32) -> String {
 }
 
 
-pub fn parse_section_flags(flags: u64) -> String {
-    match flags {
-        0x00000001 => "SHF_WRITE".to_string(),
-        0x00000002 => "SHF_ALLOC".to_string(),
-        0x00000004 => "SHF_EXECINSTR".to_string(),
-        0x00000010 => "SHF_MERGE".to_string(),
-        0x00000020 => "SHF_STRINGS".to_string(),
-        0x00000040 => "SHF_INFO_LINK".to_string(),
-        0x00000080 => "SHF_LINK_ORDER".to_string(),
-        0x00000100 => "SHF_OS_NONCONFORMING".to_string(),
-        0x00000200 => "SHF_GROUP".to_string(),
-        0x00000400 => "SHF_TLS".to_string(),
-        0x0FF00000 => "SHF_MASKOS".to_string(),
-        0xF0000000 => "SHF_MASKPROC".to_string(),
-        0x40000000 => "SHF_ORDERED".to_string(),
-        0x80000000 => "SHF_EXCLUDE".to_string(),
+// Render section flags as the familiar readelf key letters, e.g. "AX"
+// for .text or "WA" for .data. Bits without a letter are shown as 'x'.
+pub fn parse_section_flags(flags: elf::SectionFlags) -> String {
+    let letters: [(elf::SectionFlags, char); 14] = [
+        (elf::SectionFlags::WRITE, 'W'),
+        (elf::SectionFlags::ALLOC, 'A'),
+        (elf::SectionFlags::EXECINSTR, 'X'),
+        (elf::SectionFlags::MERGE, 'M'),
+        (elf::SectionFlags::STRINGS, 'S'),
+        (elf::SectionFlags::INFO_LINK, 'I'),
+        (elf::SectionFlags::LINK_ORDER, 'L'),
+        (elf::SectionFlags::OS_NONCONFORMING, 'O'),
+        (elf::SectionFlags::GROUP, 'G'),
+        (elf::SectionFlags::TLS, 'T'),
+        (elf::SectionFlags::COMPRESSED, 'C'),
+        (elf::SectionFlags::GNU_RETAIN, 'R'),
+        (elf::SectionFlags::X86_64_LARGE, 'l'),
+        (elf::SectionFlags::EXCLUDE, 'E'),
+    ];
+
+    let mut rendered: String = String::new();
+    let mut remaining: u64 = flags.bits();
+
+    for (flag, letter) in letters.iter() {
+        if flags.contains(*flag) {
+            rendered.push(*letter);
+            remaining &= !flag.bits();
+        }
+    }
 
-        // Match unknown segment type
+    if remaining != 0 {
+        rendered.push('x');
+    }
+
+    rendered
+}
+
+
+// Render segment flags in R, W, X order with '-' for clear bits, e.g. "R-X"
+pub fn parse_segment_flags(flags: elf::SegmentFlags) -> String {
+    let mut rendered: String = String::new();
+
+    rendered.push(if flags.contains(elf::SegmentFlags::R) { 'R' } else { '-' });
+    rendered.push(if flags.contains(elf::SegmentFlags::W) { 'W' } else { '-' });
+    rendered.push(if flags.contains(elf::SegmentFlags::X) { 'X' } else { '-' });
+
+    rendered
+}
+
+
+pub fn parse_symbol_type(sym_type: u8) -> String {
+    match sym_type {
+        0x00 => "NOTYPE".to_string(),
+        0x01 => "OBJECT".to_string(),
+        0x02 => "FUNC".to_string(),
+        0x03 => "SECTION".to_string(),
+        0x04 => "FILE".to_string(),
+        0x05 => "COMMON".to_string(),
+        0x06 => "TLS".to_string(),
+        0x0A => "GNU_IFUNC".to_string(),
+
+        // Match unknown symbol type
+        _ => "UNKNOWN".to_string()
+    }
+}
+
+
+pub fn parse_symbol_binding(binding: u8) -> String {
+    match binding {
+        0x00 => "LOCAL".to_string(),
+        0x01 => "GLOBAL".to_string(),
+        0x02 => "WEAK".to_string(),
+        0x0A => "GNU_UNIQUE".to_string(),
+
+        // Match unknown symbol binding
+        _ => "UNKNOWN".to_string()
+    }
+}
+
+
+pub fn parse_symbol_visibility(visibility: u8) -> String {
+    match visibility {
+        0x00 => "DEFAULT".to_string(),
+        0x01 => "INTERNAL".to_string(),
+        0x02 => "HIDDEN".to_string(),
+        0x03 => "PROTECTED".to_string(),
+
+        // Match unknown symbol visibility
+        _ => "UNKNOWN".to_string()
+    }
+}
+
+
+pub fn parse_symbol_section_index(shndx: u16) -> String {
+    match shndx {
+        elf::SHN_UNDEF => "UND".to_string(),
+        elf::SHN_ABS => "ABS".to_string(),
+        elf::SHN_COMMON => "COM".to_string(),
+        _ => shndx.to_string()
+    }
+}
+
+
+pub fn parse_dynamic_tag(tag: u64) -> String {
+    match tag {
+        0x00000000 => "DT_NULL".to_string(),
+        0x00000001 => "DT_NEEDED".to_string(),
+        0x00000002 => "DT_PLTRELSZ".to_string(),
+        0x00000003 => "DT_PLTGOT".to_string(),
+        0x00000004 => "DT_HASH".to_string(),
+        0x00000005 => "DT_STRTAB".to_string(),
+        0x00000006 => "DT_SYMTAB".to_string(),
+        0x00000007 => "DT_RELA".to_string(),
+        0x00000008 => "DT_RELASZ".to_string(),
+        0x00000009 => "DT_RELAENT".to_string(),
+        0x0000000A => "DT_STRSZ".to_string(),
+        0x0000000B => "DT_SYMENT".to_string(),
+        0x0000000C => "DT_INIT".to_string(),
+        0x0000000D => "DT_FINI".to_string(),
+        0x0000000E => "DT_SONAME".to_string(),
+        0x0000000F => "DT_RPATH".to_string(),
+        0x00000010 => "DT_SYMBOLIC".to_string(),
+        0x00000011 => "DT_REL".to_string(),
+        0x00000012 => "DT_RELSZ".to_string(),
+        0x00000013 => "DT_RELENT".to_string(),
+        0x00000014 => "DT_PLTREL".to_string(),
+        0x00000015 => "DT_DEBUG".to_string(),
+        0x00000016 => "DT_TEXTREL".to_string(),
+        0x00000017 => "DT_JMPREL".to_string(),
+        0x00000018 => "DT_BIND_NOW".to_string(),
+        0x00000019 => "DT_INIT_ARRAY".to_string(),
+        0x0000001A => "DT_FINI_ARRAY".to_string(),
+        0x0000001B => "DT_INIT_ARRAYSZ".to_string(),
+        0x0000001C => "DT_FINI_ARRAYSZ".to_string(),
+        0x0000001D => "DT_RUNPATH".to_string(),
+        0x0000001E => "DT_FLAGS".to_string(),
+        0x00000020 => "DT_PREINIT_ARRAY".to_string(),
+        0x00000021 => "DT_PREINIT_ARRAYSZ".to_string(),
+        0x00000022 => "DT_SYMTAB_SHNDX".to_string(),
+        0x00000023 => "DT_RELRSZ".to_string(),
+        0x00000024 => "DT_RELR".to_string(),
+        0x00000025 => "DT_RELRENT".to_string(),
+        0x6FFFFEF5 => "DT_GNU_HASH".to_string(),
+        0x6FFFFFF0 => "DT_VERSYM".to_string(),
+        0x6FFFFFF9 => "DT_RELACOUNT".to_string(),
+        0x6FFFFFFA => "DT_RELCOUNT".to_string(),
+        0x6FFFFFFB => "DT_FLAGS_1".to_string(),
+        0x6FFFFFFC => "DT_VERDEF".to_string(),
+        0x6FFFFFFD => "DT_VERDEFNUM".to_string(),
+        0x6FFFFFFE => "DT_VERNEED".to_string(),
+        0x6FFFFFFF => "DT_VERNEEDNUM".to_string(),
+
+        // Match unknown dynamic tag
         _ => "UNKNOWN".to_string()
     }
 }
 
 
+// Render every set bit of a flag word using the supplied name table,
+// falling back on the hex value for bits without a name
+pub fn parse_flag_names(flags: u64, names: &[(u64, &str)]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut remaining: u64 = flags;
+
+    for (bit, name) in names {
+        if flags & bit != 0 {
+            parts.push(name.to_string());
+            remaining &= !bit;
+        }
+    }
+
+    if remaining != 0 {
+        parts.push(format!("{:#x}", remaining));
+    }
+
+    parts.join(" ")
+}
+
+
+pub fn parse_dynamic_flags(flags: u64) -> String {
+    parse_flag_names(flags, &[
+        (0x00000001, "ORIGIN"),
+        (0x00000002, "SYMBOLIC"),
+        (0x00000004, "TEXTREL"),
+        (0x00000008, "BIND_NOW"),
+        (0x00000010, "STATIC_TLS"),
+    ])
+}
+
+
+pub fn parse_dynamic_flags_1(flags: u64) -> String {
+    parse_flag_names(flags, &[
+        (0x00000001, "NOW"),
+        (0x00000002, "GLOBAL"),
+        (0x00000004, "GROUP"),
+        (0x00000008, "NODELETE"),
+        (0x00000010, "LOADFLTR"),
+        (0x00000020, "INITFIRST"),
+        (0x00000040, "NOOPEN"),
+        (0x00000080, "ORIGIN"),
+        (0x00000100, "DIRECT"),
+        (0x00000400, "INTERPOSE"),
+        (0x00000800, "NODEFLIB"),
+        (0x00001000, "NODUMP"),
+        (0x00002000, "CONFALT"),
+        (0x00004000, "ENDFILTEE"),
+        (0x00008000, "DISPRELDNE"),
+        (0x00010000, "DISPRELPND"),
+        (0x00020000, "NODIRECT"),
+        (0x00040000, "IGNMULDEF"),
+        (0x00080000, "NOKSYMS"),
+        (0x00100000, "NOHDR"),
+        (0x00200000, "EDITED"),
+        (0x00400000, "NORELOC"),
+        (0x00800000, "SYMINTPOSE"),
+        (0x01000000, "GLOBAUDIT"),
+        (0x02000000, "SINGLETON"),
+        (0x04000000, "STUB"),
+        (0x08000000, "PIE"),
+    ])
+}
+
+
+pub fn parse_relocation_type(rel_type: u32, is_x86_64: bool) -> String {
+    if is_x86_64 {
+        match rel_type {
+            0 => "R_X86_64_NONE".to_string(),
+            1 => "R_X86_64_64".to_string(),
+            2 => "R_X86_64_PC32".to_string(),
+            3 => "R_X86_64_GOT32".to_string(),
+            4 => "R_X86_64_PLT32".to_string(),
+            5 => "R_X86_64_COPY".to_string(),
+            6 => "R_X86_64_GLOB_DAT".to_string(),
+            7 => "R_X86_64_JUMP_SLOT".to_string(),
+            8 => "R_X86_64_RELATIVE".to_string(),
+            9 => "R_X86_64_GOTPCREL".to_string(),
+            10 => "R_X86_64_32".to_string(),
+            11 => "R_X86_64_32S".to_string(),
+            12 => "R_X86_64_16".to_string(),
+            13 => "R_X86_64_PC16".to_string(),
+            14 => "R_X86_64_8".to_string(),
+            15 => "R_X86_64_PC8".to_string(),
+            16 => "R_X86_64_DTPMOD64".to_string(),
+            17 => "R_X86_64_DTPOFF64".to_string(),
+            18 => "R_X86_64_TPOFF64".to_string(),
+            19 => "R_X86_64_TLSGD".to_string(),
+            20 => "R_X86_64_TLSLD".to_string(),
+            21 => "R_X86_64_DTPOFF32".to_string(),
+            22 => "R_X86_64_GOTTPOFF".to_string(),
+            23 => "R_X86_64_TPOFF32".to_string(),
+            24 => "R_X86_64_PC64".to_string(),
+            25 => "R_X86_64_GOTOFF64".to_string(),
+            26 => "R_X86_64_GOTPC32".to_string(),
+            27 => "R_X86_64_GOT64".to_string(),
+            28 => "R_X86_64_GOTPCREL64".to_string(),
+            29 => "R_X86_64_GOTPC64".to_string(),
+            30 => "R_X86_64_GOTPLT64".to_string(),
+            31 => "R_X86_64_PLTOFF64".to_string(),
+            32 => "R_X86_64_SIZE32".to_string(),
+            33 => "R_X86_64_SIZE64".to_string(),
+            34 => "R_X86_64_GOTPC32_TLSDESC".to_string(),
+            35 => "R_X86_64_TLSDESC_CALL".to_string(),
+            36 => "R_X86_64_TLSDESC".to_string(),
+            37 => "R_X86_64_IRELATIVE".to_string(),
+            38 => "R_X86_64_RELATIVE64".to_string(),
+            41 => "R_X86_64_GOTPCRELX".to_string(),
+            42 => "R_X86_64_REX_GOTPCRELX".to_string(),
+
+            // Match unknown relocation type
+            _ => "UNKNOWN".to_string()
+        }
+    } else {
+        match rel_type {
+            0 => "R_386_NONE".to_string(),
+            1 => "R_386_32".to_string(),
+            2 => "R_386_PC32".to_string(),
+            3 => "R_386_GOT32".to_string(),
+            4 => "R_386_PLT32".to_string(),
+            5 => "R_386_COPY".to_string(),
+            6 => "R_386_GLOB_DAT".to_string(),
+            7 => "R_386_JMP_SLOT".to_string(),
+            8 => "R_386_RELATIVE".to_string(),
+            9 => "R_386_GOTOFF".to_string(),
+            10 => "R_386_GOTPC".to_string(),
+            11 => "R_386_32PLT".to_string(),
+            14 => "R_386_TLS_TPOFF".to_string(),
+            15 => "R_386_TLS_IE".to_string(),
+            16 => "R_386_TLS_GOTIE".to_string(),
+            17 => "R_386_TLS_LE".to_string(),
+            18 => "R_386_TLS_GD".to_string(),
+            19 => "R_386_TLS_LDM".to_string(),
+            20 => "R_386_16".to_string(),
+            21 => "R_386_PC16".to_string(),
+            22 => "R_386_8".to_string(),
+            23 => "R_386_PC8".to_string(),
+            35 => "R_386_TLS_DTPMOD32".to_string(),
+            36 => "R_386_TLS_DTPOFF32".to_string(),
+            37 => "R_386_TLS_TPOFF32".to_string(),
+            38 => "R_386_SIZE32".to_string(),
+            39 => "R_386_TLS_GOTDESC".to_string(),
+            40 => "R_386_TLS_DESC_CALL".to_string(),
+            41 => "R_386_TLS_DESC".to_string(),
+            42 => "R_386_IRELATIVE".to_string(),
+            43 => "R_386_GOT32X".to_string(),
+
+            // Match unknown relocation type
+            _ => "UNKNOWN".to_string()
+        }
+    }
+}
+
+
+pub fn parse_note_type(name: &str, note_type: u32) -> String {
+    if name != "GNU" {
+        return format!("{:#x}", note_type);
+    }
+
+    match note_type {
+        0x01 => "NT_GNU_ABI_TAG".to_string(),
+        0x02 => "NT_GNU_HWCAP".to_string(),
+        0x03 => "NT_GNU_BUILD_ID".to_string(),
+        0x04 => "NT_GNU_GOLD_VERSION".to_string(),
+        0x05 => "NT_GNU_PROPERTY_TYPE_0".to_string(),
+
+        // Match unknown note type
+        _ => "UNKNOWN".to_string()
+    }
+}
+
+
+pub fn parse_abi_tag_os(os: u32) -> String {
+    match os {
+        0x00 => "Linux".to_string(),
+        0x01 => "Hurd".to_string(),
+        0x02 => "Solaris".to_string(),
+        0x03 => "FreeBSD".to_string(),
+
+        // Match unknown operating system
+        _ => "Unknown".to_string()
+    }
+}
+
+
+pub fn parse_gnu_property(property: &elf::GnuProperty) -> String {
+    match property.pr_type {
+        elf::GNU_PROPERTY_X86_FEATURE_1_AND => format!("x86 feature: {}", parse_flag_names(property.value as u64, &[
+            (0x00000001, "IBT"),
+            (0x00000002, "SHSTK"),
+            (0x00000004, "LAM_U48"),
+            (0x00000008, "LAM_U57"),
+        ])),
+        elf::GNU_PROPERTY_X86_ISA_1_NEEDED | elf::GNU_PROPERTY_X86_ISA_1_USED => {
+            let label: &str = if property.pr_type == elf::GNU_PROPERTY_X86_ISA_1_NEEDED { "needed" } else { "used" };
+            format!("x86 ISA {}: {}", label, parse_flag_names(property.value as u64, &[
+                (0x00000001, "x86-64-baseline"),
+                (0x00000002, "x86-64-v2"),
+                (0x00000004, "x86-64-v3"),
+                (0x00000008, "x86-64-v4"),
+            ]))
+        },
+        elf::GNU_PROPERTY_X86_ISA_1_NEEDED_OLD => format!("x86 ISA needed: {:#x}", property.value),
+        elf::GNU_PROPERTY_X86_ISA_1_USED_OLD => format!("x86 ISA used: {:#x}", property.value),
+        elf::GNU_PROPERTY_X86_FEATURE_2_USED => format!("x86 feature used: {}", parse_flag_names(property.value as u64, &[
+            (0x00000001, "x86"),
+            (0x00000002, "x87"),
+            (0x00000004, "MMX"),
+            (0x00000008, "XMM"),
+            (0x00000010, "YMM"),
+            (0x00000020, "ZMM"),
+            (0x00000040, "FXSR"),
+            (0x00000080, "XSAVE"),
+            (0x00000100, "XSAVEOPT"),
+            (0x00000200, "XSAVEC"),
+            (0x00000400, "TMM"),
+            (0x00000800, "MASK"),
+        ])),
+        0x00000001 => format!("stack size: {:#x}", property.value),
+        0x00000002 => "no copy on protected".to_string(),
+        _ => format!("<type {:#x}, {} bytes>", property.pr_type, property.data.len())
+    }
+}
+
+
 pub fn parse_program_segment_type(segment_type: u32) -> String {
     match segment_type {
         0x00000000 => "PT_NULL".to_string(),
@@ -430,24 +1321,26 @@ pub fn pp_file_header(header: &elf::FileHeader) {
 }
 
 
-pub fn pp_section_header(header: &elf::SectionHeader, number: i32, name: &String) {
-    println!("[{}] {}", number, name);
+pub fn pp_section_header(header: &elf::SectionHeader) {
+    println!("[{}] {}", header.id, header.name);
     println!("\t- Type: {}", parse_section_type(header.section_type));
     println!("\t- Flags: {}", parse_section_flags(header.flags));
-    println!("\t- Flags (Value): {}", header.flags);
-    println!("\t- Address: {:#04x}", header.section_type);
-    println!("\t- Offset: {:#04x}", header.section_type);
+    println!("\t- Flags (Value): {:#x}", header.flags.bits());
+    println!("\t- Address: {:#04x}", header.addr);
+    println!("\t- Offset: {:#04x}", header.offset);
+    println!("\t- Size: {}", header.size);
     println!("\t- Link Index: {}", header.link);
     println!("\t- Info Bytes: {}", header.info);
-    println!("\t- Alignment: {}", header.info);
+    println!("\t- Alignment: {}", header.addralign);
     println!();
 }
 
 
-pub fn pp_program_header(header: &elf::ProgramHeader, number: i32, ph_type: &String) {
-    println!("[{}] {}", number, ph_type);
+pub fn pp_program_header(header: &elf::ProgramHeader) {
+    println!("[{}] {}", header.id, parse_program_segment_type(header.program_type));
     println!("\t- Type: {}", parse_section_type(header.program_type));
-    println!("\t- Flags: {}", header.flags);
+    println!("\t- Flags: {}", parse_segment_flags(header.flags));
+    println!("\t- Flags (Value): {:#x}", header.flags.bits());
     println!("\t- Offset: {:#04x}", header.offset);
     println!("\t- Virtual Address: {:#04x}", header.vaddr);
     println!("\t- Physical Address: {:#04x}", header.paddr);
@@ -458,10 +1351,109 @@ pub fn pp_program_header(header: &elf::ProgramHeader, number: i32, ph_type: &Str
 }
 
 
-pub fn print_help() {
-    print!("Usage: chisel [EXECUTABLE] [-p] [PATCH_FILE]\n\n \
-            Options:\n \
-            \t-p\t\tToggle binary patching mode\n");
+pub fn pp_symbol_table(section: &elf::SectionHeader, symbols: &[elf::Symbol]) {
+    println!("[{}] {} ({} entries)", section.id, section.name, symbols.len());
+    println!("\t{:>5}  {:<16}  {:>6}  {:<9}  {:<6}  {:<9}  {:>5}  Name", "Num", "Value", "Size", "Type", "Bind", "Vis", "Ndx");
+
+    for symbol in symbols {
+        println!(
+            "\t{:>5}  {:016x}  {:>6}  {:<9}  {:<6}  {:<9}  {:>5}  {}",
+            symbol.id,
+            symbol.value,
+            symbol.size,
+            parse_symbol_type(symbol.sym_type),
+            parse_symbol_binding(symbol.binding),
+            parse_symbol_visibility(symbol.visibility),
+            parse_symbol_section_index(symbol.shndx),
+            symbol.name
+        );
+    }
+    println!();
+}
+
+
+pub fn pp_relocation_table(section: &elf::SectionHeader, relocations: &[elf::Relocation], is_x86_64: bool) {
+    println!("[{}] {} ({} entries)", section.id, section.name, relocations.len());
+    println!("\t{:>5}  {:<16}  {:<16}  {:<24}  {:<16}  Symbol + Addend", "Num", "Offset", "Info", "Type", "Sym. Value");
+
+    for relocation in relocations {
+        let (symbol_value, symbol_name): (String, String) = match &relocation.symbol {
+            Some(symbol) => (format!("{:016x}", symbol.value), symbol.name.clone()),
+            None => (String::new(), String::new())
+        };
+
+        let addend: String = match relocation.addend {
+            Some(addend) if addend < 0 => format!(" - {:x}", addend.unsigned_abs()),
+            Some(addend) => format!(" + {:x}", addend),
+            None => String::new()
+        };
+
+        println!(
+            "\t{:>5}  {:016x}  {:016x}  {:<24}  {:<16}  {}{}",
+            relocation.id,
+            relocation.offset,
+            relocation.info,
+            parse_relocation_type(relocation.rel_type, is_x86_64),
+            symbol_value,
+            symbol_name,
+            addend
+        );
+    }
+    println!();
+}
+
+
+pub fn pp_notes(source: &str, notes: &[elf::Note]) {
+    println!("{}", source);
+
+    for note in notes {
+        println!("\t- Owner: {}", note.name);
+        println!("\t- Type: {}", parse_note_type(&note.name, note.note_type));
+        println!("\t- Data Size: {}", note.desc.len());
+
+        match &note.data {
+            elf::NoteData::BuildId(id) => println!("\t- Build ID: {}", id),
+            elf::NoteData::AbiTag { os, major, minor, patch } => {
+                println!("\t- OS: {}, ABI: {}.{}.{}", parse_abi_tag_os(*os), major, minor, patch)
+            },
+            elf::NoteData::GnuProperties(properties) => {
+                for property in properties {
+                    println!("\t- Property: {}", parse_gnu_property(property));
+                }
+            },
+            elf::NoteData::Unknown => {}
+        }
+        println!();
+    }
+}
+
+
+pub fn pp_dynamic_section(entries: &[elf::DynamicEntry]) {
+    println!("\t{:>5}  {:<18}  Value", "Num", "Tag");
+
+    for entry in entries {
+        let value: String = match (entry.tag, &entry.string) {
+            (elf::DT_NEEDED, Some(name)) => format!("Shared library: [{}]", name),
+            (elf::DT_SONAME, Some(name)) => format!("Library soname: [{}]", name),
+            (elf::DT_RPATH, Some(path)) => format!("Library rpath: [{}]", path),
+            (elf::DT_RUNPATH, Some(path)) => format!("Library runpath: [{}]", path),
+            (elf::DT_FLAGS, _) => parse_dynamic_flags(entry.value),
+            (elf::DT_FLAGS_1, _) => format!("Flags: {}", parse_dynamic_flags_1(entry.value)),
+            (elf::DT_PLTREL, _) => parse_dynamic_tag(entry.value).trim_start_matches("DT_").to_string(),
+
+            // Size tags are reported in bytes, everything else as an address or count
+            (
+                elf::DT_PLTRELSZ | elf::DT_RELASZ | elf::DT_RELAENT | elf::DT_STRSZ | elf::DT_SYMENT | elf::DT_RELSZ
+                    | elf::DT_RELENT | elf::DT_INIT_ARRAYSZ | elf::DT_FINI_ARRAYSZ | elf::DT_PREINIT_ARRAYSZ,
+                _
+            ) => format!("{} (bytes)", entry.value),
+            (elf::DT_RELACOUNT | elf::DT_RELCOUNT | elf::DT_VERDEFNUM | elf::DT_VERNEEDNUM, _) => entry.value.to_string(),
+            _ => format!("{:#x}", entry.value)
+        };
+
+        println!("\t{:>5}  {:<18}  {}", entry.id, parse_dynamic_tag(entry.tag), value);
+    }
+    println!();
 }
 
 
@@ -489,4 +1481,63 @@ pub fn print_help() {
 
 // pub fn usize_to_hex(i: usize) -> String {
 //     return format!("{:X}", i).to_string();
-// }
\ No newline at end of file
+// }
+
+#[cfg(test)]
+mod tests {
+    use crate::binary::Elf;
+    use crate::elf::{self, EndianType};
+
+    use super::{
+        addr_from_buffer, parse_section_flags, parse_segment_flags, u16_from_buffer, u32_from_buffer, u32_to_buffer,
+        u64_from_buffer, u64_to_buffer
+    };
+
+
+    #[test]
+    fn reads_both_byte_orders() {
+        let bytes: [u8; 8] = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+
+        assert_eq!(u16_from_buffer(&bytes, 0, EndianType::Little), 0x0201);
+        assert_eq!(u16_from_buffer(&bytes, 0, EndianType::Big), 0x0102);
+        assert_eq!(u32_from_buffer(&bytes, 4, EndianType::Little), 0x08070605);
+        assert_eq!(u32_from_buffer(&bytes, 4, EndianType::Big), 0x05060708);
+        assert_eq!(u64_from_buffer(&bytes, 0, EndianType::Little), 0x0807060504030201);
+        assert_eq!(u64_from_buffer(&bytes, 0, EndianType::Big), 0x0102030405060708);
+
+        // Elf32 addresses are 4 bytes wide
+        assert_eq!(addr_from_buffer(&bytes, 0, false, EndianType::Big), 0x01020304);
+    }
+
+
+    #[test]
+    fn writes_both_byte_orders() {
+        let mut bytes: [u8; 12] = [0; 12];
+
+        u32_to_buffer(&mut bytes, 0, 0x01020304, EndianType::Big);
+        u64_to_buffer(&mut bytes, 4, 0x0102030405060708, EndianType::Little);
+
+        assert_eq!(bytes, [0x01, 0x02, 0x03, 0x04, 0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+    }
+
+
+    #[test]
+    fn renders_section_and_segment_flags() {
+        let binary: Elf = Elf::from_file("testing/hello32").unwrap();
+        let flags = |name: &str| parse_section_flags(binary.section_by_name(name).unwrap().flags);
+
+        assert_eq!(flags(".text"), "AX");
+        assert_eq!(flags(".dynamic"), "WA");
+        assert_eq!(flags(".rodata"), "AMS");
+        assert_eq!(flags(".rel.plt"), "AI");
+
+        // Bits without a letter are kept rather than dropped
+        assert_eq!(parse_section_flags(elf::SectionFlags::ALLOC | elf::SectionFlags(0x1000)), "Ax");
+
+        let segments: Vec<String> = binary.segments()
+            .filter(|segment| segment.program_type == elf::PT_LOAD)
+            .map(|segment| parse_segment_flags(segment.flags))
+            .collect();
+        assert_eq!(segments, vec!["R--", "R-X", "R--", "RW-"]);
+    }
+}
@@ -0,0 +1,218 @@
+// xrefs.rs
+// Created: 10/17/2026
+// Description: Cross reference database. Records the code references made
+//              by calls and jumps and the data references made by memory
+//              operands and immediates that point into the mapped image.
+
+use std::fmt;
+
+use iced_x86::{FlowControl, Instruction, InstructionInfoFactory, Mnemonic, OpAccess, OpKind, Register};
+
+use crate::binary::{CodeRegion, Elf};
+use crate::disasm;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XrefKind {
+    Call,
+    Jump,
+    ConditionalJump,
+    Read,
+    Write,
+    ReadWrite,
+    Address
+}
+
+
+impl fmt::Display for XrefKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            XrefKind::Call => "call",
+            XrefKind::Jump => "jump",
+            XrefKind::ConditionalJump => "conditional jump",
+            XrefKind::Read => "read",
+            XrefKind::Write => "write",
+            XrefKind::ReadWrite => "read/write",
+            XrefKind::Address => "address"
+        })
+    }
+}
+
+
+#[derive(Debug, Clone, Copy)]
+pub struct Xref {
+    pub from: u64,
+    pub to: u64,
+    pub kind: XrefKind
+}
+
+
+// Every reference found in the code, indexed by both source and target
+#[derive(Debug, Default)]
+pub struct XrefDatabase {
+    by_source: Vec<Xref>,
+    by_target: Vec<Xref>
+}
+
+
+impl XrefDatabase {
+    pub fn from_xrefs(xrefs: Vec<Xref>) -> XrefDatabase {
+        let mut by_source: Vec<Xref> = xrefs;
+        by_source.sort_by_key(|xref| (xref.from, xref.to));
+
+        let mut by_target: Vec<Xref> = by_source.clone();
+        by_target.sort_by_key(|xref| (xref.to, xref.from));
+
+        XrefDatabase { by_source, by_target }
+    }
+
+
+    // References made to an address
+    pub fn refs_to(&self, address: u64) -> &[Xref] {
+        let start: usize = self.by_target.partition_point(|xref| xref.to < address);
+        let end: usize = self.by_target.partition_point(|xref| xref.to <= address);
+        &self.by_target[start..end]
+    }
+
+
+    // References made by the instruction at an address
+    pub fn refs_from(&self, address: u64) -> &[Xref] {
+        let start: usize = self.by_source.partition_point(|xref| xref.from < address);
+        let end: usize = self.by_source.partition_point(|xref| xref.from <= address);
+        &self.by_source[start..end]
+    }
+
+
+    pub fn iter(&self) -> impl Iterator<Item = &Xref> {
+        self.by_source.iter()
+    }
+
+
+    pub fn len(&self) -> usize {
+        self.by_source.len()
+    }
+
+
+    pub fn is_empty(&self) -> bool {
+        self.by_source.is_empty()
+    }
+}
+
+
+// Build the cross references of every instruction in the code regions.
+// When a code map from recursive descent is given only the instructions it
+// reached are used, otherwise each region is linearly swept. Addresses are
+// shifted by load_bias, matching the (possibly rebased) regions.
+pub fn build_xrefs(
+    binary: &Elf,
+    regions: &[CodeRegion],
+    code_map: Option<&disasm::CodeMap>,
+    load_bias: u64) -> XrefDatabase {
+
+    let mut xrefs: Vec<Xref> = Vec::new();
+    let mut factory: InstructionInfoFactory = InstructionInfoFactory::new();
+
+    // Position independent code never embeds absolute addresses, and its
+    // image starts at zero, so small constants would be mistaken for pointers
+    let check_immediates: bool = !binary.is_position_independent();
+    let is_mapped = |address: u64| binary.is_mapped(address.wrapping_sub(load_bias));
+
+    match code_map {
+        Some(code_map) => {
+            for instruction in code_map.instructions() {
+                instruction_xrefs(instruction, &mut factory, check_immediates, &is_mapped, &mut xrefs);
+            }
+        },
+        None => {
+            for region in regions {
+                for instruction in disasm::LinearSweep::new(region.data, region.vaddr, binary.bitness()) {
+                    instruction_xrefs(&instruction, &mut factory, check_immediates, &is_mapped, &mut xrefs);
+                }
+            }
+        }
+    }
+
+    XrefDatabase::from_xrefs(xrefs)
+}
+
+
+// Collect the references made by a single instruction
+fn instruction_xrefs(
+    instruction: &Instruction,
+    factory: &mut InstructionInfoFactory,
+    check_immediates: bool,
+    is_mapped: &dyn Fn(u64) -> bool,
+    xrefs: &mut Vec<Xref>) {
+
+    if instruction.is_invalid() {
+        return;
+    }
+
+    let from: u64 = instruction.ip();
+
+    // Code references from direct branches
+    let branch_kind: Option<XrefKind> = match instruction.flow_control() {
+        FlowControl::Call => Some(XrefKind::Call),
+        FlowControl::UnconditionalBranch => Some(XrefKind::Jump),
+        FlowControl::ConditionalBranch => Some(XrefKind::ConditionalJump),
+        _ => None
+    };
+
+    if let (Some(kind), Some(to)) = (branch_kind, disasm::branch_target(instruction)) {
+        xrefs.push(Xref { from, to, kind });
+        return;
+    }
+
+    // Data references from RIP-relative and absolute memory operands
+    for memory in factory.info(instruction).used_memory() {
+        let to: u64 = if memory.base() == Register::RIP || memory.base() == Register::EIP {
+            instruction.ip_rel_memory_address()
+        } else if memory.base() == Register::None && memory.index() == Register::None && memory.segment() == Register::DS {
+            memory.displacement()
+        } else {
+            continue;
+        };
+
+        let kind: XrefKind = match memory.access() {
+            OpAccess::Read | OpAccess::CondRead => XrefKind::Read,
+            OpAccess::Write | OpAccess::CondWrite => XrefKind::Write,
+            OpAccess::ReadWrite | OpAccess::ReadCondWrite => XrefKind::ReadWrite,
+            _ => XrefKind::Address
+        };
+
+        if is_mapped(to) {
+            xrefs.push(Xref { from, to, kind });
+        }
+    }
+
+    // lea only computes an address, so it does not appear as used memory
+    if instruction.mnemonic() == Mnemonic::Lea {
+        let to: Option<u64> = if instruction.is_ip_rel_memory_operand() {
+            Some(instruction.ip_rel_memory_address())
+        } else if instruction.memory_base() == Register::None && instruction.memory_index() == Register::None {
+            Some(instruction.memory_displacement64())
+        } else {
+            None
+        };
+
+        if let Some(to) = to.filter(|to| is_mapped(*to)) {
+            xrefs.push(Xref { from, to, kind: XrefKind::Address });
+        }
+    }
+
+    // Immediates that hold the address of something in the image
+    if check_immediates {
+        for operand in 0..instruction.op_count() {
+            let to: u64 = match instruction.op_kind(operand) {
+                OpKind::Immediate32 => instruction.immediate32() as u64,
+                OpKind::Immediate32to64 => instruction.immediate32to64() as u64,
+                OpKind::Immediate64 => instruction.immediate64(),
+                _ => continue
+            };
+
+            if is_mapped(to) {
+                xrefs.push(Xref { from, to, kind: XrefKind::Address });
+            }
+        }
+    }
+}
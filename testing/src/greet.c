@@ -0,0 +1,5 @@
+// Shared library hello32 links against, standing in for libc:
+//   gcc -m32 -O1 -fPIC -shared -nostdlib greet.c -o libgreet.so
+int greet(const char *name) {
+   return name[0];
+}
@@ -0,0 +1,22 @@
+// i386 sample built without a C library, with IBT PLT entries:
+//   gcc -m32 -O1 -fPIE -pie -nostdlib -fcf-protection -Wl,-z,ibtplt \
+//       -Wl,--build-id hello32.c -L. -lgreet -o hello32
+extern int greet(const char *name);
+
+const char *names[] = { "chisel", "i386" };
+
+static void sys_exit(int status) {
+   __asm__ volatile ("int $0x80" : : "a"(1), "b"(status));
+}
+
+int main(void) {
+   int total = 0;
+   for (int i = 0; i < 2; i++) {
+      total += greet(names[i]);
+   }
+   return total;
+}
+
+void _start(void) {
+   sys_exit(main());
+}